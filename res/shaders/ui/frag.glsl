@@ -10,8 +10,13 @@ uniform vec2 bottomLeft;
 uniform vec2 size;
 uniform bool enableCorner;
 uniform float cornerRadius;
+//0 for a rectangle, 1 for an ellipse, see ui::shape::Shape.
+uniform int shape;
 
 uniform vec4 color;
+//Drawn inside the shape, none for a width of 0.
+uniform float borderWidth;
+uniform vec4 borderColor;
 uniform FillStrategy fillStrategy;
 
 in vec2 currentPos;
@@ -44,10 +49,42 @@ bool perform_strategy() {
     return false;
 }
 
+//Mirrors Shape::contains, so hit-testing matches what is drawn.
+bool outside_shape() {
+    if (shape == 1) {
+        vec2 centered = currentPos * 2 - 1;
+        return dot(centered, centered) > 1;
+    }
+    if (!enableCorner)
+    return false;
+    vec2 local = currentPos * size;
+    float radius = min(cornerRadius, min(size.x, size.y) / 2);
+    vec2 corner = local - clamp(local, vec2(radius), size - vec2(radius));
+    return dot(corner, corner) > radius * radius;
+}
+
+//Whether the fragment is within borderWidth of the outline, i.e. outside the shape shrunk by the border.
+bool in_border() {
+    if (borderWidth <= 0)
+    return false;
+    vec2 local = currentPos * size;
+    vec2 inner_min = vec2(borderWidth);
+    vec2 inner_max = size - vec2(borderWidth);
+    if (any(lessThan(local, inner_min)) || any(greaterThan(local, inner_max)))
+    return true;
+    if (shape == 1) {
+        vec2 centered = (local - size / 2) / (size / 2 - vec2(borderWidth));
+        return dot(centered, centered) > 1;
+    }
+    float radius = enableCorner ? max(min(cornerRadius, min(size.x, size.y) / 2) - borderWidth, 0) : 0;
+    vec2 corner = local - clamp(local, inner_min + vec2(radius), inner_max - vec2(radius));
+    return dot(corner, corner) > radius * radius;
+}
+
 void main()
 {
-    if (perform_strategy())
+    if (perform_strategy() || outside_shape())
     discard;
     //FragColor = vec4(fillStrategy.fillMethod, 0, 0, 1);
-    FragColor = color;
+    FragColor = in_border() ? borderColor : color;
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+#version 330 core
+
+uniform sampler2D atlas;
+uniform vec4 color;
+
+in vec2 uv;
+
+out vec4 FragColor;
+
+void main()
+{
+    //0.5 lies on the outline. Smoothing over one screen pixel keeps edges crisp at any scale.
+    float distance = texture(atlas, uv).r;
+    float width = fwidth(distance);
+    float alpha = smoothstep(0.5 - width, 0.5 + width, distance);
+    FragColor = vec4(color.rgb, color.a * alpha);
+}
@@ -0,0 +1,15 @@
+#version 330 core
+
+uniform sampler2D atlas;
+uniform vec4 color;
+
+in vec2 uv;
+
+out vec4 FragColor;
+
+void main()
+{
+    //The atlas only stores coverage.
+    float coverage = texture(atlas, uv).r;
+    FragColor = vec4(color.rgb, color.a * coverage);
+}
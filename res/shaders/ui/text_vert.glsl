@@ -0,0 +1,14 @@
+#version 330 core
+
+uniform mat4 projection;
+uniform vec2 origin;
+
+out vec2 uv;
+
+layout (location = 0) in vec2 aPos;
+layout (location = 1) in vec2 aUV;
+
+void main() {
+    gl_Position = projection * vec4(origin + aPos, 0, 1);
+    uv = aUV;
+}
@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::ffi::c_void;
+use crate::gfx::backend::native::NativeBackend;
+
+pub mod native;
+pub mod recording;
+
+thread_local! {
+    ///GL contexts are bound to a thread, so every thread gets its own backend. Defaults to the real `gl` implementation.
+    static BACKEND: RefCell<Box<dyn GlBackend>> = RefCell::new(Box::new(NativeBackend));
+}
+
+///Installs a new backend for the current thread and returns the previously installed one.
+pub fn set_backend<B: GlBackend + 'static>(backend: B) -> Box<dyn GlBackend> {
+    BACKEND.with(|current| current.replace(Box::new(backend)))
+}
+
+///Restores a backend returned by [`set_backend`].
+pub fn restore_backend(backend: Box<dyn GlBackend>) {
+    BACKEND.with(|current| {
+        current.replace(backend);
+    });
+}
+
+///Runs `f` against the backend installed on the current thread.
+pub fn with_backend<R, F>(f: F) -> R where F: FnOnce(&mut dyn GlBackend) -> R {
+    BACKEND.with(|backend| f(backend.borrow_mut().as_mut()))
+}
+
+///Every GL call made by `gfx::bindings` goes through this trait.
+///Arguments are the raw GL enums and names, so implementations do not need to know about the wrapper types.
+pub trait GlBackend {
+    fn name(&self) -> &'static str;
+
+    //Programs
+    fn create_program(&mut self) -> u32;
+    fn use_program(&mut self, program: u32);
+    fn attach_shader(&mut self, program: u32, shader: u32);
+    fn link_program(&mut self, program: u32);
+    fn delete_program(&mut self, program: u32);
+    fn get_program_iv(&mut self, program: u32, pname: u32) -> i32;
+    fn get_program_info_log(&mut self, program: u32, max_length: i32) -> String;
+
+    //Shaders
+    fn create_shader(&mut self, shader_type: u32) -> u32;
+    fn shader_source(&mut self, shader: u32, source: &str);
+    fn compile_shader(&mut self, shader: u32);
+    fn get_shader_iv(&mut self, shader: u32, pname: u32) -> i32;
+    fn get_shader_info_log(&mut self, shader: u32, max_length: i32) -> String;
+    fn delete_shader(&mut self, shader: u32);
+
+    //Buffers
+    fn gen_buffers(&mut self, count: i32) -> u32;
+    fn bind_buffer(&mut self, target: u32, buffer: u32);
+    fn buffer_data(&mut self, target: u32, size: isize, data: *const c_void, usage: u32);
+    fn delete_buffers(&mut self, buffer: u32);
+    fn gen_vertex_arrays(&mut self, count: i32) -> u32;
+    fn bind_vertex_array(&mut self, vao: u32);
+    fn delete_vertex_arrays(&mut self, vao: u32);
+
+    //Graphics
+    fn clear(&mut self, mask: u32);
+    fn clear_color(&mut self, color: [f32; 4]);
+    fn draw_arrays(&mut self, mode: u32, first: i32, count: i32);
+    fn draw_elements(&mut self, mode: u32, count: i32, index_type: u32, indices: *const c_void);
+    fn viewport(&mut self, x: i32, y: i32, width: i32, height: i32);
+    fn scissor(&mut self, x: i32, y: i32, width: i32, height: i32);
+    fn enable(&mut self, capability: u32);
+    fn disable(&mut self, capability: u32);
+    fn cull_face(&mut self, face: u32);
+    fn front_face(&mut self, ordering: u32);
+    fn depth_mask(&mut self, flag: bool);
+    fn depth_func(&mut self, func: u32);
+    fn polygon_mode(&mut self, face: u32, mode: u32);
+    ///Queries integer state such as `GL_VIEWPORT`. Unused trailing values are 0.
+    fn get_integer_v(&mut self, pname: u32) -> [i32; 4];
+
+    //Textures
+    fn gen_textures(&mut self, count: i32) -> u32;
+    fn delete_textures(&mut self, texture: u32);
+    fn active_texture(&mut self, unit: u32);
+    fn bind_texture(&mut self, target: u32, texture: u32);
+    fn tex_image_2d(&mut self, target: u32, level: i32, internal_format: i32, width: i32, height: i32, format: u32, pixel_type: u32, data: *const c_void);
+    fn tex_parameter_i(&mut self, target: u32, pname: u32, param: i32);
+    fn generate_mipmap(&mut self, target: u32);
+    fn pixel_store_i(&mut self, pname: u32, param: i32);
+
+    //Framebuffers
+    fn gen_framebuffers(&mut self, count: i32) -> u32;
+    fn delete_framebuffers(&mut self, framebuffer: u32);
+    fn bind_framebuffer(&mut self, target: u32, framebuffer: u32);
+    fn framebuffer_texture_2d(&mut self, target: u32, attachment: u32, texture_target: u32, texture: u32, level: i32);
+    fn framebuffer_renderbuffer(&mut self, target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: u32);
+    fn check_framebuffer_status(&mut self, target: u32) -> u32;
+    fn draw_buffers(&mut self, attachments: &[u32]);
+    fn read_buffer(&mut self, attachment: u32);
+    fn read_pixels(&mut self, x: i32, y: i32, width: i32, height: i32, format: u32, pixel_type: u32, buffer: &mut [u8]);
+    fn blit_framebuffer(&mut self, source: [i32; 4], destination: [i32; 4], mask: u32, filter: u32);
+    fn gen_renderbuffers(&mut self, count: i32) -> u32;
+    fn delete_renderbuffers(&mut self, renderbuffer: u32);
+    fn bind_renderbuffer(&mut self, target: u32, renderbuffer: u32);
+    fn renderbuffer_storage(&mut self, target: u32, internal_format: u32, width: i32, height: i32);
+
+    //Attrib pointers
+    fn vertex_attrib_pointer(&mut self, index: u32, size: i32, data_type: u32, normalized: bool, stride: i32, offset: *const c_void);
+    fn enable_vertex_attrib_array(&mut self, index: u32);
+    fn disable_vertex_attrib_array(&mut self, index: u32);
+
+    //Uniforms
+    fn get_uniform_location(&mut self, program: u32, name: &str) -> i32;
+    fn uniform_1ui(&mut self, location: i32, value: u32);
+    fn uniform_1i(&mut self, location: i32, value: i32);
+    fn uniform_1f(&mut self, location: i32, value: f32);
+    fn uniform_1fv(&mut self, location: i32, count: i32, value: &[f32]);
+    fn uniform_2fv(&mut self, location: i32, count: i32, value: &[f32]);
+    fn uniform_4fv(&mut self, location: i32, count: i32, value: &[f32]);
+    fn uniform_matrix4fv(&mut self, location: i32, count: i32, transpose: bool, value: &[f32]);
+}
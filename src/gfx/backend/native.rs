@@ -0,0 +1,469 @@
+use std::ffi::{c_void, CString};
+use std::ptr::{null, null_mut};
+use crate::gfx::backend::GlBackend;
+
+///Forwards every call to the `gl` crate. Requires a current GL context with loaded function pointers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativeBackend;
+
+fn gl_bool(value: bool) -> gl::types::GLboolean {
+    match value {
+        true => gl::TRUE,
+        false => gl::FALSE
+    }
+}
+
+fn create_whitespace_cstring_with_len(len: usize) -> CString {
+    // allocate buffer of correct size
+    let mut buffer: Vec<u8> = Vec::with_capacity(len + 1);
+    // fill it with len spaces
+    buffer.extend([b' '].iter().cycle().take(len));
+    // convert buffer to CString
+    unsafe { CString::from_vec_unchecked(buffer) }
+}
+
+impl GlBackend for NativeBackend {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn create_program(&mut self) -> u32 {
+        unsafe {
+            gl::CreateProgram()
+        }
+    }
+
+    fn use_program(&mut self, program: u32) {
+        unsafe {
+            gl::UseProgram(program);
+        }
+    }
+
+    fn attach_shader(&mut self, program: u32, shader: u32) {
+        unsafe {
+            gl::AttachShader(program, shader);
+        }
+    }
+
+    fn link_program(&mut self, program: u32) {
+        unsafe {
+            gl::LinkProgram(program);
+        }
+    }
+
+    fn delete_program(&mut self, program: u32) {
+        unsafe {
+            gl::DeleteProgram(program);
+        }
+    }
+
+    fn get_program_iv(&mut self, program: u32, pname: u32) -> i32 {
+        unsafe {
+            let mut value: i32 = 0;
+            gl::GetProgramiv(program, pname, &mut value);
+            value
+        }
+    }
+
+    fn get_program_info_log(&mut self, program: u32, max_length: i32) -> String {
+        unsafe {
+            let buffer = create_whitespace_cstring_with_len(max_length.max(0) as usize);
+
+            gl::GetProgramInfoLog(
+                program,
+                max_length,
+                null_mut(),
+                buffer.as_ptr() as *mut gl::types::GLchar,
+            );
+            buffer.to_string_lossy().to_string()
+        }
+    }
+
+    fn create_shader(&mut self, shader_type: u32) -> u32 {
+        unsafe {
+            gl::CreateShader(shader_type)
+        }
+    }
+
+    fn shader_source(&mut self, shader: u32, source: &str) {
+        unsafe {
+            let source = CString::new(source).unwrap();
+            gl::ShaderSource(shader, 1, &source.as_ptr(), null());
+        }
+    }
+
+    fn compile_shader(&mut self, shader: u32) {
+        unsafe {
+            gl::CompileShader(shader);
+        }
+    }
+
+    fn get_shader_iv(&mut self, shader: u32, pname: u32) -> i32 {
+        unsafe {
+            let mut value: i32 = 0;
+            gl::GetShaderiv(shader, pname, &mut value);
+            value
+        }
+    }
+
+    fn get_shader_info_log(&mut self, shader: u32, max_length: i32) -> String {
+        unsafe {
+            let buffer = create_whitespace_cstring_with_len(max_length.max(0) as usize);
+
+            gl::GetShaderInfoLog(
+                shader,
+                max_length,
+                null_mut(),
+                buffer.as_ptr() as *mut gl::types::GLchar,
+            );
+            buffer.to_string_lossy().to_string()
+        }
+    }
+
+    fn delete_shader(&mut self, shader: u32) {
+        unsafe {
+            gl::DeleteShader(shader);
+        }
+    }
+
+    fn gen_buffers(&mut self, count: i32) -> u32 {
+        unsafe {
+            let mut id: u32 = 0;
+            gl::GenBuffers(count, &mut id);
+            id
+        }
+    }
+
+    fn bind_buffer(&mut self, target: u32, buffer: u32) {
+        unsafe {
+            gl::BindBuffer(target, buffer);
+        }
+    }
+
+    fn buffer_data(&mut self, target: u32, size: isize, data: *const c_void, usage: u32) {
+        unsafe {
+            gl::BufferData(target, size, data, usage);
+        }
+    }
+
+    fn delete_buffers(&mut self, buffer: u32) {
+        unsafe {
+            gl::DeleteBuffers(1, &buffer);
+        }
+    }
+
+    fn gen_vertex_arrays(&mut self, count: i32) -> u32 {
+        unsafe {
+            let mut vao: u32 = 0;
+            gl::GenVertexArrays(count, &mut vao);
+            vao
+        }
+    }
+
+    fn bind_vertex_array(&mut self, vao: u32) {
+        unsafe {
+            gl::BindVertexArray(vao);
+        }
+    }
+
+    fn delete_vertex_arrays(&mut self, vao: u32) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &vao);
+        }
+    }
+
+    fn clear(&mut self, mask: u32) {
+        unsafe {
+            gl::Clear(mask)
+        }
+    }
+
+    fn clear_color(&mut self, color: [f32; 4]) {
+        unsafe {
+            gl::ClearColor(color[0], color[1], color[2], color[3]);
+        }
+    }
+
+    fn draw_arrays(&mut self, mode: u32, first: i32, count: i32) {
+        unsafe {
+            gl::DrawArrays(mode, first, count);
+        }
+    }
+
+    fn draw_elements(&mut self, mode: u32, count: i32, index_type: u32, indices: *const c_void) {
+        unsafe {
+            gl::DrawElements(mode, count, index_type, indices);
+        }
+    }
+
+    fn viewport(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe {
+            gl::Viewport(x, y, width, height)
+        }
+    }
+
+    fn scissor(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe {
+            gl::Scissor(x, y, width, height)
+        }
+    }
+
+    fn enable(&mut self, capability: u32) {
+        unsafe {
+            gl::Enable(capability);
+        }
+    }
+
+    fn disable(&mut self, capability: u32) {
+        unsafe {
+            gl::Disable(capability);
+        }
+    }
+
+    fn cull_face(&mut self, face: u32) {
+        unsafe {
+            gl::CullFace(face);
+        }
+    }
+
+    fn front_face(&mut self, ordering: u32) {
+        unsafe {
+            gl::FrontFace(ordering);
+        }
+    }
+
+    fn depth_mask(&mut self, flag: bool) {
+        unsafe {
+            gl::DepthMask(gl_bool(flag))
+        }
+    }
+
+    fn depth_func(&mut self, func: u32) {
+        unsafe {
+            gl::DepthFunc(func);
+        }
+    }
+
+    fn polygon_mode(&mut self, face: u32, mode: u32) {
+        unsafe {
+            gl::PolygonMode(face, mode);
+        }
+    }
+
+    fn get_integer_v(&mut self, pname: u32) -> [i32; 4] {
+        unsafe {
+            let mut values = [0i32; 4];
+            gl::GetIntegerv(pname, values.as_mut_ptr());
+            values
+        }
+    }
+
+    fn gen_framebuffers(&mut self, count: i32) -> u32 {
+        unsafe {
+            let mut id: u32 = 0;
+            gl::GenFramebuffers(count, &mut id);
+            id
+        }
+    }
+
+    fn delete_framebuffers(&mut self, framebuffer: u32) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &framebuffer);
+        }
+    }
+
+    fn bind_framebuffer(&mut self, target: u32, framebuffer: u32) {
+        unsafe {
+            gl::BindFramebuffer(target, framebuffer);
+        }
+    }
+
+    fn framebuffer_texture_2d(&mut self, target: u32, attachment: u32, texture_target: u32, texture: u32, level: i32) {
+        unsafe {
+            gl::FramebufferTexture2D(target, attachment, texture_target, texture, level);
+        }
+    }
+
+    fn framebuffer_renderbuffer(&mut self, target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: u32) {
+        unsafe {
+            gl::FramebufferRenderbuffer(target, attachment, renderbuffer_target, renderbuffer);
+        }
+    }
+
+    fn check_framebuffer_status(&mut self, target: u32) -> u32 {
+        unsafe {
+            gl::CheckFramebufferStatus(target)
+        }
+    }
+
+    fn draw_buffers(&mut self, attachments: &[u32]) {
+        unsafe {
+            gl::DrawBuffers(attachments.len() as i32, attachments.as_ptr());
+        }
+    }
+
+    fn read_buffer(&mut self, attachment: u32) {
+        unsafe {
+            gl::ReadBuffer(attachment);
+        }
+    }
+
+    fn read_pixels(&mut self, x: i32, y: i32, width: i32, height: i32, format: u32, pixel_type: u32, buffer: &mut [u8]) {
+        unsafe {
+            gl::ReadPixels(x, y, width, height, format, pixel_type, buffer.as_mut_ptr() as *mut c_void);
+        }
+    }
+
+    fn blit_framebuffer(&mut self, source: [i32; 4], destination: [i32; 4], mask: u32, filter: u32) {
+        unsafe {
+            gl::BlitFramebuffer(
+                source[0], source[1], source[2], source[3],
+                destination[0], destination[1], destination[2], destination[3],
+                mask, filter,
+            );
+        }
+    }
+
+    fn gen_renderbuffers(&mut self, count: i32) -> u32 {
+        unsafe {
+            let mut id: u32 = 0;
+            gl::GenRenderbuffers(count, &mut id);
+            id
+        }
+    }
+
+    fn delete_renderbuffers(&mut self, renderbuffer: u32) {
+        unsafe {
+            gl::DeleteRenderbuffers(1, &renderbuffer);
+        }
+    }
+
+    fn bind_renderbuffer(&mut self, target: u32, renderbuffer: u32) {
+        unsafe {
+            gl::BindRenderbuffer(target, renderbuffer);
+        }
+    }
+
+    fn renderbuffer_storage(&mut self, target: u32, internal_format: u32, width: i32, height: i32) {
+        unsafe {
+            gl::RenderbufferStorage(target, internal_format, width, height);
+        }
+    }
+
+    fn gen_textures(&mut self, count: i32) -> u32 {
+        unsafe {
+            let mut id: u32 = 0;
+            gl::GenTextures(count, &mut id);
+            id
+        }
+    }
+
+    fn delete_textures(&mut self, texture: u32) {
+        unsafe {
+            gl::DeleteTextures(1, &texture);
+        }
+    }
+
+    fn active_texture(&mut self, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(unit);
+        }
+    }
+
+    fn bind_texture(&mut self, target: u32, texture: u32) {
+        unsafe {
+            gl::BindTexture(target, texture);
+        }
+    }
+
+    fn tex_image_2d(&mut self, target: u32, level: i32, internal_format: i32, width: i32, height: i32, format: u32, pixel_type: u32, data: *const c_void) {
+        unsafe {
+            gl::TexImage2D(target, level, internal_format, width, height, 0, format, pixel_type, data);
+        }
+    }
+
+    fn tex_parameter_i(&mut self, target: u32, pname: u32, param: i32) {
+        unsafe {
+            gl::TexParameteri(target, pname, param);
+        }
+    }
+
+    fn generate_mipmap(&mut self, target: u32) {
+        unsafe {
+            gl::GenerateMipmap(target);
+        }
+    }
+
+    fn pixel_store_i(&mut self, pname: u32, param: i32) {
+        unsafe {
+            gl::PixelStorei(pname, param);
+        }
+    }
+
+    fn vertex_attrib_pointer(&mut self, index: u32, size: i32, data_type: u32, normalized: bool, stride: i32, offset: *const c_void) {
+        unsafe {
+            gl::VertexAttribPointer(index, size, data_type, gl_bool(normalized), stride, offset);
+        }
+    }
+
+    fn enable_vertex_attrib_array(&mut self, index: u32) {
+        unsafe {
+            gl::EnableVertexAttribArray(index);
+        }
+    }
+
+    fn disable_vertex_attrib_array(&mut self, index: u32) {
+        unsafe {
+            gl::DisableVertexAttribArray(index);
+        }
+    }
+
+    fn get_uniform_location(&mut self, program: u32, name: &str) -> i32 {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            gl::GetUniformLocation(program, name.as_ptr())
+        }
+    }
+
+    fn uniform_1ui(&mut self, location: i32, value: u32) {
+        unsafe {
+            gl::Uniform1ui(location, value);
+        }
+    }
+
+    fn uniform_1i(&mut self, location: i32, value: i32) {
+        unsafe {
+            gl::Uniform1i(location, value);
+        }
+    }
+
+    fn uniform_1f(&mut self, location: i32, value: f32) {
+        unsafe {
+            gl::Uniform1f(location, value);
+        }
+    }
+
+    fn uniform_1fv(&mut self, location: i32, count: i32, value: &[f32]) {
+        unsafe {
+            gl::Uniform1fv(location, count, value.as_ptr());
+        }
+    }
+
+    fn uniform_2fv(&mut self, location: i32, count: i32, value: &[f32]) {
+        unsafe {
+            gl::Uniform2fv(location, count, value.as_ptr());
+        }
+    }
+
+    fn uniform_4fv(&mut self, location: i32, count: i32, value: &[f32]) {
+        unsafe {
+            gl::Uniform4fv(location, count, value.as_ptr());
+        }
+    }
+
+    fn uniform_matrix4fv(&mut self, location: i32, count: i32, transpose: bool, value: &[f32]) {
+        unsafe {
+            gl::UniformMatrix4fv(location, count, gl_bool(transpose), value.as_ptr())
+        }
+    }
+}
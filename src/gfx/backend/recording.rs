@@ -0,0 +1,517 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::rc::Rc;
+use crate::gfx::backend::GlBackend;
+
+///A single call made against a [`RecordingBackend`], with the same arguments the real GL function would receive.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GlCall {
+    CreateProgram(u32),
+    UseProgram(u32),
+    AttachShader(u32, u32),
+    LinkProgram(u32),
+    DeleteProgram(u32),
+    GetProgramIv(u32, u32),
+    GetProgramInfoLog(u32),
+
+    CreateShader(u32, u32),
+    ShaderSource(u32, String),
+    CompileShader(u32),
+    GetShaderIv(u32, u32),
+    GetShaderInfoLog(u32),
+    DeleteShader(u32),
+
+    GenBuffers(i32, u32),
+    BindBuffer(u32, u32),
+    ///target, size in bytes, usage. The data itself is not copied.
+    BufferData(u32, isize, u32),
+    DeleteBuffers(u32),
+    GenVertexArrays(i32, u32),
+    BindVertexArray(u32),
+    DeleteVertexArrays(u32),
+
+    Clear(u32),
+    ClearColor([f32; 4]),
+    DrawArrays(u32, i32, i32),
+    ///mode, count, index type, byte offset into the element array buffer
+    DrawElements(u32, i32, u32, isize),
+    Viewport(i32, i32, i32, i32),
+    Scissor(i32, i32, i32, i32),
+    Enable(u32),
+    Disable(u32),
+    CullFace(u32),
+    FrontFace(u32),
+    DepthMask(bool),
+    DepthFunc(u32),
+    PolygonMode(u32, u32),
+
+    GetIntegerV(u32),
+
+    GenFramebuffers(i32, u32),
+    DeleteFramebuffers(u32),
+    BindFramebuffer(u32, u32),
+    ///target, attachment, texture target, texture, level
+    FramebufferTexture2D(u32, u32, u32, u32, i32),
+    FramebufferRenderbuffer(u32, u32, u32, u32),
+    CheckFramebufferStatus(u32),
+    DrawBuffers(Vec<u32>),
+    ReadBuffer(u32),
+    ///x, y, width, height, format, pixel type
+    ReadPixels(i32, i32, i32, i32, u32, u32),
+    ///source rect, destination rect, mask, filter
+    BlitFramebuffer([i32; 4], [i32; 4], u32, u32),
+    GenRenderbuffers(i32, u32),
+    DeleteRenderbuffers(u32),
+    BindRenderbuffer(u32, u32),
+    RenderbufferStorage(u32, u32, i32, i32),
+
+    GenTextures(i32, u32),
+    DeleteTextures(u32),
+    ActiveTexture(u32),
+    BindTexture(u32, u32),
+    ///target, level, internal format, width, height, format, pixel type. The pixels are not copied.
+    TexImage2D(u32, i32, i32, i32, i32, u32, u32),
+    TexParameterI(u32, u32, i32),
+    GenerateMipmap(u32),
+    PixelStoreI(u32, i32),
+
+    ///index, size, data type, normalized, stride, offset
+    VertexAttribPointer(u32, i32, u32, bool, i32, isize),
+    EnableVertexAttribArray(u32),
+    DisableVertexAttribArray(u32),
+
+    GetUniformLocation(u32, String),
+    Uniform1ui(i32, u32),
+    Uniform1i(i32, i32),
+    Uniform1f(i32, f32),
+    ///location, count, values
+    Uniform1fv(i32, i32, Vec<f32>),
+    Uniform2fv(i32, i32, Vec<f32>),
+    Uniform4fv(i32, i32, Vec<f32>),
+    ///location, count, transpose, values
+    UniformMatrix4fv(i32, i32, bool, Vec<f32>),
+}
+
+///Shared view of the calls a [`RecordingBackend`] received. Stays valid after the backend is installed.
+#[derive(Debug, Clone, Default)]
+pub struct CallLog(Rc<RefCell<Vec<GlCall>>>);
+
+impl CallLog {
+    pub fn calls(&self) -> Vec<GlCall> {
+        self.0.borrow().clone()
+    }
+    pub fn take(&self) -> Vec<GlCall> {
+        self.0.borrow_mut().drain(..).collect()
+    }
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+    pub fn push(&self, call: GlCall) {
+        self.0.borrow_mut().push(call);
+    }
+}
+
+///Backend that needs no GL context. Every call is logged, object names are handed out from a counter and
+///compile/link statuses are synthetic, so the command stream of the wrappers can be asserted on.
+#[derive(Debug)]
+pub struct RecordingBackend {
+    log: CallLog,
+    next_id: u32,
+    compile_status: bool,
+    link_status: bool,
+    info_log: String,
+    framebuffer_status: u32,
+    uniform_locations: HashMap<(u32, String), i32>,
+    viewport: [i32; 4],
+    scissor: [i32; 4],
+    draw_framebuffer: u32,
+    read_framebuffer: u32,
+}
+
+impl RecordingBackend {
+    pub fn new() -> RecordingBackend {
+        Self {
+            log: CallLog::default(),
+            next_id: 1,
+            compile_status: true,
+            link_status: true,
+            info_log: String::new(),
+            framebuffer_status: gl::FRAMEBUFFER_COMPLETE,
+            uniform_locations: Default::default(),
+            viewport: [0; 4],
+            scissor: [0; 4],
+            draw_framebuffer: 0,
+            read_framebuffer: 0,
+        }
+    }
+
+    ///Makes every shader report `COMPILE_STATUS` as `status`.
+    pub fn with_compile_status(mut self, status: bool) -> RecordingBackend {
+        self.compile_status = status;
+        self
+    }
+
+    ///Makes every program report `LINK_STATUS` as `status`.
+    pub fn with_link_status(mut self, status: bool) -> RecordingBackend {
+        self.link_status = status;
+        self
+    }
+
+    ///The log returned by both `GetShaderInfoLog` and `GetProgramInfoLog`.
+    pub fn with_info_log<T: ToString>(mut self, info_log: T) -> RecordingBackend {
+        self.info_log = info_log.to_string();
+        self
+    }
+
+    ///The status every `CheckFramebufferStatus` returns, `GL_FRAMEBUFFER_COMPLETE` by default.
+    pub fn with_framebuffer_status(mut self, status: u32) -> RecordingBackend {
+        self.framebuffer_status = status;
+        self
+    }
+
+    pub fn log(&self) -> CallLog {
+        self.log.clone()
+    }
+
+    fn next_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn iv(&self, pname: u32) -> i32 {
+        match pname {
+            gl::COMPILE_STATUS => self.compile_status as i32,
+            gl::LINK_STATUS => self.link_status as i32,
+            gl::INFO_LOG_LENGTH => self.info_log.len() as i32,
+            _ => 0
+        }
+    }
+}
+
+impl Default for RecordingBackend {
+    fn default() -> Self {
+        RecordingBackend::new()
+    }
+}
+
+impl GlBackend for RecordingBackend {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    fn create_program(&mut self) -> u32 {
+        let id = self.next_id();
+        self.log.push(GlCall::CreateProgram(id));
+        id
+    }
+
+    fn use_program(&mut self, program: u32) {
+        self.log.push(GlCall::UseProgram(program));
+    }
+
+    fn attach_shader(&mut self, program: u32, shader: u32) {
+        self.log.push(GlCall::AttachShader(program, shader));
+    }
+
+    fn link_program(&mut self, program: u32) {
+        self.log.push(GlCall::LinkProgram(program));
+    }
+
+    fn delete_program(&mut self, program: u32) {
+        self.log.push(GlCall::DeleteProgram(program));
+    }
+
+    fn get_program_iv(&mut self, program: u32, pname: u32) -> i32 {
+        self.log.push(GlCall::GetProgramIv(program, pname));
+        self.iv(pname)
+    }
+
+    fn get_program_info_log(&mut self, program: u32, _max_length: i32) -> String {
+        self.log.push(GlCall::GetProgramInfoLog(program));
+        self.info_log.clone()
+    }
+
+    fn create_shader(&mut self, shader_type: u32) -> u32 {
+        let id = self.next_id();
+        self.log.push(GlCall::CreateShader(shader_type, id));
+        id
+    }
+
+    fn shader_source(&mut self, shader: u32, source: &str) {
+        self.log.push(GlCall::ShaderSource(shader, source.to_string()));
+    }
+
+    fn compile_shader(&mut self, shader: u32) {
+        self.log.push(GlCall::CompileShader(shader));
+    }
+
+    fn get_shader_iv(&mut self, shader: u32, pname: u32) -> i32 {
+        self.log.push(GlCall::GetShaderIv(shader, pname));
+        self.iv(pname)
+    }
+
+    fn get_shader_info_log(&mut self, shader: u32, _max_length: i32) -> String {
+        self.log.push(GlCall::GetShaderInfoLog(shader));
+        self.info_log.clone()
+    }
+
+    fn delete_shader(&mut self, shader: u32) {
+        self.log.push(GlCall::DeleteShader(shader));
+    }
+
+    fn gen_buffers(&mut self, count: i32) -> u32 {
+        let id = self.next_id();
+        self.log.push(GlCall::GenBuffers(count, id));
+        id
+    }
+
+    fn bind_buffer(&mut self, target: u32, buffer: u32) {
+        self.log.push(GlCall::BindBuffer(target, buffer));
+    }
+
+    fn buffer_data(&mut self, target: u32, size: isize, _data: *const c_void, usage: u32) {
+        self.log.push(GlCall::BufferData(target, size, usage));
+    }
+
+    fn delete_buffers(&mut self, buffer: u32) {
+        self.log.push(GlCall::DeleteBuffers(buffer));
+    }
+
+    fn gen_vertex_arrays(&mut self, count: i32) -> u32 {
+        let id = self.next_id();
+        self.log.push(GlCall::GenVertexArrays(count, id));
+        id
+    }
+
+    fn bind_vertex_array(&mut self, vao: u32) {
+        self.log.push(GlCall::BindVertexArray(vao));
+    }
+
+    fn delete_vertex_arrays(&mut self, vao: u32) {
+        self.log.push(GlCall::DeleteVertexArrays(vao));
+    }
+
+    fn clear(&mut self, mask: u32) {
+        self.log.push(GlCall::Clear(mask));
+    }
+
+    fn clear_color(&mut self, color: [f32; 4]) {
+        self.log.push(GlCall::ClearColor(color));
+    }
+
+    fn draw_arrays(&mut self, mode: u32, first: i32, count: i32) {
+        self.log.push(GlCall::DrawArrays(mode, first, count));
+    }
+
+    fn draw_elements(&mut self, mode: u32, count: i32, index_type: u32, indices: *const c_void) {
+        self.log.push(GlCall::DrawElements(mode, count, index_type, indices as isize));
+    }
+
+    fn viewport(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.viewport = [x, y, width, height];
+        self.log.push(GlCall::Viewport(x, y, width, height));
+    }
+
+    fn scissor(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        self.scissor = [x, y, width, height];
+        self.log.push(GlCall::Scissor(x, y, width, height));
+    }
+
+    fn enable(&mut self, capability: u32) {
+        self.log.push(GlCall::Enable(capability));
+    }
+
+    fn disable(&mut self, capability: u32) {
+        self.log.push(GlCall::Disable(capability));
+    }
+
+    fn cull_face(&mut self, face: u32) {
+        self.log.push(GlCall::CullFace(face));
+    }
+
+    fn front_face(&mut self, ordering: u32) {
+        self.log.push(GlCall::FrontFace(ordering));
+    }
+
+    fn depth_mask(&mut self, flag: bool) {
+        self.log.push(GlCall::DepthMask(flag));
+    }
+
+    fn depth_func(&mut self, func: u32) {
+        self.log.push(GlCall::DepthFunc(func));
+    }
+
+    fn polygon_mode(&mut self, face: u32, mode: u32) {
+        self.log.push(GlCall::PolygonMode(face, mode));
+    }
+
+    fn get_integer_v(&mut self, pname: u32) -> [i32; 4] {
+        self.log.push(GlCall::GetIntegerV(pname));
+        match pname {
+            gl::VIEWPORT => self.viewport,
+            gl::SCISSOR_BOX => self.scissor,
+            gl::DRAW_FRAMEBUFFER_BINDING => [self.draw_framebuffer as i32, 0, 0, 0],
+            gl::READ_FRAMEBUFFER_BINDING => [self.read_framebuffer as i32, 0, 0, 0],
+            _ => [0; 4]
+        }
+    }
+
+    fn gen_framebuffers(&mut self, count: i32) -> u32 {
+        let id = self.next_id();
+        self.log.push(GlCall::GenFramebuffers(count, id));
+        id
+    }
+
+    fn delete_framebuffers(&mut self, framebuffer: u32) {
+        self.log.push(GlCall::DeleteFramebuffers(framebuffer));
+    }
+
+    fn bind_framebuffer(&mut self, target: u32, framebuffer: u32) {
+        match target {
+            gl::READ_FRAMEBUFFER => self.read_framebuffer = framebuffer,
+            gl::DRAW_FRAMEBUFFER => self.draw_framebuffer = framebuffer,
+            _ => {
+                self.read_framebuffer = framebuffer;
+                self.draw_framebuffer = framebuffer;
+            }
+        }
+        self.log.push(GlCall::BindFramebuffer(target, framebuffer));
+    }
+
+    fn framebuffer_texture_2d(&mut self, target: u32, attachment: u32, texture_target: u32, texture: u32, level: i32) {
+        self.log.push(GlCall::FramebufferTexture2D(target, attachment, texture_target, texture, level));
+    }
+
+    fn framebuffer_renderbuffer(&mut self, target: u32, attachment: u32, renderbuffer_target: u32, renderbuffer: u32) {
+        self.log.push(GlCall::FramebufferRenderbuffer(target, attachment, renderbuffer_target, renderbuffer));
+    }
+
+    fn check_framebuffer_status(&mut self, target: u32) -> u32 {
+        self.log.push(GlCall::CheckFramebufferStatus(target));
+        self.framebuffer_status
+    }
+
+    fn draw_buffers(&mut self, attachments: &[u32]) {
+        self.log.push(GlCall::DrawBuffers(attachments.to_vec()));
+    }
+
+    fn read_buffer(&mut self, attachment: u32) {
+        self.log.push(GlCall::ReadBuffer(attachment));
+    }
+
+    fn read_pixels(&mut self, x: i32, y: i32, width: i32, height: i32, format: u32, pixel_type: u32, buffer: &mut [u8]) {
+        //There is nothing to read back, so every pixel is zero.
+        buffer.iter_mut().for_each(|byte| *byte = 0);
+        self.log.push(GlCall::ReadPixels(x, y, width, height, format, pixel_type));
+    }
+
+    fn blit_framebuffer(&mut self, source: [i32; 4], destination: [i32; 4], mask: u32, filter: u32) {
+        self.log.push(GlCall::BlitFramebuffer(source, destination, mask, filter));
+    }
+
+    fn gen_renderbuffers(&mut self, count: i32) -> u32 {
+        let id = self.next_id();
+        self.log.push(GlCall::GenRenderbuffers(count, id));
+        id
+    }
+
+    fn delete_renderbuffers(&mut self, renderbuffer: u32) {
+        self.log.push(GlCall::DeleteRenderbuffers(renderbuffer));
+    }
+
+    fn bind_renderbuffer(&mut self, target: u32, renderbuffer: u32) {
+        self.log.push(GlCall::BindRenderbuffer(target, renderbuffer));
+    }
+
+    fn renderbuffer_storage(&mut self, target: u32, internal_format: u32, width: i32, height: i32) {
+        self.log.push(GlCall::RenderbufferStorage(target, internal_format, width, height));
+    }
+
+    fn gen_textures(&mut self, count: i32) -> u32 {
+        let id = self.next_id();
+        self.log.push(GlCall::GenTextures(count, id));
+        id
+    }
+
+    fn delete_textures(&mut self, texture: u32) {
+        self.log.push(GlCall::DeleteTextures(texture));
+    }
+
+    fn active_texture(&mut self, unit: u32) {
+        self.log.push(GlCall::ActiveTexture(unit));
+    }
+
+    fn bind_texture(&mut self, target: u32, texture: u32) {
+        self.log.push(GlCall::BindTexture(target, texture));
+    }
+
+    fn tex_image_2d(&mut self, target: u32, level: i32, internal_format: i32, width: i32, height: i32, format: u32, pixel_type: u32, _data: *const c_void) {
+        self.log.push(GlCall::TexImage2D(target, level, internal_format, width, height, format, pixel_type));
+    }
+
+    fn tex_parameter_i(&mut self, target: u32, pname: u32, param: i32) {
+        self.log.push(GlCall::TexParameterI(target, pname, param));
+    }
+
+    fn generate_mipmap(&mut self, target: u32) {
+        self.log.push(GlCall::GenerateMipmap(target));
+    }
+
+    fn pixel_store_i(&mut self, pname: u32, param: i32) {
+        self.log.push(GlCall::PixelStoreI(pname, param));
+    }
+
+    fn vertex_attrib_pointer(&mut self, index: u32, size: i32, data_type: u32, normalized: bool, stride: i32, offset: *const c_void) {
+        self.log.push(GlCall::VertexAttribPointer(index, size, data_type, normalized, stride, offset as isize));
+    }
+
+    fn enable_vertex_attrib_array(&mut self, index: u32) {
+        self.log.push(GlCall::EnableVertexAttribArray(index));
+    }
+
+    fn disable_vertex_attrib_array(&mut self, index: u32) {
+        self.log.push(GlCall::DisableVertexAttribArray(index));
+    }
+
+    fn get_uniform_location(&mut self, program: u32, name: &str) -> i32 {
+        self.log.push(GlCall::GetUniformLocation(program, name.to_string()));
+        let next = self.uniform_locations.len() as i32;
+        *self.uniform_locations.entry((program, name.to_string())).or_insert(next)
+    }
+
+    fn uniform_1ui(&mut self, location: i32, value: u32) {
+        self.log.push(GlCall::Uniform1ui(location, value));
+    }
+
+    fn uniform_1i(&mut self, location: i32, value: i32) {
+        self.log.push(GlCall::Uniform1i(location, value));
+    }
+
+    fn uniform_1f(&mut self, location: i32, value: f32) {
+        self.log.push(GlCall::Uniform1f(location, value));
+    }
+
+    fn uniform_1fv(&mut self, location: i32, count: i32, value: &[f32]) {
+        self.log.push(GlCall::Uniform1fv(location, count, value.to_vec()));
+    }
+
+    fn uniform_2fv(&mut self, location: i32, count: i32, value: &[f32]) {
+        self.log.push(GlCall::Uniform2fv(location, count, value.to_vec()));
+    }
+
+    fn uniform_4fv(&mut self, location: i32, count: i32, value: &[f32]) {
+        self.log.push(GlCall::Uniform4fv(location, count, value.to_vec()));
+    }
+
+    fn uniform_matrix4fv(&mut self, location: i32, count: i32, transpose: bool, value: &[f32]) {
+        self.log.push(GlCall::UniformMatrix4fv(location, count, transpose, value.to_vec()));
+    }
+}
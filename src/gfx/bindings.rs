@@ -1,7 +1,4 @@
-use std::ffi::CString;
 use std::mem::size_of;
-use gl::*;
-use std::ptr::{null_mut};
 use crate::gfx::shader::Shader;
 
 #[derive(Debug, Clone, Copy)]
@@ -29,7 +26,26 @@ pub enum DrawType {
 #[derive(Debug, Clone, Copy)]
 #[repr(u32)]
 pub enum BufferType {
-    ArrayBuffer = gl::ARRAY_BUFFER
+    ArrayBuffer = gl::ARRAY_BUFFER,
+    ElementArrayBuffer = gl::ELEMENT_ARRAY_BUFFER,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum IndexType {
+    UnsignedByte = gl::UNSIGNED_BYTE,
+    UnsignedShort = gl::UNSIGNED_SHORT,
+    UnsignedInt = gl::UNSIGNED_INT,
+}
+
+impl IndexType {
+    pub fn size(&self) -> usize {
+        match self {
+            IndexType::UnsignedByte => size_of::<u8>(),
+            IndexType::UnsignedShort => size_of::<u16>(),
+            IndexType::UnsignedInt => size_of::<u32>(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -37,6 +53,7 @@ pub enum BufferType {
 pub enum ColorFlags {
     ColorBufferBit = GLConsts::ColorBufferBit as u32,
     DepthBufferBit = GLConsts::DepthBufferBit as u32,
+    StencilBufferBit = GLConsts::StencilBufferBit as u32,
 }
 
 impl std::ops::BitOr for ColorFlags {
@@ -77,6 +94,7 @@ pub enum GLConsts {
     DepthTest = gl::DEPTH_TEST,
     ColorBufferBit = gl::COLOR_BUFFER_BIT,
     DepthBufferBit = gl::DEPTH_BUFFER_BIT,
+    StencilBufferBit = gl::STENCIL_BUFFER_BIT,
 
     Always = gl::ALWAYS,
     Never = gl::NEVER,
@@ -99,6 +117,7 @@ pub enum GLConsts {
     CounterClockwise = gl::CCW,
     Clockwise = gl::CW,
     Blend = gl::BLEND,
+    ScissorTest = gl::SCISSOR_TEST,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -123,48 +142,140 @@ pub enum Ordering {
     ClockWise = GLConsts::Clockwise as u32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum TextureTarget {
+    Texture2D = gl::TEXTURE_2D,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum TextureParameter {
+    MinFilter = gl::TEXTURE_MIN_FILTER,
+    MagFilter = gl::TEXTURE_MAG_FILTER,
+    WrapS = gl::TEXTURE_WRAP_S,
+    WrapT = gl::TEXTURE_WRAP_T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum TextureFilter {
+    Nearest = gl::NEAREST,
+    Linear = gl::LINEAR,
+    NearestMipmapNearest = gl::NEAREST_MIPMAP_NEAREST,
+    LinearMipmapNearest = gl::LINEAR_MIPMAP_NEAREST,
+    NearestMipmapLinear = gl::NEAREST_MIPMAP_LINEAR,
+    LinearMipmapLinear = gl::LINEAR_MIPMAP_LINEAR,
+}
+
+impl TextureFilter {
+    pub fn uses_mipmaps(&self) -> bool {
+        !matches!(self, TextureFilter::Nearest | TextureFilter::Linear)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum TextureWrap {
+    Repeat = gl::REPEAT,
+    MirroredRepeat = gl::MIRRORED_REPEAT,
+    ClampToEdge = gl::CLAMP_TO_EDGE,
+    ClampToBorder = gl::CLAMP_TO_BORDER,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum PixelType {
+    UnsignedByte = gl::UNSIGNED_BYTE,
+    Float = gl::FLOAT,
+}
+
+impl PixelType {
+    pub fn size(&self) -> usize {
+        match self {
+            PixelType::UnsignedByte => size_of::<u8>(),
+            PixelType::Float => size_of::<f32>(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum FramebufferTarget {
+    Framebuffer = gl::FRAMEBUFFER,
+    ReadFramebuffer = gl::READ_FRAMEBUFFER,
+    DrawFramebuffer = gl::DRAW_FRAMEBUFFER,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Attachment {
+    Color(u32),
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl Attachment {
+    pub fn gl_attachment(&self) -> u32 {
+        match self {
+            Attachment::Color(index) => gl::COLOR_ATTACHMENT0 + index,
+            Attachment::Depth => gl::DEPTH_ATTACHMENT,
+            Attachment::Stencil => gl::STENCIL_ATTACHMENT,
+            Attachment::DepthStencil => gl::DEPTH_STENCIL_ATTACHMENT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum RenderbufferFormat {
+    Depth24Stencil8 = gl::DEPTH24_STENCIL8,
+    DepthComponent24 = gl::DEPTH_COMPONENT24,
+    StencilIndex8 = gl::STENCIL_INDEX8,
+}
+
+impl RenderbufferFormat {
+    pub fn attachment(&self) -> Attachment {
+        match self {
+            RenderbufferFormat::Depth24Stencil8 => Attachment::DepthStencil,
+            RenderbufferFormat::DepthComponent24 => Attachment::Depth,
+            RenderbufferFormat::StencilIndex8 => Attachment::Stencil,
+        }
+    }
+}
+
 pub mod program {
-    use std::ptr::null_mut;
-    use crate::gfx::bindings::{create_whitespace_cstring_with_len, IV};
+    use crate::gfx::backend::with_backend;
+    use crate::gfx::bindings::IV;
     use crate::gfx::program::Program;
     use crate::gfx::shader::Shader;
 
     pub fn use_program(program: &Program) {
-        unsafe {
-            gl::UseProgram(program.id());
-        }
+        with_backend(|gl| gl.use_program(program.id()));
     }
 
     pub fn disable_program() {
-        unsafe {
-            gl::UseProgram(0);
-        }
+        with_backend(|gl| gl.use_program(0));
     }
 
     pub fn create_program() -> u32 {
-        unsafe {
-            gl::CreateProgram()
-        }
+        with_backend(|gl| gl.create_program())
     }
 
     pub fn attach_shader<S: Shader>(program: &Program, shader: &S) {
-        unsafe {
-            gl::AttachShader(program.id(), shader.id());
-        }
+        with_backend(|gl| gl.attach_shader(program.id(), shader.id()));
+    }
+
+    pub fn delete_program(program: &Program) {
+        with_backend(|gl| gl.delete_program(program.id()));
     }
 
     pub fn link_program(program: &Program) {
-        unsafe {
-            gl::LinkProgram(program.id());
-        }
+        with_backend(|gl| gl.link_program(program.id()));
     }
 
     pub fn get_program_iv(program: &Program, iv: IV) -> i32 {
-        unsafe {
-            let mut success: i32 = 0;
-            gl::GetProgramiv(program.id(), iv as u32, &mut success);
-            success
-        }
+        with_backend(|gl| gl.get_program_iv(program.id(), iv as u32))
     }
 
     pub fn program_iv(program: &Program, iv: IV) -> bool {
@@ -176,53 +287,30 @@ pub mod program {
     }
 
     pub fn program_info_log(program: &Program) -> String {
-        unsafe {
-            let log_size = program_log_len(program);
-            let buffer = create_whitespace_cstring_with_len(log_size as usize);
-
-            gl::GetProgramInfoLog(
-                program.id(),
-                log_size,
-                null_mut(),
-                buffer.as_ptr() as *mut gl::types::GLchar,
-            );
-            buffer.to_string_lossy().to_string()
-        }
+        let log_size = program_log_len(program);
+        with_backend(|gl| gl.get_program_info_log(program.id(), log_size))
     }
 }
 
 pub mod shader {
-    use std::ffi::CString;
-    use std::ptr::null;
+    use crate::gfx::backend::with_backend;
     use super::*;
 
     pub fn gl_create_shader(shader_type: ShaderType) -> u32 {
-        unsafe {
-            CreateShader(shader_type as u32)
-        }
+        with_backend(|gl| gl.create_shader(shader_type as u32))
     }
 
     pub fn shader_source<T: ToString>(source: T, shader: &dyn Shader) {
-        unsafe {
-            let source = source.to_string();
-            let source = &CString::new(source).unwrap();
-
-            ShaderSource(shader.id(), 1, &source.as_ptr(), null());
-        }
+        let source = source.to_string();
+        with_backend(|gl| gl.shader_source(shader.id(), &source));
     }
 
     pub fn compile_shader(shader: &dyn Shader) {
-        unsafe {
-            CompileShader(shader.id());
-        }
+        with_backend(|gl| gl.compile_shader(shader.id()));
     }
 
     pub fn get_shader_iv<S: Shader + ?Sized>(shader: &S, iv: IV) -> i32 {
-        unsafe {
-            let mut success: i32 = 0;
-            GetShaderiv(shader.id(), iv as u32, &mut success);
-            success
-        }
+        with_backend(|gl| gl.get_shader_iv(shader.id(), iv as u32))
     }
 
     pub fn info_log_len<S: Shader + ?Sized>(shader: &S) -> i32 {
@@ -230,148 +318,128 @@ pub mod shader {
     }
 
     pub fn shader_info_log<S: Shader + ?Sized>(shader: &S) -> String {
-        unsafe {
-            let log_size = info_log_len(shader);
-            let buffer = create_whitespace_cstring_with_len(log_size as usize);
-
-            gl::GetShaderInfoLog(
-                shader.id(),
-                log_size,
-                null_mut(),
-                buffer.as_ptr() as *mut gl::types::GLchar,
-            );
-            buffer.to_string_lossy().to_string()
-        }
+        let log_size = info_log_len(shader);
+        with_backend(|gl| gl.get_shader_info_log(shader.id(), log_size))
     }
 
     pub fn delete_shader<S: Shader>(shader: &S) {
-        unsafe {
-            gl::DeleteShader(shader.id());
-        }
+        with_backend(|gl| gl.delete_shader(shader.id()));
     }
 }
 
 pub mod buffers {
     use std::ffi::c_void;
     use crate::DrawType;
+    use crate::gfx::backend::with_backend;
     use crate::gfx::bindings::BufferType;
     use crate::gfx::objects::Buffer;
     use crate::gfx::objects::vertex_array_object::VertexArrayObject;
 
     pub fn gen_buffers(count: i32) -> u32 {
-        unsafe {
-            let mut id: u32 = 0;
-            gl::GenBuffers(count, &mut id);
-            id
-        }
+        with_backend(|gl| gl.gen_buffers(count))
     }
 
     pub fn bind_buffer<B: Buffer + ?Sized>(buffer_type: BufferType, buffer: &B) {
-        unsafe {
-            gl::BindBuffer(buffer_type as u32, buffer.id());
-        }
+        with_backend(|gl| gl.bind_buffer(buffer_type as u32, buffer.id()));
     }
 
     pub fn buffer_data_array(buffer_type: BufferType, size: isize, data: *const c_void, draw_type: DrawType) {
-        unsafe {
-            gl::BufferData(buffer_type as u32, size, data, draw_type as u32);
-        }
+        with_backend(|gl| gl.buffer_data(buffer_type as u32, size, data, draw_type as u32));
         println!("glBufferData({:?}, {}, {:?}, {:?})", buffer_type, size, data, draw_type);
     }
 
+    pub fn delete_buffer(buffer: u32) {
+        with_backend(|gl| gl.delete_buffers(buffer));
+    }
+
     pub fn gen_vertex_arrays(size: i32) -> u32 {
-        unsafe {
-            let mut vao: u32 = 0;
-            gl::GenVertexArrays(size, &mut vao);
-            vao
-        }
+        with_backend(|gl| gl.gen_vertex_arrays(size))
     }
 
     pub fn bind_vertex_array(vao: &VertexArrayObject) {
-        unsafe {
-            gl::BindVertexArray(vao.id());
-        }
+        with_backend(|gl| gl.bind_vertex_array(vao.id()));
     }
 
     pub fn unbind_vertex_array() {
-        unsafe {
-            gl::BindVertexArray(0);
-        }
+        with_backend(|gl| gl.bind_vertex_array(0));
+    }
+
+    pub fn delete_vertex_array(vao: u32) {
+        with_backend(|gl| gl.delete_vertex_arrays(vao));
     }
 }
 
 pub mod graphics {
-    use crate::gfx::bindings::{DrawMode, Face, GLConsts, Ordering, PolygonMode};
+    use std::ffi::c_void;
+    use crate::gfx::backend::with_backend;
+    use crate::gfx::bindings::{DrawMode, Face, GLConsts, IndexType, Ordering, PolygonMode};
     use crate::Program;
 
     pub fn clear(flags: u32) {
-        unsafe {
-            gl::Clear(flags)
-        }
+        with_backend(|gl| gl.clear(flags));
     }
 
     pub fn clear_color(color: [f32; 4]) {
-        unsafe {
-            gl::ClearColor(color[0], color[1], color[2], color[3]);
-        }
+        with_backend(|gl| gl.clear_color(color));
     }
 
     pub fn draw_arrays(mode: DrawMode, first: i32, count: i32) {
-        unsafe {
-            gl::DrawArrays(mode as u32, first, count);
-        }
+        with_backend(|gl| gl.draw_arrays(mode as u32, first, count));
+    }
+
+    ///Draws `count` indices from the bound element array buffer, starting `offset` indices in.
+    pub fn draw_elements(mode: DrawMode, count: i32, index_type: IndexType, offset: usize) {
+        let offset = (offset * index_type.size()) as *const c_void;
+        with_backend(|gl| gl.draw_elements(mode as u32, count, index_type as u32, offset));
     }
 
     pub fn viewport(position: [i32; 2], size: [i32; 2]) {
-        unsafe {
-            gl::Viewport(position[0], position[1], size[0], size[1])
-        }
+        with_backend(|gl| gl.viewport(position[0], position[1], size[0], size[1]));
+    }
+
+    ///The current viewport as position and size.
+    pub fn get_viewport() -> ([i32; 2], [i32; 2]) {
+        let viewport = with_backend(|gl| gl.get_integer_v(gl::VIEWPORT));
+        ([viewport[0], viewport[1]], [viewport[2], viewport[3]])
+    }
+
+    ///Restricts drawing to the rectangle at `position` of `size`, in window coordinates, while `GLConsts::ScissorTest` is enabled.
+    pub fn scissor(position: [i32; 2], size: [i32; 2]) {
+        with_backend(|gl| gl.scissor(position[0], position[1], size[0], size[1]));
+    }
+
+    ///The current scissor rectangle as position and size.
+    pub fn get_scissor() -> ([i32; 2], [i32; 2]) {
+        let scissor = with_backend(|gl| gl.get_integer_v(gl::SCISSOR_BOX));
+        ([scissor[0], scissor[1]], [scissor[2], scissor[3]])
     }
 
     pub fn enable(constant: GLConsts) {
-        unsafe {
-            gl::Enable(constant as u32);
-        }
+        with_backend(|gl| gl.enable(constant as u32));
     }
 
     pub fn cull_face(face: Face) {
-        unsafe {
-            gl::CullFace(face as u32);
-        }
+        with_backend(|gl| gl.cull_face(face as u32));
     }
 
     pub fn front_face(ordering: Ordering) {
-        unsafe {
-            gl::FrontFace(ordering as u32);
-        }
+        with_backend(|gl| gl.front_face(ordering as u32));
     }
 
     pub fn disable(constant: GLConsts) {
-        unsafe {
-            gl::Disable(constant as u32);
-        }
+        with_backend(|gl| gl.disable(constant as u32));
     }
 
     pub fn depth_mask(depth_mask: bool) {
-        unsafe {
-            let mask = match depth_mask {
-                true => gl::TRUE,
-                false => gl::FALSE
-            };
-            gl::DepthMask(mask)
-        }
+        with_backend(|gl| gl.depth_mask(depth_mask));
     }
 
     pub fn depth_func(constant: GLConsts) {
-        unsafe {
-            gl::DepthFunc(constant as u32);
-        }
+        with_backend(|gl| gl.depth_func(constant as u32));
     }
 
     pub fn polygon_mode(face: Face, mode: PolygonMode) {
-        unsafe {
-            gl::PolygonMode(face as u32, mode as u32);
-        }
+        with_backend(|gl| gl.polygon_mode(face as u32, mode as u32));
     }
 
     pub fn shaded_wireframe<F>(face: Face, wireframe_color: &[f32; 4], draw: F) where F: Fn() {
@@ -387,103 +455,194 @@ pub mod graphics {
     }
 }
 
+pub mod textures {
+    use std::ffi::c_void;
+    use crate::gfx::backend::with_backend;
+    use crate::gfx::bindings::{PixelType, TextureTarget, TextureParameter};
+
+    pub fn gen_textures(count: i32) -> u32 {
+        with_backend(|gl| gl.gen_textures(count))
+    }
+
+    pub fn delete_texture(texture: u32) {
+        with_backend(|gl| gl.delete_textures(texture));
+    }
+
+    ///Selects texture unit `unit`, i.e. `GL_TEXTURE0 + unit`.
+    pub fn active_texture(unit: u32) {
+        with_backend(|gl| gl.active_texture(gl::TEXTURE0 + unit));
+    }
+
+    pub fn bind_texture(target: TextureTarget, texture: u32) {
+        with_backend(|gl| gl.bind_texture(target as u32, texture));
+    }
+
+    pub fn unbind_texture(target: TextureTarget) {
+        bind_texture(target, 0);
+    }
+
+    pub fn tex_image_2d(target: TextureTarget, level: i32, internal_format: u32, size: [i32; 2], format: u32, pixel_type: PixelType, data: *const c_void) {
+        with_backend(|gl| gl.tex_image_2d(target as u32, level, internal_format as i32, size[0], size[1], format, pixel_type as u32, data));
+    }
+
+    pub fn tex_parameter_i(target: TextureTarget, parameter: TextureParameter, value: u32) {
+        with_backend(|gl| gl.tex_parameter_i(target as u32, parameter as u32, value as i32));
+    }
+
+    pub fn generate_mipmap(target: TextureTarget) {
+        with_backend(|gl| gl.generate_mipmap(target as u32));
+    }
+
+    ///Sets the row alignment used when reading client pixel data.
+    pub fn unpack_alignment(alignment: i32) {
+        with_backend(|gl| gl.pixel_store_i(gl::UNPACK_ALIGNMENT, alignment));
+    }
+}
+
+pub mod framebuffers {
+    use crate::gfx::backend::with_backend;
+    use crate::gfx::bindings::{Attachment, FramebufferTarget, PixelType, RenderbufferFormat, TextureFilter, TextureTarget};
+
+    pub fn gen_framebuffers(count: i32) -> u32 {
+        with_backend(|gl| gl.gen_framebuffers(count))
+    }
+
+    pub fn delete_framebuffer(framebuffer: u32) {
+        with_backend(|gl| gl.delete_framebuffers(framebuffer));
+    }
+
+    ///Binds `framebuffer`, 0 being the default framebuffer of the window.
+    pub fn bind_framebuffer(target: FramebufferTarget, framebuffer: u32) {
+        with_backend(|gl| gl.bind_framebuffer(target as u32, framebuffer));
+    }
+
+    ///The framebuffer currently bound for drawing.
+    pub fn bound_framebuffer() -> u32 {
+        with_backend(|gl| gl.get_integer_v(gl::DRAW_FRAMEBUFFER_BINDING))[0] as u32
+    }
+
+    pub fn framebuffer_texture_2d(target: FramebufferTarget, attachment: Attachment, texture_target: TextureTarget, texture: u32, level: i32) {
+        with_backend(|gl| gl.framebuffer_texture_2d(target as u32, attachment.gl_attachment(), texture_target as u32, texture, level));
+    }
+
+    pub fn framebuffer_renderbuffer(target: FramebufferTarget, attachment: Attachment, renderbuffer: u32) {
+        with_backend(|gl| gl.framebuffer_renderbuffer(target as u32, attachment.gl_attachment(), gl::RENDERBUFFER, renderbuffer));
+    }
+
+    pub fn check_framebuffer_status(target: FramebufferTarget) -> u32 {
+        with_backend(|gl| gl.check_framebuffer_status(target as u32))
+    }
+
+    pub fn draw_buffers(attachments: &[Attachment]) {
+        let attachments: Vec<u32> = attachments.iter().map(|attachment| attachment.gl_attachment()).collect();
+        with_backend(|gl| gl.draw_buffers(&attachments));
+    }
+
+    pub fn read_buffer(attachment: Attachment) {
+        with_backend(|gl| gl.read_buffer(attachment.gl_attachment()));
+    }
+
+    ///Size of a pixel of `format` and `pixel_type` as `read_pixels` writes it, with a pack alignment of 1.
+    pub fn bytes_per_pixel(format: u32, pixel_type: PixelType) -> usize {
+        let channels = match format {
+            gl::RED | gl::GREEN | gl::BLUE | gl::ALPHA | gl::DEPTH_COMPONENT | gl::STENCIL_INDEX => 1,
+            gl::RG => 2,
+            gl::RGB | gl::BGR => 3,
+            gl::RGBA | gl::BGRA => 4,
+            _ => panic!("Unsupported pixel format {:#x}", format)
+        };
+        channels * pixel_type.size()
+    }
+
+    ///Reads a block of pixels from the read framebuffer into `buffer`, bottom row first.
+    ///Panics if `buffer` is too small for `size` pixels, see `bytes_per_pixel`.
+    pub fn read_pixels(position: [i32; 2], size: [i32; 2], format: u32, pixel_type: PixelType, buffer: &mut [u8]) {
+        let required = size[0].max(0) as usize * size[1].max(0) as usize * bytes_per_pixel(format, pixel_type);
+        assert!(buffer.len() >= required, "read_pixels needs {} bytes, the buffer has {}", required, buffer.len());
+        with_backend(|gl| {
+            gl.pixel_store_i(gl::PACK_ALIGNMENT, 1);
+            gl.read_pixels(position[0], position[1], size[0], size[1], format, pixel_type as u32, buffer);
+            gl.pixel_store_i(gl::PACK_ALIGNMENT, 4);
+        });
+    }
+
+    ///Copies `source` ([x0, y0, x1, y1]) of the read framebuffer into `destination` of the draw framebuffer.
+    pub fn blit_framebuffer(source: [i32; 4], destination: [i32; 4], mask: u32, filter: TextureFilter) {
+        with_backend(|gl| gl.blit_framebuffer(source, destination, mask, filter as u32));
+    }
+
+    pub fn gen_renderbuffers(count: i32) -> u32 {
+        with_backend(|gl| gl.gen_renderbuffers(count))
+    }
+
+    pub fn delete_renderbuffer(renderbuffer: u32) {
+        with_backend(|gl| gl.delete_renderbuffers(renderbuffer));
+    }
+
+    pub fn bind_renderbuffer(renderbuffer: u32) {
+        with_backend(|gl| gl.bind_renderbuffer(gl::RENDERBUFFER, renderbuffer));
+    }
+
+    pub fn renderbuffer_storage(format: RenderbufferFormat, size: [i32; 2]) {
+        with_backend(|gl| gl.renderbuffer_storage(gl::RENDERBUFFER, format as u32, size[0], size[1]));
+    }
+}
+
 pub mod attrib_pointer {
     use std::ffi::c_void;
-    use std::mem::transmute;
     use crate::BufferDataType;
+    use crate::gfx::backend::with_backend;
     use crate::gfx::objects::vertex_attrib_pointer::VertexAttribPointer;
 
     pub fn vertex_attrib_pointer(id: u32, size: i32, data_type: BufferDataType, normalized: bool, stride: i32, offset: *const c_void) {
-        unsafe {
-            let normalized = match normalized {
-                true => gl::TRUE,
-                false => gl::FALSE
-            };
-            gl::VertexAttribPointer(id, size, data_type as u32, normalized, stride, offset);
-            let offset: isize = transmute(offset);
-            println!("VertexAttribPointer({}, {}, {:?}, {}, {}, {})", id, size, data_type, normalized, stride, offset);
-        }
+        with_backend(|gl| gl.vertex_attrib_pointer(id, size, data_type as u32, normalized, stride, offset));
+        println!("VertexAttribPointer({}, {}, {:?}, {}, {}, {})", id, size, data_type, normalized, stride, offset as isize);
     }
 
     pub fn enable_vertex_attrib_array(attrib_pointer: &VertexAttribPointer) {
-        unsafe {
-            gl::EnableVertexAttribArray(attrib_pointer.id());
-        }
+        with_backend(|gl| gl.enable_vertex_attrib_array(attrib_pointer.id()));
     }
 
     pub fn disable_vertex_attrib_array(attrib_pointer: &VertexAttribPointer) {
-        unsafe {
-            gl::DisableVertexAttribArray(attrib_pointer.id());
-        }
+        with_backend(|gl| gl.disable_vertex_attrib_array(attrib_pointer.id()));
     }
 }
 
 pub mod uniforms {
-    use std::ffi::CString;
     use vecmath::{Matrix4, Vector2, Vector4};
+    use crate::gfx::backend::with_backend;
     use crate::Program;
 
     pub fn uniform_location<T: ToString>(program: &Program, name: T) -> i32 {
-        unsafe {
-            let name = name.to_string();
-            let name = CString::new(name).unwrap();
-
-            gl::GetUniformLocation(program.id(), name.as_ptr())
-        }
+        let name = name.to_string();
+        with_backend(|gl| gl.get_uniform_location(program.id(), &name))
     }
 
     pub fn uniform_1ui(location: i32, value: &u32) {
-        unsafe {
-            gl::Uniform1ui(location, *value);
-        }
+        with_backend(|gl| gl.uniform_1ui(location, *value));
     }
 
     pub fn uniform_1i(location: i32, value: &i32) {
-        unsafe {
-            gl::Uniform1i(location, *value);
-        }
+        with_backend(|gl| gl.uniform_1i(location, *value));
     }
 
     pub fn uniform_1f(location: i32, value: &f32) {
-        unsafe {
-            gl::Uniform1f(location, *value);
-        }
+        with_backend(|gl| gl.uniform_1f(location, *value));
     }
 
-    pub fn uniform_1fv(location: i32, count: i32, value: &f32) {
-        unsafe {
-            gl::Uniform1fv(location, count, value as *const f32);
-        }
+    pub fn uniform_1fv(location: i32, values: &[f32]) {
+        with_backend(|gl| gl.uniform_1fv(location, values.len() as i32, values));
     }
 
-    pub fn uniform_2fv(location: i32, count: i32, value: &Vector2<f32>) {
-        unsafe {
-            gl::Uniform2fv(location, count, value.as_ptr());
-        }
+    pub fn uniform_2fv(location: i32, values: &[Vector2<f32>]) {
+        with_backend(|gl| gl.uniform_2fv(location, values.len() as i32, values.as_flattened()));
     }
 
-    pub fn uniform_4fv(location: i32, count: i32, value: &Vector4<f32>) {
-        unsafe {
-            gl::Uniform4fv(location, count, value.as_ptr());
-        }
+    pub fn uniform_4fv(location: i32, values: &[Vector4<f32>]) {
+        with_backend(|gl| gl.uniform_4fv(location, values.len() as i32, values.as_flattened()));
     }
 
-    pub fn uniform_matrix4fv(location: i32, count: i32, transpose: bool, value: &Matrix4<f32>) {
-        unsafe {
-            let transpose = match transpose {
-                true => gl::TRUE,
-                false => gl::FALSE
-            };
-            gl::UniformMatrix4fv(location, count, transpose, value[0].as_ptr())
-        }
+    pub fn uniform_matrix4fv(location: i32, transpose: bool, values: &[Matrix4<f32>]) {
+        with_backend(|gl| gl.uniform_matrix4fv(location, values.len() as i32, transpose, values.as_flattened().as_flattened()));
     }
 }
-
-fn create_whitespace_cstring_with_len(len: usize) -> CString {
-    // allocate buffer of correct size
-    let mut buffer: Vec<u8> = Vec::with_capacity(len + 1);
-    // fill it with len spaces
-    buffer.extend([b' '].iter().cycle().take(len));
-    // convert buffer to CString
-    unsafe { CString::from_vec_unchecked(buffer) }
-}
\ No newline at end of file
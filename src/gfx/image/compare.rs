@@ -0,0 +1,59 @@
+use crate::gfx::image::Image;
+use crate::math::color::rgba::RGBA;
+
+///Result of comparing two images channel by channel.
+#[derive(Debug, Clone)]
+pub struct ImageComparison {
+    mismatched_pixels: usize,
+    max_difference: u8,
+    ///Red where a pixel differs by more than the tolerance, a dimmed grey version of the expected image elsewhere.
+    diff: Image,
+}
+
+impl ImageComparison {
+    pub fn matches(&self) -> bool {
+        self.mismatched_pixels == 0
+    }
+    pub fn mismatched_pixels(&self) -> usize {
+        self.mismatched_pixels
+    }
+    pub fn max_difference(&self) -> u8 {
+        self.max_difference
+    }
+    pub fn diff(&self) -> &Image {
+        &self.diff
+    }
+}
+
+///Compares `actual` against `expected`, allowing each channel to differ by at most `tolerance`.
+///Returns `None` when the sizes differ.
+pub fn compare(expected: &Image, actual: &Image, tolerance: u8) -> Option<ImageComparison> {
+    if expected.size() != actual.size() {
+        return None;
+    }
+    let mut mismatched_pixels = 0;
+    let mut max_difference = 0;
+    let mut diff = Vec::with_capacity(expected.pixels().len());
+
+    for (expected, actual) in expected.pixels().iter().zip(actual.pixels()) {
+        let difference = (0..4)
+            .map(|channel| (expected[channel] as i16 - actual[channel] as i16).unsigned_abs() as u8)
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+
+        if difference > tolerance {
+            mismatched_pixels += 1;
+            diff.push(RGBA([255, 0, 0, 255]));
+        } else {
+            let grey = ((expected[0] as u16 + expected[1] as u16 + expected[2] as u16) / 3 / 4) as u8;
+            diff.push(RGBA([grey, grey, grey, 255]));
+        }
+    }
+
+    Some(ImageComparison {
+        mismatched_pixels,
+        max_difference,
+        diff: Image::new(expected.size(), diff),
+    })
+}
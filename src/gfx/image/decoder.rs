@@ -0,0 +1,230 @@
+use std::fs;
+use std::path::Path;
+use crate::gfx::image::{Image, ImageError};
+use crate::gfx::texture::texture_2d::Texture2D;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Tga,
+    Bmp,
+}
+
+impl ImageFormat {
+    pub fn from_extension(extension: &str) -> Option<ImageFormat> {
+        match extension.to_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "tga" => Some(ImageFormat::Tga),
+            "bmp" => Some(ImageFormat::Bmp),
+            _ => None
+        }
+    }
+
+    ///Detects the format from the magic bytes. TGA has none, so it is only found by extension.
+    pub fn from_magic(bytes: &[u8]) -> Option<ImageFormat> {
+        if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+            Some(ImageFormat::Png)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageFormat::Jpeg)
+        } else if bytes.starts_with(b"BM") {
+            Some(ImageFormat::Bmp)
+        } else {
+            None
+        }
+    }
+
+    fn to_image_format(&self) -> ::image::ImageFormat {
+        match self {
+            ImageFormat::Png => ::image::ImageFormat::Png,
+            ImageFormat::Jpeg => ::image::ImageFormat::Jpeg,
+            ImageFormat::Tga => ::image::ImageFormat::Tga,
+            ImageFormat::Bmp => ::image::ImageFormat::Bmp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImageOptions {
+    ///Files store the top row first while GL expects the bottom row first.
+    flip_vertically: bool,
+    srgb: bool,
+    premultiply_alpha: bool,
+}
+
+impl ImageOptions {
+    pub fn new() -> ImageOptions {
+        Self { flip_vertically: true, srgb: false, premultiply_alpha: false }
+    }
+    pub fn with_flip_vertically(mut self, flip_vertically: bool) -> ImageOptions {
+        self.flip_vertically = flip_vertically;
+        self
+    }
+    pub fn with_srgb(mut self, srgb: bool) -> ImageOptions {
+        self.srgb = srgb;
+        self
+    }
+    pub fn with_premultiplied_alpha(mut self, premultiply_alpha: bool) -> ImageOptions {
+        self.premultiply_alpha = premultiply_alpha;
+        self
+    }
+    pub fn flip_vertically(&self) -> bool {
+        self.flip_vertically
+    }
+    pub fn srgb(&self) -> bool {
+        self.srgb
+    }
+    pub fn premultiply_alpha(&self) -> bool {
+        self.premultiply_alpha
+    }
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        ImageOptions::new()
+    }
+}
+
+///Decodes an encoded image. Does not touch GL.
+pub fn decode(bytes: &[u8], format: ImageFormat, options: &ImageOptions) -> Result<Image, ImageError> {
+    let decoded = ::image::load_from_memory_with_format(bytes, format.to_image_format())
+        .map_err(|error| ImageError::Decode(error.to_string()))?
+        .to_rgba8();
+    let size = [decoded.width(), decoded.height()];
+
+    let mut image = Image::from_bytes(size, decoded.as_raw()).with_srgb(options.srgb);
+    if options.flip_vertically {
+        image.flip_vertically();
+    }
+    if options.premultiply_alpha {
+        image.premultiply_alpha();
+    }
+    Ok(image)
+}
+
+///Reads and decodes an image file. The format is taken from the magic bytes, falling back to the extension.
+pub fn load<P: AsRef<Path>>(path: P, options: &ImageOptions) -> Result<Image, ImageError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|error| ImageError::Io(format!("{}: {}", path.display(), error)))?;
+    let format = match ImageFormat::from_magic(&bytes) {
+        Some(format) => format,
+        None => {
+            let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("");
+            match ImageFormat::from_extension(extension) {
+                Some(format) => format,
+                None => return Err(ImageError::UnsupportedFormat(path.display().to_string()))
+            }
+        }
+    };
+    decode(&bytes, format, options)
+}
+
+///Loads an image file straight into a texture.
+pub fn load_texture<P: AsRef<Path>>(path: P, options: &ImageOptions) -> Result<Texture2D, ImageError> {
+    let image = load(path, options)?;
+    Ok(image.to_texture()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gfx::image::encoder::encode_png;
+    use crate::gfx::texture::PixelFormat;
+    use crate::math::color::rgba::RGBA;
+
+    const TOP: RGBA = RGBA([255, 0, 0, 255]);
+    const BOTTOM: RGBA = RGBA([0, 255, 0, 128]);
+
+    fn unflipped() -> ImageOptions {
+        ImageOptions::new().with_flip_vertically(false)
+    }
+
+    ///One column, `TOP` above `BOTTOM`.
+    fn png() -> Vec<u8> {
+        encode_png(&Image::new([1, 2], vec![TOP, BOTTOM])).unwrap()
+    }
+
+    ///One column, 24 bit, rows stored bottom up and padded to four bytes.
+    fn bmp() -> Vec<u8> {
+        let mut bytes = b"BM".to_vec();
+        [62u32, 0, 54, 40].iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes()));
+        [1i32, 2].iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes()));
+        [1u16, 24].iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes()));
+        [0u32, 8, 2835, 2835, 0, 0].iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes()));
+        bytes.extend_from_slice(&[0, 255, 0, 0, 0, 0, 255, 0]);
+        bytes
+    }
+
+    ///One column, 32 bit BGRA, rows stored top down.
+    fn tga() -> Vec<u8> {
+        let mut bytes = vec![0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        [1u16, 2].iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes()));
+        bytes.extend_from_slice(&[32, 0x28]);
+        bytes.extend_from_slice(&[0, 0, 255, 255, 0, 255, 0, 128]);
+        bytes
+    }
+
+    #[test]
+    fn detects_format_by_magic() {
+        assert_eq!(ImageFormat::from_magic(&png()), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::from_magic(&bmp()), Some(ImageFormat::Bmp));
+        assert_eq!(ImageFormat::from_magic(&tga()), None);
+        assert_eq!(ImageFormat::from_extension("TGA"), Some(ImageFormat::Tga));
+    }
+
+    #[test]
+    fn decodes_top_row_first_without_flip() {
+        let image = decode(&png(), ImageFormat::Png, &unflipped()).unwrap();
+        assert_eq!(image.size(), [1, 2]);
+        assert_eq!(image.pixels(), &vec![TOP, BOTTOM]);
+    }
+
+    #[test]
+    fn flips_to_bottom_row_first_by_default() {
+        let image = decode(&png(), ImageFormat::Png, &ImageOptions::new()).unwrap();
+        assert_eq!(image.pixels(), &vec![BOTTOM, TOP]);
+    }
+
+    #[test]
+    fn premultiplies_alpha() {
+        let image = decode(&png(), ImageFormat::Png, &unflipped().with_premultiplied_alpha(true)).unwrap();
+        assert_eq!(image.pixels(), &vec![TOP, RGBA([0, 128, 0, 128])]);
+    }
+
+    #[test]
+    fn marks_srgb() {
+        let image = decode(&png(), ImageFormat::Png, &unflipped()).unwrap();
+        assert!(!image.is_srgb());
+        assert_eq!(image.pixel_format(), PixelFormat::Rgba8);
+
+        let image = decode(&png(), ImageFormat::Png, &unflipped().with_srgb(true)).unwrap();
+        assert!(image.is_srgb());
+        assert_eq!(image.pixel_format(), PixelFormat::Srgb8Alpha8);
+        //The pixels stay as stored, only the texture format changes.
+        assert_eq!(image.pixels(), &vec![TOP, BOTTOM]);
+    }
+
+    #[test]
+    fn decodes_bmp() {
+        let image = decode(&bmp(), ImageFormat::Bmp, &unflipped()).unwrap();
+        assert_eq!(image.pixels(), &vec![TOP, RGBA([0, 255, 0, 255])]);
+
+        let image = decode(&bmp(), ImageFormat::Bmp, &ImageOptions::new()).unwrap();
+        assert_eq!(image.pixels(), &vec![RGBA([0, 255, 0, 255]), TOP]);
+    }
+
+    #[test]
+    fn decodes_tga() {
+        let image = decode(&tga(), ImageFormat::Tga, &unflipped()).unwrap();
+        assert_eq!(image.pixels(), &vec![TOP, BOTTOM]);
+
+        let image = decode(&tga(), ImageFormat::Tga, &ImageOptions::new().with_premultiplied_alpha(true)).unwrap();
+        assert_eq!(image.pixels(), &vec![RGBA([0, 128, 0, 128]), TOP]);
+    }
+
+    #[test]
+    fn reports_invalid_data() {
+        assert!(matches!(decode(b"not an image", ImageFormat::Png, &ImageOptions::new()), Err(ImageError::Decode(_))));
+    }
+}
@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::Path;
+use crate::gfx::image::{Image, ImageError};
+use crate::gfx::image::decoder::ImageFormat;
+
+///Encodes the image as a binary PPM (P6). PPM has no alpha channel, so alpha is dropped.
+pub fn encode_ppm(image: &Image) -> Vec<u8> {
+    let mut bytes = format!("P6\n{} {}\n255\n", image.width(), image.height()).into_bytes();
+    bytes.reserve(image.pixels().len() * 3);
+    for pixel in image.pixels() {
+        bytes.extend_from_slice(&pixel.0[..3]);
+    }
+    bytes
+}
+
+pub fn encode_png(image: &Image) -> Result<Vec<u8>, ImageError> {
+    let mut bytes = vec![];
+    ::image::codecs::png::PngEncoder::new(&mut bytes)
+        .encode(&image.to_bytes(), image.width(), image.height(), ::image::ColorType::Rgba8)
+        .map_err(|error| ImageError::Encode(error.to_string()))?;
+    Ok(bytes)
+}
+
+pub fn save_ppm<P: AsRef<Path>>(image: &Image, path: P) -> Result<(), ImageError> {
+    write(path.as_ref(), &encode_ppm(image))
+}
+
+pub fn save_png<P: AsRef<Path>>(image: &Image, path: P) -> Result<(), ImageError> {
+    write(path.as_ref(), &encode_png(image)?)
+}
+
+///Saves the image as PNG or PPM depending on the extension of `path`. Rows are written in stored order.
+pub fn save<P: AsRef<Path>>(image: &Image, path: P) -> Result<(), ImageError> {
+    let path = path.as_ref();
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("").to_lowercase();
+    if extension == "ppm" {
+        return save_ppm(image, path);
+    }
+    match ImageFormat::from_extension(&extension) {
+        Some(ImageFormat::Png) => save_png(image, path),
+        _ => Err(ImageError::UnsupportedFormat(path.display().to_string()))
+    }
+}
+
+fn write(path: &Path, bytes: &[u8]) -> Result<(), ImageError> {
+    fs::write(path, bytes).map_err(|error| ImageError::Io(format!("{}: {}", path.display(), error)))
+}
@@ -0,0 +1,137 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::gfx::texture::{PixelFormat, TextureError};
+use crate::gfx::texture::texture_2d::Texture2D;
+use crate::math::color::rgba::RGBA;
+
+pub mod decoder;
+pub mod encoder;
+pub mod compare;
+
+///CPU side RGBA8 pixel buffer. Rows are kept in the order they were produced in: decoded files are flipped to GL's
+///bottom-up order by default, while captures are flipped to the top-down order image files use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    size: [u32; 2],
+    pixels: Vec<RGBA>,
+    srgb: bool,
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(String),
+    UnsupportedFormat(String),
+    Decode(String),
+    Encode(String),
+    Texture(TextureError),
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::Io(error) => f.write_fmt(format_args!("IO error: {}", error)),
+            ImageError::UnsupportedFormat(format) => f.write_fmt(format_args!("Unsupported image format: {}", format)),
+            ImageError::Decode(error) => f.write_fmt(format_args!("Decoding error: {}", error)),
+            ImageError::Encode(error) => f.write_fmt(format_args!("Encoding error: {}", error)),
+            ImageError::Texture(error) => f.write_fmt(format_args!("Texture error: {}", error)),
+        }
+    }
+}
+
+impl Error for ImageError {}
+
+impl From<TextureError> for ImageError {
+    fn from(error: TextureError) -> Self {
+        ImageError::Texture(error)
+    }
+}
+
+impl Image {
+    pub fn new(size: [u32; 2], pixels: Vec<RGBA>) -> Image {
+        assert_eq!(size[0] as usize * size[1] as usize, pixels.len(), "Image size does not match the amount of pixels.");
+        Self { size, pixels, srgb: false }
+    }
+
+    pub fn filled(size: [u32; 2], color: RGBA) -> Image {
+        Self::new(size, vec![color; size[0] as usize * size[1] as usize])
+    }
+
+    ///Builds an image from tightly packed RGBA8 bytes.
+    pub fn from_bytes(size: [u32; 2], bytes: &[u8]) -> Image {
+        let pixels = bytes.chunks_exact(4).map(|p| RGBA([p[0], p[1], p[2], p[3]])).collect();
+        Self::new(size, pixels)
+    }
+
+    pub fn with_srgb(mut self, srgb: bool) -> Image {
+        self.srgb = srgb;
+        self
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+    pub fn width(&self) -> u32 {
+        self.size[0]
+    }
+    pub fn height(&self) -> u32 {
+        self.size[1]
+    }
+    pub fn is_srgb(&self) -> bool {
+        self.srgb
+    }
+    pub fn pixels(&self) -> &Vec<RGBA> {
+        &self.pixels
+    }
+    pub fn pixels_mut(&mut self) -> &mut Vec<RGBA> {
+        &mut self.pixels
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> &RGBA {
+        &self.pixels[(y * self.size[0] + x) as usize]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: RGBA) {
+        let width = self.size[0];
+        self.pixels[(y * width + x) as usize] = color;
+    }
+
+    pub fn flip_vertically(&mut self) {
+        let width = self.size[0] as usize;
+        let height = self.size[1] as usize;
+        for row in 0..height / 2 {
+            let (top, bottom) = self.pixels.split_at_mut((height - row - 1) * width);
+            top[row * width..(row + 1) * width].swap_with_slice(&mut bottom[..width]);
+        }
+    }
+
+    ///Multiplies the color channels by alpha, rounding to the nearest value.
+    pub fn premultiply_alpha(&mut self) {
+        for pixel in &mut self.pixels {
+            let alpha = pixel.0[3] as u32;
+            for channel in 0..3 {
+                pixel.0[channel] = ((pixel.0[channel] as u32 * alpha + 127) / 255) as u8;
+            }
+        }
+    }
+
+    ///The pixels as tightly packed RGBA8 bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.pixels.len() * 4);
+        for pixel in &self.pixels {
+            bytes.extend_from_slice(&pixel.0);
+        }
+        bytes
+    }
+
+    pub fn pixel_format(&self) -> PixelFormat {
+        match self.srgb {
+            true => PixelFormat::Srgb8Alpha8,
+            false => PixelFormat::Rgba8
+        }
+    }
+
+    ///Uploads the image as a new 2D texture.
+    pub fn to_texture(&self) -> Result<Texture2D, TextureError> {
+        Texture2D::new(self.size, self.pixel_format(), &self.to_bytes())
+    }
+}
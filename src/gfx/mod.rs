@@ -1,6 +1,10 @@
 pub mod program;
 pub mod shader;
 pub mod bindings;
+pub mod backend;
 pub mod render;
 pub mod objects;
+pub mod texture;
+pub mod image;
+pub mod text;
 pub mod ui;
\ No newline at end of file
@@ -1,15 +1,15 @@
-use obj::Obj;
+use obj::{Obj, Vertex};
 use crate::{BufferDataType, BufferType, Constructor, DrawType, LocalAttribPointer, VertexArrayObject, VertexArrayObjectType, VertexBufferObject};
+use crate::gfx::objects::index_buffer_object::IndexBufferObject;
 
-impl ToVAO for Obj {
+///Indices are uploaded as `u16` where every vertex fits, and as `u32` for meshes with more vertices.
+impl<I: Copy + Into<u32>> ToVAO for Obj<Vertex, I> {
     fn to_vao(&self) -> VertexArrayObject {
         let vertices = &self.vertices;
-        let indices = &self.indices;
+        let indices: Vec<u32> = self.indices.iter().map(|index| (*index).into()).collect();
         let mut positions = vec![];
 
-        for index in indices {
-            let index = *index;
-            let vertex = vertices[index as usize];
+        for vertex in vertices {
             let position = vertex.position;
             let normal = vertex.normal;
             positions.push(position[0]);
@@ -21,8 +21,17 @@ impl ToVAO for Obj {
             positions.push(normal[2]);
         }
 
-        VertexArrayObject::new(Some(VertexArrayObjectType::Arrays(indices.len() as i32)))
+        let index_buffer = match vertices.len() > u16::MAX as usize {
+            true => IndexBufferObject::new(DrawType::StaticDraw, &indices),
+            false => {
+                let indices: Vec<u16> = indices.iter().map(|index| *index as u16).collect();
+                IndexBufferObject::new(DrawType::StaticDraw, &indices)
+            }
+        };
+
+        VertexArrayObject::new(Some(VertexArrayObjectType::Elements(index_buffer.count(), index_buffer.index_type())))
             .with_buffer(VertexBufferObject::array(BufferType::ArrayBuffer, DrawType::StaticDraw, &positions))
+            .with_buffer(index_buffer)
             .with_local_attrib_pointer(LocalAttribPointer::new(3, BufferDataType::Float, false))
             .with_local_attrib_pointer(LocalAttribPointer::new(3, BufferDataType::Float, false))
             .build()
@@ -31,4 +40,4 @@ impl ToVAO for Obj {
 
 pub trait ToVAO {
     fn to_vao(&self) -> VertexArrayObject;
-}
\ No newline at end of file
+}
@@ -0,0 +1,74 @@
+use std::ffi::c_void;
+use std::mem::size_of_val;
+use std::slice::from_raw_parts;
+use crate::gfx::bindings::{BufferType, DrawType, IndexType};
+use crate::gfx::bindings::buffers::*;
+use crate::gfx::objects::Buffer;
+
+///Integer types that can be used as indices of an element array buffer.
+pub trait IndexElement: Copy {
+    fn index_type() -> IndexType;
+}
+
+impl IndexElement for u8 {
+    fn index_type() -> IndexType {
+        IndexType::UnsignedByte
+    }
+}
+
+impl IndexElement for u16 {
+    fn index_type() -> IndexType {
+        IndexType::UnsignedShort
+    }
+}
+
+impl IndexElement for u32 {
+    fn index_type() -> IndexType {
+        IndexType::UnsignedInt
+    }
+}
+
+///An element array buffer. Unlike vertex buffers the indices are kept, and are uploaded once the buffer is bound to a
+///[`VertexArrayObject`](crate::gfx::objects::vertex_array_object::VertexArrayObject), which records the binding.
+///Not `Clone`, the vertex array it is bound to deletes the buffer.
+#[derive(Debug)]
+pub struct IndexBufferObject(u32, BufferType, DrawType, Vec<u8>, IndexType, i32);
+
+impl IndexBufferObject {
+    pub fn new<I: IndexElement>(draw_type: DrawType, indices: &[I]) -> IndexBufferObject {
+        let id = gen_buffers(1);
+        let bytes = unsafe { from_raw_parts(indices.as_ptr() as *const u8, size_of_val(indices)) }.to_vec();
+
+        IndexBufferObject(id, BufferType::ElementArrayBuffer, draw_type, bytes, I::index_type(), indices.len() as i32)
+    }
+
+    pub fn index_type(&self) -> IndexType {
+        self.4
+    }
+
+    pub fn count(&self) -> i32 {
+        self.5
+    }
+}
+
+impl Buffer for IndexBufferObject {
+    fn size(&self) -> isize {
+        self.3.len() as isize
+    }
+
+    fn data(&self) -> *const c_void {
+        self.3.as_ptr() as *const _
+    }
+
+    fn id(&self) -> u32 {
+        self.0
+    }
+
+    fn buffer_type(&self) -> &BufferType {
+        &self.1
+    }
+
+    fn draw_type(&self) -> &DrawType {
+        &self.2
+    }
+}
@@ -6,6 +6,7 @@ use crate::gfx::bindings::buffers::*;
 pub mod vertex_array_object_type;
 pub mod vertex_array_object;
 pub mod vertex_buffer_object;
+pub mod index_buffer_object;
 pub mod vertex_attrib_pointer;
 pub mod conversion;
 
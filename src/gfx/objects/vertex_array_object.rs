@@ -1,6 +1,6 @@
 use crate::Constructor;
 use crate::gfx::bindings::attrib_pointer::*;
-use crate::gfx::bindings::buffers::{bind_vertex_array, gen_vertex_arrays, unbind_vertex_array};
+use crate::gfx::bindings::buffers::{bind_vertex_array, delete_buffer, delete_vertex_array, gen_vertex_arrays, unbind_vertex_array};
 use crate::gfx::objects::Buffer;
 use crate::gfx::objects::vertex_array_object_type::VertexArrayObjectType;
 use crate::gfx::objects::vertex_attrib_pointer::{LocalAttribPointer, VertexAttribPointer};
@@ -55,8 +55,7 @@ impl VertexArrayObject {
 
     pub fn with_local_attrib_pointer(self, pointer: LocalAttribPointer) -> VertexArrayObject {
         let id = self.2.len();
-        let stride = *self.stride();
-        self.with_vertex_attrib_pointer(pointer.into_vap(id as u32, stride))
+        self.with_vertex_attrib_pointer(pointer.into_vap(id as u32))
     }
 
     pub fn with_vertex_attrib_pointer(mut self, pointer: VertexAttribPointer) -> VertexArrayObject {
@@ -80,8 +79,72 @@ impl VertexArrayObject {
     }
 }
 
+///Deletes the vertex array together with the buffers that were bound to it.
+impl Drop for VertexArrayObject {
+    fn drop(&mut self) {
+        for buffer in &self.1 {
+            delete_buffer(buffer.id());
+        }
+        delete_vertex_array(self.0);
+    }
+}
+
 impl Constructor<Option<VertexArrayObjectType>> for VertexArrayObject {
     fn new(_type: Option<VertexArrayObjectType>) -> Self {
         Self(gen_vertex_arrays(1), vec![], vec![], 0, _type)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gfx::backend::{restore_backend, set_backend};
+    use crate::gfx::backend::recording::{GlCall, RecordingBackend};
+    use crate::gfx::bindings::{BufferDataType, BufferType, DrawType};
+    use crate::gfx::objects::vertex_buffer_object::VertexBufferObject;
+
+    const VERTICES: [f32; 8] = [0f32, 1f32, 0f32, 1f32, 1f32, 0f32, 1f32, 0f32];
+
+    #[test]
+    fn build_points_attributes_into_the_buffer() {
+        let backend = RecordingBackend::new();
+        let log = backend.log();
+        let previous = set_backend(backend);
+
+        let vao = VertexArrayObject::new(Some(VertexArrayObjectType::ArrayStrips(2)))
+            .with_buffer(VertexBufferObject::array(BufferType::ArrayBuffer, DrawType::StaticDraw, &VERTICES))
+            .with_local_attrib_pointer(LocalAttribPointer::new(2, BufferDataType::Float, false))
+            .with_local_attrib_pointer(LocalAttribPointer::new(2, BufferDataType::Float, false));
+        assert_eq!(log.take(), vec![
+            GlCall::GenVertexArrays(1, 1),
+            GlCall::GenBuffers(1, 2),
+            GlCall::BindBuffer(gl::ARRAY_BUFFER, 2),
+            GlCall::BufferData(gl::ARRAY_BUFFER, 32, gl::STATIC_DRAW),
+            GlCall::BindVertexArray(1),
+            GlCall::BindBuffer(gl::ARRAY_BUFFER, 2),
+            GlCall::BindBuffer(gl::ARRAY_BUFFER, 2),
+            GlCall::BufferData(gl::ARRAY_BUFFER, 32, gl::STATIC_DRAW),
+            GlCall::BindVertexArray(0),
+            GlCall::BindVertexArray(1),
+            GlCall::EnableVertexAttribArray(0),
+            GlCall::BindVertexArray(0),
+            GlCall::BindVertexArray(1),
+            GlCall::EnableVertexAttribArray(1),
+            GlCall::BindVertexArray(0),
+        ]);
+
+        //Interleaved: the second attribute starts after the two floats of the first.
+        let vao = vao.build();
+        assert_eq!(*vao.stride(), 16);
+        assert_eq!(log.take(), vec![
+            GlCall::BindVertexArray(1),
+            GlCall::VertexAttribPointer(0, 2, gl::FLOAT, false, 16, 0),
+            GlCall::EnableVertexAttribArray(0),
+            GlCall::VertexAttribPointer(1, 2, gl::FLOAT, false, 16, 8),
+            GlCall::EnableVertexAttribArray(1),
+        ]);
+
+        drop(vao);
+        assert_eq!(log.take(), vec![GlCall::DeleteBuffers(2), GlCall::DeleteVertexArrays(1)]);
+        restore_backend(previous);
+    }
+}
@@ -1,5 +1,9 @@
+use crate::gfx::bindings::IndexType;
+
 #[derive(Debug, Clone)]
 pub enum VertexArrayObjectType {
     Arrays(i32),
-    ArrayStrips(i32)
-}
\ No newline at end of file
+    ArrayStrips(i32),
+    ///Indexed triangles: index count and the type of the bound element array buffer.
+    Elements(i32, IndexType)
+}
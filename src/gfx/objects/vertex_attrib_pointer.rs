@@ -9,8 +9,9 @@ pub struct LocalAttribPointer {
 }
 
 impl LocalAttribPointer {
-    pub fn into_vap(self, id: u32, stride: isize) -> VertexAttribPointer {
-        VertexAttribPointer::new(id, self.data_len, self.buffer_data_type, self.normalized, stride)
+    pub fn into_vap(self, id: u32) -> VertexAttribPointer {
+        let size = (self.data_len as usize * self.buffer_data_type.size()) as isize;
+        VertexAttribPointer::new(id, self.data_len, self.buffer_data_type, self.normalized, size)
     }
     pub fn new(data_len: u32, buffer_data_type: BufferDataType, normalized: bool) -> Self { Self { data_len, buffer_data_type, normalized } }
     pub fn data_len(&self) -> u32 {
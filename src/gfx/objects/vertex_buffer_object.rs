@@ -5,7 +5,8 @@ use crate::gfx::bindings::buffers::*;
 use crate::gfx::objects::Buffer;
 use crate::utils::{flatten_3};
 
-#[derive(Debug, Clone)]
+///Not `Clone`, the vertex array it is bound to deletes the buffer.
+#[derive(Debug)]
 pub struct VertexBufferObject(u32, BufferType, DrawType, isize, *const c_void);
 
 impl VertexBufferObject {
@@ -1,10 +1,12 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::slice;
 use vecmath::{Matrix4, Vector2, Vector4};
 use crate::gfx::bindings::IV;
-use crate::gfx::bindings::program::{attach_shader, create_program, disable_program, link_program, program_iv, use_program};
+use crate::gfx::bindings::program::{attach_shader, create_program, delete_program, disable_program, link_program, program_iv, use_program};
 use crate::gfx::bindings::uniforms::{uniform_1f, uniform_1i, uniform_1ui, uniform_2fv, uniform_4fv, uniform_location, uniform_matrix4fv};
 use crate::gfx::shader::Shader;
+use crate::gfx::texture::texture_unit::TextureUnit;
 use super::shader::{fragment_shader::FragmentShader, vertex_shader::VertexShader};
 
 static mut CURRENT_PROGRAM: Option<Program> = None;
@@ -34,10 +36,13 @@ impl Program {
         let id = create_program();
         let program = Program(id);
 
+        //Failed programs are deleted, so rebuilding a broken shader over and over does not leak them.
         if let Err(error) = fragment_shader.compilation_status() {
+            program.delete();
             return Err(Box::new(error));
         }
         if let Err(error) = vertex_shader.compilation_status() {
+            program.delete();
             return Err(Box::new(error));
         }
 
@@ -48,6 +53,7 @@ impl Program {
         let status = program.link_status();
         if !status {
             let status = program.status();
+            program.delete();
             return Err(Box::new(ProgramError::ProgramLinkError(status.to_string())));
         }
         Ok(program)
@@ -70,6 +76,10 @@ impl Program {
         unsafe { CURRENT_PROGRAM = None };
         disable_program()
     }
+    ///Frees the GL program. `Program` is `Copy`, so the owner has to make sure no copies are used afterwards.
+    pub fn delete(&self) {
+        delete_program(self);
+    }
 
     pub fn uniform<T: ToString>(&self, uniform: T) -> i32 {
         uniform_location(self, uniform)
@@ -101,17 +111,22 @@ impl Program {
 
     pub fn set_uniform_vec2<T: ToString>(&self, uniform: T, value: &Vector2<f32>) {
         let location = self.uniform(uniform);
-        uniform_2fv(location, 1, value);
+        uniform_2fv(location, slice::from_ref(value));
     }
 
     pub fn set_uniform_vec4<T: ToString>(&self, uniform: T, value: &Vector4<f32>) {
         let location = self.uniform(uniform);
-        uniform_4fv(location, 1, value);
+        uniform_4fv(location, slice::from_ref(value));
+    }
+
+    ///Points a `sampler2D` uniform at a texture unit.
+    pub fn set_uniform_sampler<T: ToString>(&self, uniform: T, unit: &TextureUnit) {
+        self.set_uniform_int(uniform, &(unit.index() as i32));
     }
 
     pub fn set_uniform_mat4<T: ToString>(&self, uniform: T, value: &Matrix4<f32>) {
         let location = self.uniform(uniform);
-        uniform_matrix4fv(location, 1, false, value);
+        uniform_matrix4fv(location, false, slice::from_ref(value));
     }
 
     pub fn current_program<F>(f: F) where F: Fn(&'static Program) {
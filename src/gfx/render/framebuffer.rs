@@ -0,0 +1,197 @@
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::gfx::bindings::{Attachment, FramebufferTarget, RenderbufferFormat, TextureFilter, TextureTarget};
+use crate::gfx::bindings::framebuffers::*;
+use crate::gfx::bindings::graphics::{get_viewport, viewport};
+use crate::gfx::image::Image;
+use crate::gfx::render::readback::capture_framebuffer;
+use crate::gfx::render::renderbuffer::Renderbuffer;
+use crate::gfx::texture::PixelFormat;
+use crate::gfx::texture::texture_2d::Texture2D;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FramebufferError {
+    Undefined,
+    IncompleteAttachment,
+    MissingAttachment,
+    IncompleteDrawBuffer,
+    IncompleteReadBuffer,
+    Unsupported,
+    IncompleteMultisample,
+    IncompleteLayerTargets,
+    Unknown(u32),
+}
+
+impl FramebufferError {
+    ///Maps the result of `glCheckFramebufferStatus`, `None` meaning complete.
+    pub fn from_status(status: u32) -> Option<FramebufferError> {
+        match status {
+            gl::FRAMEBUFFER_COMPLETE => None,
+            gl::FRAMEBUFFER_UNDEFINED => Some(FramebufferError::Undefined),
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => Some(FramebufferError::IncompleteAttachment),
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => Some(FramebufferError::MissingAttachment),
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => Some(FramebufferError::IncompleteDrawBuffer),
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => Some(FramebufferError::IncompleteReadBuffer),
+            gl::FRAMEBUFFER_UNSUPPORTED => Some(FramebufferError::Unsupported),
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => Some(FramebufferError::IncompleteMultisample),
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => Some(FramebufferError::IncompleteLayerTargets),
+            status => Some(FramebufferError::Unknown(status))
+        }
+    }
+}
+
+impl Display for FramebufferError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FramebufferError::Unknown(status) => f.write_fmt(format_args!("Framebuffer incomplete: unknown status 0x{:X}", status)),
+            error => f.write_fmt(format_args!("Framebuffer incomplete: {:?}", error))
+        }
+    }
+}
+
+impl Error for FramebufferError {}
+
+///What `bind` replaced, so `unbind` can put it back.
+#[derive(Debug, Clone, Copy)]
+struct BindState {
+    framebuffer: u32,
+    viewport: ([i32; 2], [i32; 2]),
+}
+
+///An offscreen render target with texture color attachments and an optional depth/stencil renderbuffer.
+///
+///```ignore
+///let target = Framebuffer::new([256, 256])
+///    .with_color_attachment(PixelFormat::Rgba8)
+///    .with_depth_stencil(RenderbufferFormat::Depth24Stencil8)
+///    .build()?;
+///```
+#[derive(Debug)]
+pub struct Framebuffer {
+    id: u32,
+    size: [u32; 2],
+    color_attachments: Vec<Texture2D>,
+    depth_stencil: Option<Renderbuffer>,
+    previous: Cell<Option<BindState>>,
+}
+
+impl Framebuffer {
+    pub fn new(size: [u32; 2]) -> Framebuffer {
+        Self {
+            id: gen_framebuffers(1),
+            size,
+            color_attachments: vec![],
+            depth_stencil: None,
+            previous: Cell::new(None),
+        }
+    }
+
+    ///Runs `f` with this framebuffer bound, without touching the viewport.
+    fn attach<F>(&self, f: F) where F: FnOnce() {
+        let previous = bound_framebuffer();
+        bind_framebuffer(FramebufferTarget::Framebuffer, self.id);
+        f();
+        bind_framebuffer(FramebufferTarget::Framebuffer, previous);
+    }
+
+    ///Adds a color attachment backed by a new texture of the framebuffer's size.
+    pub fn with_color_attachment(mut self, format: PixelFormat) -> Framebuffer {
+        let texture = Texture2D::empty(self.size, format);
+        texture.unbind();
+        let attachment = Attachment::Color(self.color_attachments.len() as u32);
+        self.attach(|| framebuffer_texture_2d(FramebufferTarget::Framebuffer, attachment, TextureTarget::Texture2D, texture.id(), 0));
+        self.color_attachments.push(texture);
+        self
+    }
+
+    pub fn with_depth_stencil(mut self, format: RenderbufferFormat) -> Framebuffer {
+        let renderbuffer = Renderbuffer::new(format, self.size);
+        self.attach(|| framebuffer_renderbuffer(FramebufferTarget::Framebuffer, format.attachment(), renderbuffer.id()));
+        self.depth_stencil = Some(renderbuffer);
+        self
+    }
+
+    ///Enables drawing into every color attachment and checks completeness.
+    pub fn build(self) -> Result<Framebuffer, FramebufferError> {
+        let attachments: Vec<Attachment> = (0..self.color_attachments.len()).map(|index| Attachment::Color(index as u32)).collect();
+        let mut status = gl::FRAMEBUFFER_COMPLETE;
+        self.attach(|| {
+            draw_buffers(&attachments);
+            status = check_framebuffer_status(FramebufferTarget::Framebuffer);
+        });
+        match FramebufferError::from_status(status) {
+            None => Ok(self),
+            Some(error) => Err(error)
+        }
+    }
+
+    ///Redirects rendering into this framebuffer and sets the viewport to cover it.
+    pub fn bind(&self) {
+        self.previous.set(Some(BindState {
+            framebuffer: bound_framebuffer(),
+            viewport: get_viewport(),
+        }));
+        bind_framebuffer(FramebufferTarget::Framebuffer, self.id);
+        viewport([0, 0], [self.size[0] as i32, self.size[1] as i32]);
+    }
+
+    ///Restores the framebuffer and viewport that were active when `bind` was called.
+    pub fn unbind(&self) {
+        match self.previous.take() {
+            None => bind_framebuffer(FramebufferTarget::Framebuffer, 0),
+            Some(state) => {
+                bind_framebuffer(FramebufferTarget::Framebuffer, state.framebuffer);
+                viewport(state.viewport.0, state.viewport.1);
+            }
+        }
+    }
+
+    ///Binds the framebuffer for the duration of `draw`.
+    pub fn render<F>(&self, draw: F) where F: FnOnce() {
+        self.bind();
+        draw();
+        self.unbind();
+    }
+
+    ///Copies the first color attachment into `target`, or the window when `None`. `destination` is [x0, y0, x1, y1].
+    pub fn blit_to(&self, target: Option<&Framebuffer>, destination: [i32; 4], mask: u32, filter: TextureFilter) {
+        self.blit_color_attachment_to(0, target, destination, mask, filter);
+    }
+
+    pub fn blit_color_attachment_to(&self, index: u32, target: Option<&Framebuffer>, destination: [i32; 4], mask: u32, filter: TextureFilter) {
+        let previous = bound_framebuffer();
+        bind_framebuffer(FramebufferTarget::ReadFramebuffer, self.id);
+        bind_framebuffer(FramebufferTarget::DrawFramebuffer, target.map(|target| target.id).unwrap_or(0));
+        read_buffer(Attachment::Color(index));
+        blit_framebuffer([0, 0, self.size[0] as i32, self.size[1] as i32], destination, mask, filter);
+        bind_framebuffer(FramebufferTarget::Framebuffer, previous);
+    }
+
+    ///Reads back a color attachment in top-down row order.
+    pub fn read_pixels(&self, index: u32) -> Image {
+        capture_framebuffer(self, index)
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+    pub fn color_attachments(&self) -> &Vec<Texture2D> {
+        &self.color_attachments
+    }
+    pub fn color_attachment(&self, index: usize) -> Option<&Texture2D> {
+        self.color_attachments.get(index)
+    }
+    pub fn depth_stencil(&self) -> &Option<Renderbuffer> {
+        &self.depth_stencil
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        delete_framebuffer(self.id);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod framebuffer;
+pub mod renderbuffer;
+pub mod readback;
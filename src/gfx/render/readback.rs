@@ -0,0 +1,40 @@
+use std::path::Path;
+use crate::gfx::bindings::{Attachment, FramebufferTarget, PixelType};
+use crate::gfx::bindings::framebuffers::{bind_framebuffer, bound_framebuffer, read_buffer, read_pixels};
+use crate::gfx::image::{encoder, Image, ImageError};
+use crate::gfx::render::framebuffer::Framebuffer;
+
+///Reads a region of the current read framebuffer and returns it in top-down row order.
+pub fn read_region(position: [i32; 2], size: [u32; 2]) -> Image {
+    let mut bytes = vec![0u8; size[0] as usize * size[1] as usize * 4];
+    read_pixels(position, [size[0] as i32, size[1] as i32], gl::RGBA, PixelType::UnsignedByte, &mut bytes);
+    let mut image = Image::from_bytes(size, &bytes);
+    image.flip_vertically();
+    image
+}
+
+///Captures the back buffer of the window. Call it after drawing and before swapping buffers.
+pub fn capture_window(size: [u32; 2]) -> Image {
+    let previous = bound_framebuffer();
+    bind_framebuffer(FramebufferTarget::Framebuffer, 0);
+    let image = read_region([0, 0], size);
+    bind_framebuffer(FramebufferTarget::Framebuffer, previous);
+    image
+}
+
+///Captures a color attachment of `framebuffer`.
+pub fn capture_framebuffer(framebuffer: &Framebuffer, attachment: u32) -> Image {
+    let previous = bound_framebuffer();
+    bind_framebuffer(FramebufferTarget::ReadFramebuffer, framebuffer.id());
+    read_buffer(Attachment::Color(attachment));
+    let image = read_region([0, 0], framebuffer.size());
+    bind_framebuffer(FramebufferTarget::Framebuffer, previous);
+    image
+}
+
+///Captures the window and saves it as PNG or PPM, depending on the extension.
+pub fn screenshot<P: AsRef<Path>>(size: [u32; 2], path: P) -> Result<Image, ImageError> {
+    let image = capture_window(size);
+    encoder::save(&image, path)?;
+    Ok(image)
+}
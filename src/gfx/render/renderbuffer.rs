@@ -0,0 +1,36 @@
+use crate::gfx::bindings::RenderbufferFormat;
+use crate::gfx::bindings::framebuffers::{bind_renderbuffer, delete_renderbuffer, gen_renderbuffers, renderbuffer_storage};
+
+///Storage for attachments that are rendered to but never sampled, such as depth and stencil.
+#[derive(Debug)]
+pub struct Renderbuffer(u32, RenderbufferFormat, [u32; 2]);
+
+impl Renderbuffer {
+    pub fn new(format: RenderbufferFormat, size: [u32; 2]) -> Renderbuffer {
+        let renderbuffer = Renderbuffer(gen_renderbuffers(1), format, size);
+        renderbuffer.bind();
+        renderbuffer_storage(format, [size[0] as i32, size[1] as i32]);
+        bind_renderbuffer(0);
+        renderbuffer
+    }
+
+    pub fn bind(&self) {
+        bind_renderbuffer(self.0);
+    }
+
+    pub fn id(&self) -> u32 {
+        self.0
+    }
+    pub fn format(&self) -> RenderbufferFormat {
+        self.1
+    }
+    pub fn size(&self) -> [u32; 2] {
+        self.2
+    }
+}
+
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        delete_renderbuffer(self.0);
+    }
+}
@@ -5,6 +5,7 @@ use crate::gfx::shader::error::Error;
 pub mod fragment_shader;
 pub mod vertex_shader;
 pub mod error;
+pub mod watcher;
 
 pub type ShaderResult = Result<(), Error>;
 
@@ -0,0 +1,148 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::{Constructor, FragmentShader, Program, VertexShader};
+use crate::utils::file_watcher::FileWatcher;
+
+///Where `ShaderWatcher::new` looks for changed shaders.
+pub const DEFAULT_SHADER_DIRECTORY: &'static str = "res/shaders";
+
+///GLSL source of one shader stage.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShaderSource {
+    ///Compiled into the binary, never changes.
+    Embedded(String),
+    ///Read when the program is built.
+    File(PathBuf),
+}
+
+impl ShaderSource {
+    pub fn embedded<T: ToString>(source: T) -> ShaderSource {
+        ShaderSource::Embedded(source.to_string())
+    }
+
+    pub fn file<P: AsRef<Path>>(path: P) -> ShaderSource {
+        ShaderSource::File(path.as_ref().to_path_buf())
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ShaderSource::Embedded(_) => None,
+            ShaderSource::File(path) => Some(path)
+        }
+    }
+
+    pub fn read(&self) -> Result<String, Box<dyn Error>> {
+        match self {
+            ShaderSource::Embedded(source) => Ok(source.clone()),
+            ShaderSource::File(path) => fs::read_to_string(path)
+                .map_err(|error| format!("{}: {}", path.display(), error).into())
+        }
+    }
+}
+
+///Vertex and fragment source of a program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramSources {
+    vertex: ShaderSource,
+    fragment: ShaderSource,
+}
+
+impl ProgramSources {
+    pub fn new(vertex: ShaderSource, fragment: ShaderSource) -> ProgramSources {
+        Self { vertex, fragment }
+    }
+
+    pub fn vertex(&self) -> &ShaderSource {
+        &self.vertex
+    }
+
+    pub fn fragment(&self) -> &ShaderSource {
+        &self.fragment
+    }
+
+    ///Reads, compiles and links both stages. Fails with a shader `CompilationError`, a `ProgramLinkError` or
+    ///an IO error. Requires a current GL context.
+    pub fn compile(&self) -> Result<Program, Box<dyn Error>> {
+        let vertex = self.vertex.read()?;
+        let fragment = self.fragment.read()?;
+        Program::new(FragmentShader::new(fragment), VertexShader::new(vertex))
+    }
+
+    ///Whether one of the stages is read from `path`.
+    pub fn depends_on(&self, path: &Path) -> bool {
+        [&self.vertex, &self.fragment].iter()
+            .filter_map(|source| source.path())
+            .any(|source| same_file(source, path))
+    }
+}
+
+///Rebuilds programs whose shader files changed. The watcher does not own the programs: rebuilt ones are handed
+///to the caller to install, e.g. with `UiContext::set_default_program`, and to delete once replaced.
+#[derive(Debug, Clone)]
+pub struct ShaderWatcher {
+    files: FileWatcher,
+    programs: Vec<(String, ProgramSources)>,
+}
+
+impl ShaderWatcher {
+    ///Watches the `.glsl` files below `res/shaders`.
+    pub fn new() -> ShaderWatcher {
+        Self::with_directory(DEFAULT_SHADER_DIRECTORY)
+    }
+
+    pub fn with_directory<P: AsRef<Path>>(directory: P) -> ShaderWatcher {
+        Self { files: FileWatcher::new().with_directory(directory, Some("glsl")), programs: vec![] }
+    }
+
+    pub fn with_program<T: ToString>(mut self, name: T, sources: ProgramSources) -> ShaderWatcher {
+        self.add(name, sources);
+        self
+    }
+
+    ///Rebuilds the program named `name` whenever one of its source files changes, also outside the watched
+    ///directory. Replaces a program added under the same name.
+    pub fn add<T: ToString>(&mut self, name: T, sources: ProgramSources) {
+        let name = name.to_string();
+        for path in [sources.vertex(), sources.fragment()].iter().filter_map(|source| source.path()) {
+            self.files.watch(path);
+        }
+        self.programs.retain(|(other, _)| *other != name);
+        self.programs.push((name, sources));
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.programs.retain(|(other, _)| other != name);
+    }
+
+    pub fn sources(&self, name: &str) -> Option<&ProgramSources> {
+        self.programs.iter().find(|(other, _)| other == name).map(|(_, sources)| sources)
+    }
+
+    ///Rebuilds every program depending on a file changed since the last poll and returns them by name. A
+    ///program that fails to build is reported with its error, the caller keeps using the old one.
+    pub fn poll(&mut self) -> Vec<(String, Result<Program, Box<dyn Error>>)> {
+        let changed = self.files.changed();
+        if changed.is_empty() {
+            return vec![];
+        }
+        self.programs.iter()
+            .filter(|(_, sources)| changed.iter().any(|path| sources.depends_on(path)))
+            .map(|(name, sources)| (name.clone(), sources.compile()))
+            .collect()
+    }
+}
+
+impl Default for ShaderWatcher {
+    fn default() -> Self {
+        ShaderWatcher::new()
+    }
+}
+
+///Compares canonical paths where they exist, so `res/shaders/ui/../ui/frag.glsl` matches `res/shaders/ui/frag.glsl`.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b
+    }
+}
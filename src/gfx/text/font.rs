@@ -0,0 +1,69 @@
+use std::fmt::{Debug, Formatter};
+use std::fs;
+use std::path::Path;
+use ab_glyph::{Font as _, FontArc, GlyphId, PxScale, ScaleFont};
+use crate::gfx::text::{DEFAULT_FONT_DIRECTORY, FontError};
+
+///A parsed TrueType/OpenType font. Cloning is cheap, the font data is shared.
+#[derive(Clone)]
+pub struct Font {
+    name: String,
+    font: FontArc,
+}
+
+impl Debug for Font {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Font [name: {}, glyphs: {}]", self.name, self.font.glyph_count()))
+    }
+}
+
+impl Font {
+    pub fn from_bytes<T: ToString>(name: T, bytes: Vec<u8>) -> Result<Font, FontError> {
+        let font = FontArc::try_from_vec(bytes).map_err(|error| FontError::Parse(error.to_string()))?;
+        Ok(Self { name: name.to_string(), font })
+    }
+
+    ///Loads a font file, naming the font after the file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Font, FontError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|error| FontError::Io(format!("{}: {}", path.display(), error)))?;
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        Self::from_bytes(name, bytes)
+    }
+
+    ///Loads `file_name` from `res/fonts`.
+    pub fn load_default(file_name: &str) -> Result<Font, FontError> {
+        Self::load(Path::new(DEFAULT_FONT_DIRECTORY).join(file_name))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn glyph_id(&self, character: char) -> GlyphId {
+        self.font.glyph_id(character)
+    }
+
+    ///Distance from the baseline to the top of the tallest glyphs at `size` pixels.
+    pub fn ascent(&self, size: f32) -> f32 {
+        self.font.as_scaled(PxScale::from(size)).ascent()
+    }
+
+    ///Distance from one baseline to the next at `size` pixels.
+    pub fn line_height(&self, size: f32) -> f32 {
+        let font = self.font.as_scaled(PxScale::from(size));
+        font.height() + font.line_gap()
+    }
+
+    pub fn advance(&self, glyph: GlyphId, size: f32) -> f32 {
+        self.font.as_scaled(PxScale::from(size)).h_advance(glyph)
+    }
+
+    pub fn kerning(&self, left: GlyphId, right: GlyphId, size: f32) -> f32 {
+        self.font.as_scaled(PxScale::from(size)).kern(left, right)
+    }
+
+    pub(crate) fn inner(&self) -> &FontArc {
+        &self.font
+    }
+}
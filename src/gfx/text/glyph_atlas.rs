@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use ab_glyph::{Font as _, GlyphId, point, PxScale};
+use crate::gfx::text::FontError;
+use crate::gfx::text::font::Font;
+use crate::gfx::texture::PixelFormat;
+use crate::gfx::texture::texture_2d::Texture2D;
+
+pub const DEFAULT_ATLAS_SIZE: [u32; 2] = [1024, 1024];
+///Empty pixels around every glyph, so linear filtering does not bleed neighbours in.
+const PADDING: u32 = 1;
+
+///Where a rasterised glyph lives in the atlas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasGlyph {
+    ///Top left and bottom right texture coordinates.
+    pub uv: [f32; 4],
+    ///Size of the bitmap in pixels.
+    pub size: [u32; 2],
+    ///Offset of the top left corner of the bitmap from the pen position on the baseline, y pointing down.
+    pub offset: [f32; 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: String,
+    glyph: u16,
+    size: u32,
+}
+
+///Packs rectangles into rows ("shelves") of a fixed area, leaving `PADDING` pixels between them.
+#[derive(Debug, Clone)]
+pub(crate) struct ShelfPacker {
+    size: [u32; 2],
+    cursor: [u32; 2],
+    shelf_height: u32,
+}
+
+impl ShelfPacker {
+    pub(crate) fn new(size: [u32; 2]) -> ShelfPacker {
+        Self { size, cursor: [PADDING; 2], shelf_height: 0 }
+    }
+
+    ///Top left corner of the allocated area.
+    pub(crate) fn allocate(&mut self, size: [u32; 2]) -> Result<[u32; 2], FontError> {
+        if self.cursor[0] + size[0] + PADDING > self.size[0] {
+            //Start a new shelf below the tallest rectangle of the current one.
+            self.cursor = [PADDING, self.cursor[1] + self.shelf_height + PADDING];
+            self.shelf_height = 0;
+        }
+        if self.cursor[0] + size[0] + PADDING > self.size[0] || self.cursor[1] + size[1] + PADDING > self.size[1] {
+            return Err(FontError::AtlasFull(self.size));
+        }
+        let position = self.cursor;
+        self.cursor[0] += size[0] + PADDING;
+        self.shelf_height = self.shelf_height.max(size[1]);
+        Ok(position)
+    }
+}
+
+///Single channel coverage texture that glyphs are rasterised into on first use.
+#[derive(Debug)]
+pub struct GlyphAtlas {
+    size: [u32; 2],
+    pixels: Vec<u8>,
+    texture: Texture2D,
+    //`None` for glyphs without an outline, such as spaces.
+    glyphs: HashMap<GlyphKey, Option<AtlasGlyph>>,
+    packer: ShelfPacker,
+    dirty: bool,
+}
+
+impl GlyphAtlas {
+    pub fn new(size: [u32; 2]) -> GlyphAtlas {
+        let pixels = vec![0u8; size[0] as usize * size[1] as usize];
+        let texture = Texture2D::new(size, PixelFormat::R8, &pixels).unwrap();
+        Self {
+            size,
+            pixels,
+            texture,
+            glyphs: Default::default(),
+            packer: ShelfPacker::new(size),
+            dirty: false,
+        }
+    }
+
+    ///Looks up the glyph at `size` pixels, rasterising it if needed. Call `flush` before drawing.
+    pub fn glyph(&mut self, font: &Font, glyph: GlyphId, size: f32) -> Result<Option<AtlasGlyph>, FontError> {
+        let key = GlyphKey { font: font.name().to_string(), glyph: glyph.0, size: size.to_bits() };
+        if let Some(entry) = self.glyphs.get(&key) {
+            return Ok(*entry);
+        }
+
+        let outlined = match font.inner().outline_glyph(glyph.with_scale_and_position(PxScale::from(size), point(0f32, 0f32))) {
+            None => {
+                self.glyphs.insert(key, None);
+                return Ok(None);
+            }
+            Some(outlined) => outlined
+        };
+        let bounds = outlined.px_bounds();
+        let glyph_size = [bounds.width().ceil() as u32, bounds.height().ceil() as u32];
+        let position = self.packer.allocate(glyph_size)?;
+
+        let width = self.size[0] as usize;
+        let pixels = &mut self.pixels;
+        outlined.draw(|x, y, coverage| {
+            let index = (position[1] + y) as usize * width + (position[0] + x) as usize;
+            pixels[index] = (coverage.min(1f32) * 255f32) as u8;
+        });
+        self.dirty = true;
+
+        let entry = AtlasGlyph {
+            uv: [
+                position[0] as f32 / self.size[0] as f32,
+                position[1] as f32 / self.size[1] as f32,
+                (position[0] + glyph_size[0]) as f32 / self.size[0] as f32,
+                (position[1] + glyph_size[1]) as f32 / self.size[1] as f32,
+            ],
+            size: glyph_size,
+            offset: [bounds.min.x, bounds.min.y],
+        };
+        self.glyphs.insert(key, Some(entry));
+        Ok(Some(entry))
+    }
+
+    ///Uploads glyphs rasterised since the last flush.
+    pub fn flush(&mut self) {
+        if self.dirty {
+            self.texture.upload(&self.pixels).unwrap();
+            self.dirty = false;
+        }
+    }
+
+    ///Forgets every glyph. Meshes built before have to be rebuilt.
+    pub fn clear(&mut self) {
+        self.pixels.iter_mut().for_each(|pixel| *pixel = 0);
+        self.glyphs.clear();
+        self.packer = ShelfPacker::new(self.size);
+        self.dirty = true;
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+    pub fn texture(&self) -> &Texture2D {
+        &self.texture
+    }
+}
@@ -0,0 +1,102 @@
+use ab_glyph::GlyphId;
+use crate::gfx::text::TextAlignment;
+use crate::gfx::text::font::Font;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedGlyph {
+    pub glyph: GlyphId,
+    pub character: char,
+    ///Pen position on the baseline, measured from the top left of the text block with y pointing down.
+    pub position: [f32; 2],
+    pub advance: f32,
+}
+
+///Glyphs of a string broken into lines, kerned and aligned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    glyphs: Vec<PositionedGlyph>,
+    size: [f32; 2],
+    lines: usize,
+}
+
+impl TextLayout {
+    ///Lays out `text` at `size` pixels. Lines break at `\n` and, if `max_width` is set, between words that would not fit.
+    ///Lines are aligned within `max_width`, or within the widest line when there is none.
+    pub fn new(font: &Font, text: &str, size: f32, alignment: TextAlignment, max_width: Option<f32>) -> TextLayout {
+        let mut lines: Vec<Vec<PositionedGlyph>> = vec![];
+        for paragraph in text.split('\n') {
+            let mut line: Vec<PositionedGlyph> = vec![];
+            let mut x = 0f32;
+            let mut previous: Option<GlyphId> = None;
+            //Index in `line` of the first glyph after the last whitespace.
+            let mut last_break: Option<usize> = None;
+
+            for character in paragraph.chars() {
+                let glyph = font.glyph_id(character);
+                if let Some(previous) = previous {
+                    x += font.kerning(previous, glyph, size);
+                }
+                let advance = font.advance(glyph, size);
+
+                if let (Some(max_width), Some(index)) = (max_width, last_break) {
+                    if !character.is_whitespace() && x + advance > max_width && index < line.len() {
+                        let mut rest = line.split_off(index);
+                        let shift = rest[0].position[0];
+                        rest.iter_mut().for_each(|glyph| glyph.position[0] -= shift);
+                        x -= shift;
+                        lines.push(line);
+                        line = rest;
+                        last_break = None;
+                    }
+                }
+
+                line.push(PositionedGlyph { glyph, character, position: [x, 0f32], advance });
+                x += advance;
+                previous = Some(glyph);
+                if character.is_whitespace() {
+                    last_break = Some(line.len());
+                }
+            }
+            lines.push(line);
+        }
+
+        let widths: Vec<f32> = lines.iter().map(|line| line_width(line)).collect();
+        let width = max_width.unwrap_or(widths.iter().cloned().fold(0f32, f32::max));
+        let ascent = font.ascent(size);
+        let line_height = font.line_height(size);
+
+        let mut glyphs = vec![];
+        for (index, line) in lines.iter().enumerate() {
+            let offset = ((width - widths[index]) * alignment.factor()).max(0f32);
+            let baseline = ascent + index as f32 * line_height;
+            for glyph in line {
+                glyphs.push(PositionedGlyph { position: [glyph.position[0] + offset, baseline], ..*glyph });
+            }
+        }
+
+        Self {
+            glyphs,
+            size: [width, lines.len() as f32 * line_height],
+            lines: lines.len(),
+        }
+    }
+
+    pub fn glyphs(&self) -> &Vec<PositionedGlyph> {
+        &self.glyphs
+    }
+    ///Width and height of the text block.
+    pub fn size(&self) -> [f32; 2] {
+        self.size
+    }
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+}
+
+///Trailing whitespace does not count towards the width.
+fn line_width(line: &Vec<PositionedGlyph>) -> f32 {
+    line.iter().rev()
+        .find(|glyph| !glyph.character.is_whitespace())
+        .map(|glyph| glyph.position[0] + glyph.advance)
+        .unwrap_or(0f32)
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+pub mod font;
+pub mod glyph_atlas;
+pub mod layout;
+pub mod renderer;
+pub mod sdf;
+
+///Where `Font::load_default` looks for font files.
+pub const DEFAULT_FONT_DIRECTORY: &'static str = "res/fonts";
+///Used by text that does not name a font, when no other font was added before.
+pub const DEFAULT_FONT: &'static str = "mplus-1p-regular.ttf";
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(String),
+    Parse(String),
+    UnknownFont(String),
+    ///Size of the atlas that could not fit another glyph.
+    AtlasFull([u32; 2]),
+    Program(String),
+    InvalidCache(String),
+}
+
+impl Display for FontError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontError::Io(error) => f.write_fmt(format_args!("IO error: {}", error)),
+            FontError::Parse(error) => f.write_fmt(format_args!("Invalid font: {}", error)),
+            FontError::UnknownFont(name) => f.write_fmt(format_args!("No font named {} was added", name)),
+            FontError::AtlasFull(size) => f.write_fmt(format_args!("The {}x{} glyph atlas is full", size[0], size[1])),
+            FontError::Program(error) => f.write_fmt(format_args!("Could not build the text program: {}", error)),
+            FontError::InvalidCache(error) => f.write_fmt(format_args!("Invalid SDF atlas cache: {}", error)),
+        }
+    }
+}
+
+impl Error for FontError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlignment {
+    ///Share of the free horizontal space that goes to the left of a line.
+    pub fn factor(&self) -> f32 {
+        match self {
+            TextAlignment::Left => 0f32,
+            TextAlignment::Center => 0.5f32,
+            TextAlignment::Right => 1f32,
+        }
+    }
+}
+
+impl Default for TextAlignment {
+    fn default() -> Self {
+        Self::Left
+    }
+}
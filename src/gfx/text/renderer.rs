@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use vecmath::{Matrix4, Vector4};
+use crate::{BufferDataType, BufferType, Constructor, DrawMode, DrawType, FragmentShader, LocalAttribPointer, Program, VertexArrayObject, VertexArrayObjectType, VertexBufferObject, VertexShader};
+use crate::gfx::bindings::graphics::draw_arrays;
+use crate::gfx::shader::watcher::{ProgramSources, ShaderSource};
+use crate::gfx::text::{DEFAULT_FONT, FontError, TextAlignment};
+use crate::gfx::text::font::Font;
+use crate::gfx::text::glyph_atlas::{DEFAULT_ATLAS_SIZE, GlyphAtlas};
+use crate::gfx::text::layout::TextLayout;
+use crate::gfx::text::sdf::SdfAtlas;
+use crate::gfx::texture::texture_2d::Texture2D;
+use crate::gfx::texture::texture_unit::TextureUnit;
+
+const TEXT_VERTEX_SHADER: &'static str = include_str!("../../../res/shaders/ui/text_vert.glsl");
+const TEXT_FRAGMENT_SHADER: &'static str = include_str!("../../../res/shaders/ui/text_frag.glsl");
+const SDF_FRAGMENT_SHADER: &'static str = include_str!("../../../res/shaders/ui/sdf_frag.glsl");
+///Source files of the shaders above, relative to the working directory.
+const TEXT_VERTEX_SHADER_PATH: &'static str = "res/shaders/ui/text_vert.glsl";
+const TEXT_FRAGMENT_SHADER_PATH: &'static str = "res/shaders/ui/text_frag.glsl";
+const SDF_FRAGMENT_SHADER_PATH: &'static str = "res/shaders/ui/sdf_frag.glsl";
+
+///Geometry of a laid out string: two triangles per visible glyph, in pixels relative to the bottom left of the text block.
+#[derive(Debug)]
+pub struct TextMesh {
+    vao: Option<VertexArrayObject>,
+    vertices: i32,
+    size: [f32; 2],
+    ///Font of the SDF atlas the mesh samples, `None` for the coverage atlas.
+    sdf: Option<String>,
+}
+
+impl TextMesh {
+    pub fn size(&self) -> [f32; 2] {
+        self.size
+    }
+    pub fn vertices(&self) -> i32 {
+        self.vertices
+    }
+    pub fn is_sdf(&self) -> bool {
+        self.sdf.is_some()
+    }
+}
+
+#[derive(Debug)]
+struct SdfFont {
+    atlas: SdfAtlas,
+    texture: Texture2D,
+}
+
+///Fonts, the glyph atlas they share and the program text is drawn with.
+///The atlas and program are created on first use, so a renderer can be set up before a GL context exists.
+#[derive(Debug)]
+pub struct TextRenderer {
+    fonts: HashMap<String, Font>,
+    default_font: Option<String>,
+    atlas: Option<GlyphAtlas>,
+    program: Option<Program>,
+    sdf_fonts: HashMap<String, SdfFont>,
+    sdf_program: Option<Program>,
+}
+
+impl TextRenderer {
+    pub fn new() -> TextRenderer {
+        Self {
+            fonts: Default::default(),
+            default_font: None,
+            atlas: None,
+            program: None,
+            sdf_fonts: Default::default(),
+            sdf_program: None,
+        }
+    }
+
+    ///Makes `font` available under its name. The first font added becomes the default.
+    pub fn add_font(&mut self, font: Font) -> String {
+        let name = font.name().to_string();
+        if self.default_font.is_none() {
+            self.default_font = Some(name.clone());
+        }
+        self.fonts.insert(name.clone(), font);
+        name
+    }
+
+    pub fn set_default_font<T: ToString>(&mut self, name: T) {
+        self.default_font = Some(name.to_string());
+    }
+
+    pub fn font(&self, name: &str) -> Option<&Font> {
+        self.fonts.get(name)
+    }
+
+    ///Looks up `name`, or the default font when `None`. Without any font, `res/fonts/mplus-1p-regular.ttf` is loaded.
+    pub fn resolve(&mut self, name: Option<&str>) -> Result<Font, FontError> {
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => match &self.default_font {
+                Some(name) => name.clone(),
+                None => self.add_font(Font::load_default(DEFAULT_FONT)?)
+            }
+        };
+        self.fonts.get(&name).cloned().ok_or(FontError::UnknownFont(name))
+    }
+
+    pub fn layout(&mut self, font: Option<&str>, text: &str, size: f32, alignment: TextAlignment, max_width: Option<f32>) -> Result<TextLayout, FontError> {
+        let font = self.resolve(font)?;
+        Ok(TextLayout::new(&font, text, size, alignment, max_width))
+    }
+
+    ///Lays out `text`, rasterises missing glyphs and builds its geometry. Requires a current GL context.
+    pub fn build_mesh(&mut self, font: Option<&str>, text: &str, size: f32, alignment: TextAlignment, max_width: Option<f32>) -> Result<TextMesh, FontError> {
+        let font = self.resolve(font)?;
+        let layout = TextLayout::new(&font, text, size, alignment, max_width);
+        let height = layout.size()[1];
+        let atlas = self.atlas.get_or_insert_with(|| GlyphAtlas::new(DEFAULT_ATLAS_SIZE));
+
+        let mut vertices: Vec<f32> = vec![];
+        for positioned in layout.glyphs() {
+            let glyph = match atlas.glyph(&font, positioned.glyph, size)? {
+                None => continue,
+                Some(glyph) => glyph
+            };
+            let position = [positioned.position[0] + glyph.offset[0], positioned.position[1] + glyph.offset[1]];
+            push_quad(&mut vertices, height, position, [glyph.size[0] as f32, glyph.size[1] as f32], glyph.uv);
+        }
+        atlas.flush();
+
+        Ok(TextMesh { vao: build_vao(&vertices), vertices: (vertices.len() / 4) as i32, size: layout.size(), sdf: None })
+    }
+
+    ///Adds the distance field of a font, which has to be added itself as well for layout. Requires a current GL context.
+    pub fn add_sdf_atlas(&mut self, atlas: SdfAtlas) {
+        let texture = atlas.to_texture();
+        self.sdf_fonts.insert(atlas.font().to_string(), SdfFont { atlas, texture });
+    }
+
+    pub fn sdf_atlas(&self, font: &str) -> Option<&SdfAtlas> {
+        self.sdf_fonts.get(font).map(|font| &font.atlas)
+    }
+
+    ///Like `build_mesh`, but scales the glyphs of the font's SDF atlas, so the text stays crisp at any size and zoom.
+    ///Characters missing from the atlas are skipped.
+    pub fn build_sdf_mesh(&mut self, font: Option<&str>, text: &str, size: f32, alignment: TextAlignment, max_width: Option<f32>) -> Result<TextMesh, FontError> {
+        let font = self.resolve(font)?;
+        let sdf = self.sdf_fonts.get(font.name()).ok_or(FontError::UnknownFont(format!("{} (SDF)", font.name())))?;
+        let layout = TextLayout::new(&font, text, size, alignment, max_width);
+        let height = layout.size()[1];
+        let scale = size / sdf.atlas.options().glyph_size;
+
+        let mut vertices: Vec<f32> = vec![];
+        for positioned in layout.glyphs() {
+            let glyph = match sdf.atlas.glyph(positioned.character) {
+                Some(glyph) if glyph.size != [0, 0] => glyph,
+                _ => continue
+            };
+            let position = [positioned.position[0] + glyph.offset[0] * scale, positioned.position[1] + glyph.offset[1] * scale];
+            push_quad(&mut vertices, height, position, [glyph.size[0] as f32 * scale, glyph.size[1] as f32 * scale], glyph.uv);
+        }
+
+        Ok(TextMesh { vao: build_vao(&vertices), vertices: (vertices.len() / 4) as i32, size: layout.size(), sdf: Some(font.name().to_string()) })
+    }
+
+    ///Compiles the coverage and SDF text programs if that has not happened yet.
+    pub fn prepare_program(&mut self) -> Result<(), FontError> {
+        if self.program.is_none() {
+            self.program = Some(compile(TEXT_FRAGMENT_SHADER)?);
+        }
+        if self.sdf_program.is_none() {
+            self.sdf_program = Some(compile(SDF_FRAGMENT_SHADER)?);
+        }
+        Ok(())
+    }
+
+    ///Replaces the coverage text program, deleting the previous one.
+    pub fn set_program(&mut self, program: Program) {
+        if let Some(old) = self.program.replace(program) {
+            old.delete();
+        }
+    }
+
+    ///Replaces the SDF text program, deleting the previous one.
+    pub fn set_sdf_program(&mut self, program: Program) {
+        if let Some(old) = self.sdf_program.replace(program) {
+            old.delete();
+        }
+    }
+
+    ///Sources of the coverage text program, read from `res/shaders/ui` for a `ShaderWatcher`.
+    pub fn program_sources() -> ProgramSources {
+        ProgramSources::new(ShaderSource::file(TEXT_VERTEX_SHADER_PATH), ShaderSource::file(TEXT_FRAGMENT_SHADER_PATH))
+    }
+
+    pub fn sdf_program_sources() -> ProgramSources {
+        ProgramSources::new(ShaderSource::file(TEXT_VERTEX_SHADER_PATH), ShaderSource::file(SDF_FRAGMENT_SHADER_PATH))
+    }
+
+    ///Draws `mesh` with its bottom left corner at `origin`. Does nothing until a mesh was built and the program prepared.
+    pub fn draw(&self, mesh: &TextMesh, projection: &Matrix4<f32>, origin: [f32; 2], color: &Vector4<f32>) {
+        let (program, texture) = match &mesh.sdf {
+            None => (&self.program, self.atlas.as_ref().map(|atlas| atlas.texture())),
+            Some(font) => (&self.sdf_program, self.sdf_fonts.get(font).map(|font| &font.texture))
+        };
+        let (program, texture, vao) = match (program, texture, &mesh.vao) {
+            (Some(program), Some(texture), Some(vao)) => (program, texture, vao),
+            _ => return
+        };
+        let unit = TextureUnit::new(0);
+        program.enable();
+        texture.bind_to(&unit);
+        program.set_uniform_sampler("atlas", &unit);
+        program.set_uniform_mat4("projection", projection);
+        program.set_uniform_vec2("origin", &origin);
+        program.set_uniform_vec4("color", color);
+
+        vao.bind();
+        draw_arrays(DrawMode::Triangles, 0, mesh.vertices);
+        vao.unbind();
+        texture.unbind();
+        program.disable();
+    }
+
+    pub fn atlas(&self) -> Option<&GlyphAtlas> {
+        self.atlas.as_ref()
+    }
+
+    pub fn atlas_mut(&mut self) -> Option<&mut GlyphAtlas> {
+        self.atlas.as_mut()
+    }
+}
+
+impl Drop for TextRenderer {
+    fn drop(&mut self) {
+        for program in [self.program, self.sdf_program].iter().flatten() {
+            program.delete();
+        }
+    }
+}
+
+fn compile(fragment_shader: &str) -> Result<Program, FontError> {
+    Program::new(
+        FragmentShader::new(fragment_shader),
+        VertexShader::new(TEXT_VERTEX_SHADER),
+    ).map_err(|error| FontError::Program(error.to_string()))
+}
+
+///Appends two triangles for a glyph whose top left corner is at `position`, measured from the top of a block of `height` pixels.
+fn push_quad(vertices: &mut Vec<f32>, height: f32, position: [f32; 2], size: [f32; 2], uv: [f32; 4]) {
+    //The layout measures y downwards from the top, UI coordinates upwards from the bottom.
+    let x0 = position[0];
+    let y1 = height - position[1];
+    let x1 = x0 + size[0];
+    let y0 = y1 - size[1];
+    let [u0, v0, u1, v1] = uv;
+
+    //Same winding as the rectangle strip.
+    vertices.extend_from_slice(&[
+        x0, y1, u0, v0,
+        x0, y0, u0, v1,
+        x1, y1, u1, v0,
+        x1, y1, u1, v0,
+        x0, y0, u0, v1,
+        x1, y0, u1, v1,
+    ]);
+}
+
+fn build_vao(vertices: &Vec<f32>) -> Option<VertexArrayObject> {
+    let count = (vertices.len() / 4) as i32;
+    match count {
+        0 => None,
+        _ => Some(VertexArrayObject::new(Some(VertexArrayObjectType::Arrays(count)))
+            .with_buffer(VertexBufferObject::array(BufferType::ArrayBuffer, DrawType::StaticDraw, vertices))
+            .with_local_attrib_pointer(LocalAttribPointer::new(2, BufferDataType::Float, false))
+            .with_local_attrib_pointer(LocalAttribPointer::new(2, BufferDataType::Float, false))
+            .build())
+    }
+}
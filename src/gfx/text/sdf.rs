@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ab_glyph::{Font as _, point, PxScale};
+use crate::gfx::bindings::TextureFilter;
+use crate::gfx::text::FontError;
+use crate::gfx::text::font::Font;
+use crate::gfx::text::glyph_atlas::ShelfPacker;
+use crate::gfx::texture::PixelFormat;
+use crate::gfx::texture::texture_2d::Texture2D;
+
+const CACHE_MAGIC: &'static [u8; 4] = b"SDFA";
+const CACHE_VERSION: u32 = 1;
+pub const CACHE_EXTENSION: &'static str = "sdfa";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdfOptions {
+    ///Pixel size glyphs are rasterised at. Metrics of the atlas are in this size and get scaled when drawing.
+    pub glyph_size: f32,
+    ///Distance in pixels, inside and outside of the outline, that the field covers before it saturates.
+    pub spread: f32,
+    pub atlas_size: [u32; 2],
+}
+
+impl SdfOptions {
+    pub fn new() -> SdfOptions {
+        Self { glyph_size: 48f32, spread: 6f32, atlas_size: [1024, 1024] }
+    }
+
+    pub fn with_glyph_size(mut self, glyph_size: f32) -> SdfOptions {
+        self.glyph_size = glyph_size;
+        self
+    }
+
+    pub fn with_spread(mut self, spread: f32) -> SdfOptions {
+        self.spread = spread;
+        self
+    }
+
+    pub fn with_atlas_size(mut self, atlas_size: [u32; 2]) -> SdfOptions {
+        self.atlas_size = atlas_size;
+        self
+    }
+}
+
+impl Default for SdfOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///A glyph of an `SdfAtlas`, measured at `SdfOptions::glyph_size`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdfGlyph {
+    ///Top left and bottom right texture coordinates.
+    pub uv: [f32; 4],
+    ///Size of the field in pixels, including the spread on every side. Zero for glyphs without an outline.
+    pub size: [u32; 2],
+    ///Offset of the top left corner of the field from the pen position on the baseline, y pointing down.
+    pub offset: [f32; 2],
+}
+
+///Single channel signed distance field of a set of characters. 0.5 lies on the outline, larger values inside.
+///
+///Generating happens purely on the CPU. The result can be written to and read from a cache file, and uploaded with `to_texture`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdfAtlas {
+    font: String,
+    options: SdfOptions,
+    pixels: Vec<u8>,
+    glyphs: HashMap<char, SdfGlyph>,
+}
+
+impl SdfAtlas {
+    ///Printable ASCII.
+    pub fn ascii() -> Vec<char> {
+        (32u8..127).map(|character| character as char).collect()
+    }
+
+    pub fn generate<I: IntoIterator<Item=char>>(font: &Font, characters: I, options: SdfOptions) -> Result<SdfAtlas, FontError> {
+        let mut characters: Vec<char> = characters.into_iter().collect();
+        //Sorted, so the same set always produces the same atlas.
+        characters.sort();
+        characters.dedup();
+
+        let atlas_size = options.atlas_size;
+        let mut pixels = vec![0u8; atlas_size[0] as usize * atlas_size[1] as usize];
+        let mut packer = ShelfPacker::new(atlas_size);
+        let mut glyphs = HashMap::new();
+        let padding = options.spread.ceil() as u32;
+
+        for character in characters {
+            let glyph = font.glyph_id(character).with_scale_and_position(PxScale::from(options.glyph_size), point(0f32, 0f32));
+            let outlined = match font.inner().outline_glyph(glyph) {
+                None => {
+                    glyphs.insert(character, SdfGlyph { uv: [0f32; 4], size: [0, 0], offset: [0f32; 2] });
+                    continue;
+                }
+                Some(outlined) => outlined
+            };
+            let bounds = outlined.px_bounds();
+            let size = [
+                bounds.width().ceil() as u32 + 2 * padding,
+                bounds.height().ceil() as u32 + 2 * padding,
+            ];
+
+            let mut coverage = vec![0f32; size[0] as usize * size[1] as usize];
+            outlined.draw(|x, y, value| {
+                coverage[(y + padding) as usize * size[0] as usize + (x + padding) as usize] = value;
+            });
+            let field = signed_distance_field(&coverage, size, options.spread);
+
+            let position = packer.allocate(size)?;
+            for row in 0..size[1] as usize {
+                let source = row * size[0] as usize;
+                let target = (position[1] as usize + row) * atlas_size[0] as usize + position[0] as usize;
+                pixels[target..target + size[0] as usize].copy_from_slice(&field[source..source + size[0] as usize]);
+            }
+
+            glyphs.insert(character, SdfGlyph {
+                uv: [
+                    position[0] as f32 / atlas_size[0] as f32,
+                    position[1] as f32 / atlas_size[1] as f32,
+                    (position[0] + size[0]) as f32 / atlas_size[0] as f32,
+                    (position[1] + size[1]) as f32 / atlas_size[1] as f32,
+                ],
+                size,
+                offset: [bounds.min.x - padding as f32, bounds.min.y - padding as f32],
+            });
+        }
+
+        Ok(Self { font: font.name().to_string(), options, pixels, glyphs })
+    }
+
+    ///Reads `<directory>/<font>-<glyph size>-<spread>.sdfa` if it holds every character, otherwise generates the atlas
+    ///for the characters and those already cached, and writes it there.
+    pub fn cached<P: AsRef<Path>, I: IntoIterator<Item=char>>(directory: P, font: &Font, characters: I, options: SdfOptions) -> Result<SdfAtlas, FontError> {
+        let mut characters: Vec<char> = characters.into_iter().collect();
+        let path = Self::cache_path(directory, font.name(), &options);
+        if let Ok(atlas) = Self::load(&path) {
+            if atlas.font == font.name() && atlas.options == options {
+                if characters.iter().all(|character| atlas.glyphs.contains_key(character)) {
+                    return Ok(atlas);
+                }
+                characters.extend(atlas.glyphs.keys());
+            }
+        }
+        let atlas = Self::generate(font, characters, options)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| FontError::Io(format!("{}: {}", parent.display(), error)))?;
+        }
+        atlas.save(&path)?;
+        Ok(atlas)
+    }
+
+    pub fn cache_path<P: AsRef<Path>>(directory: P, font: &str, options: &SdfOptions) -> PathBuf {
+        directory.as_ref().join(format!("{}-{}-{}.{}", font, options.glyph_size, options.spread, CACHE_EXTENSION))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), FontError> {
+        let path = path.as_ref();
+        fs::write(path, self.to_bytes()).map_err(|error| FontError::Io(format!("{}: {}", path.display(), error)))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<SdfAtlas, FontError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|error| FontError::Io(format!("{}: {}", path.display(), error)))?;
+        Self::from_bytes(&bytes)
+    }
+
+    ///Little endian: magic, version, font name, options, glyphs, pixels.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = CACHE_MAGIC.to_vec();
+        write_u32(&mut bytes, CACHE_VERSION);
+        write_u32(&mut bytes, self.font.len() as u32);
+        bytes.extend_from_slice(self.font.as_bytes());
+        write_f32(&mut bytes, self.options.glyph_size);
+        write_f32(&mut bytes, self.options.spread);
+        write_u32(&mut bytes, self.options.atlas_size[0]);
+        write_u32(&mut bytes, self.options.atlas_size[1]);
+
+        let mut characters: Vec<&char> = self.glyphs.keys().collect();
+        characters.sort();
+        write_u32(&mut bytes, characters.len() as u32);
+        for character in characters {
+            let glyph = &self.glyphs[character];
+            write_u32(&mut bytes, *character as u32);
+            glyph.uv.iter().for_each(|value| write_f32(&mut bytes, *value));
+            glyph.size.iter().for_each(|value| write_u32(&mut bytes, *value));
+            glyph.offset.iter().for_each(|value| write_f32(&mut bytes, *value));
+        }
+        bytes.extend_from_slice(&self.pixels);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<SdfAtlas, FontError> {
+        let mut reader = Reader(bytes);
+        if reader.take(4)? != CACHE_MAGIC {
+            return Err(FontError::InvalidCache("Not an SDF atlas".to_string()));
+        }
+        let version = reader.u32()?;
+        if version != CACHE_VERSION {
+            return Err(FontError::InvalidCache(format!("Unsupported version {}", version)));
+        }
+        let name_length = reader.u32()? as usize;
+        let font = String::from_utf8(reader.take(name_length)?.to_vec()).map_err(|error| FontError::InvalidCache(error.to_string()))?;
+        let options = SdfOptions {
+            glyph_size: reader.f32()?,
+            spread: reader.f32()?,
+            atlas_size: [reader.u32()?, reader.u32()?],
+        };
+
+        let mut glyphs = HashMap::new();
+        for _ in 0..reader.u32()? {
+            let character = char::from_u32(reader.u32()?).ok_or(FontError::InvalidCache("Invalid character".to_string()))?;
+            let glyph = SdfGlyph {
+                uv: [reader.f32()?, reader.f32()?, reader.f32()?, reader.f32()?],
+                size: [reader.u32()?, reader.u32()?],
+                offset: [reader.f32()?, reader.f32()?],
+            };
+            glyphs.insert(character, glyph);
+        }
+        let pixels = reader.take(options.atlas_size[0] as usize * options.atlas_size[1] as usize)?.to_vec();
+        Ok(Self { font, options, pixels, glyphs })
+    }
+
+    ///Uploads the field. Linear filtering is what makes the field interpolate smoothly when scaled.
+    pub fn to_texture(&self) -> Texture2D {
+        Texture2D::new(self.options.atlas_size, PixelFormat::R8, &self.pixels).unwrap()
+            .with_filter(TextureFilter::Linear, TextureFilter::Linear)
+    }
+
+    pub fn font(&self) -> &str {
+        &self.font
+    }
+    pub fn options(&self) -> &SdfOptions {
+        &self.options
+    }
+    pub fn pixels(&self) -> &Vec<u8> {
+        &self.pixels
+    }
+    pub fn glyph(&self, character: char) -> Option<&SdfGlyph> {
+        self.glyphs.get(&character)
+    }
+    pub fn glyphs(&self) -> &HashMap<char, SdfGlyph> {
+        &self.glyphs
+    }
+}
+
+///Maps coverage to distances from the outline, clamped to `spread` and stored as 0.5 + distance / (2 * spread), inside positive.
+pub fn signed_distance_field(coverage: &[f32], size: [u32; 2], spread: f32) -> Vec<u8> {
+    let inside: Vec<bool> = coverage.iter().map(|value| *value >= 0.5f32).collect();
+    let to_inside = distance_transform(&inside, size);
+    let outside: Vec<bool> = inside.iter().map(|inside| !inside).collect();
+    let to_outside = distance_transform(&outside, size);
+
+    to_inside.iter().zip(to_outside.iter()).map(|(to_inside, to_outside)| {
+        //Pixel centers sit half a pixel off the edge in between them.
+        let distance = match *to_inside == 0f32 {
+            true => to_outside - 0.5f32,
+            false => -(to_inside - 0.5f32)
+        };
+        let value = 0.5f32 + distance / (2f32 * spread);
+        (value.max(0f32).min(1f32) * 255f32).round() as u8
+    }).collect()
+}
+
+///Euclidean distance of every pixel to the closest `seed` pixel, by propagating offsets in two passes (8SSEDT).
+fn distance_transform(seed: &[bool], size: [u32; 2]) -> Vec<f32> {
+    const FAR: i32 = 1 << 14;
+    let (width, height) = (size[0] as i32, size[1] as i32);
+    let mut offsets: Vec<[i32; 2]> = seed.iter().map(|seed| match seed {
+        true => [0, 0],
+        false => [FAR, FAR]
+    }).collect();
+
+    let length = |offset: [i32; 2]| offset[0] * offset[0] + offset[1] * offset[1];
+    let compare = |offsets: &mut Vec<[i32; 2]>, x: i32, y: i32, dx: i32, dy: i32| {
+        let (nx, ny) = (x + dx, y + dy);
+        if nx < 0 || ny < 0 || nx >= width || ny >= height {
+            return;
+        }
+        let other = offsets[(ny * width + nx) as usize];
+        let candidate = [other[0] + dx, other[1] + dy];
+        let current = &mut offsets[(y * width + x) as usize];
+        if length(candidate) < length(*current) {
+            *current = candidate;
+        }
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            compare(&mut offsets, x, y, -1, 0);
+            compare(&mut offsets, x, y, 0, -1);
+            compare(&mut offsets, x, y, -1, -1);
+            compare(&mut offsets, x, y, 1, -1);
+        }
+        for x in (0..width).rev() {
+            compare(&mut offsets, x, y, 1, 0);
+        }
+    }
+    for y in (0..height).rev() {
+        for x in (0..width).rev() {
+            compare(&mut offsets, x, y, 1, 0);
+            compare(&mut offsets, x, y, 0, 1);
+            compare(&mut offsets, x, y, -1, 1);
+            compare(&mut offsets, x, y, 1, 1);
+        }
+        for x in 0..width {
+            compare(&mut offsets, x, y, -1, 0);
+        }
+    }
+
+    offsets.iter().map(|offset| (length(*offset) as f32).sqrt()).collect()
+}
+
+fn write_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_f32(bytes: &mut Vec<u8>, value: f32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], FontError> {
+        if self.0.len() < length {
+            return Err(FontError::InvalidCache("Unexpected end of file".to_string()));
+        }
+        let (taken, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32, FontError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, FontError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atlas() -> SdfAtlas {
+        let options = SdfOptions::new().with_glyph_size(16f32).with_spread(2f32).with_atlas_size([4, 2]);
+        let glyphs = HashMap::from([
+            ('a', SdfGlyph { uv: [0f32, 0f32, 0.5f32, 1f32], size: [2, 2], offset: [-2f32, -12.5f32] }),
+            (' ', SdfGlyph { uv: [0f32; 4], size: [0, 0], offset: [0f32; 2] }),
+        ]);
+        SdfAtlas { font: "test".to_string(), options, pixels: vec![0, 64, 128, 255, 1, 2, 3, 4], glyphs }
+    }
+
+    #[test]
+    fn single_pixel_distances() {
+        let mut coverage = vec![0f32; 9];
+        coverage[4] = 1f32;
+        //Half a pixel to the outline in the center, a pixel and a half to the sides and further to the corners.
+        assert_eq!(signed_distance_field(&coverage, [3, 3], 2f32), vec![69, 96, 69, 96, 159, 96, 69, 96, 69]);
+    }
+
+    #[test]
+    fn saturates_past_the_spread() {
+        let mut coverage = vec![0f32; 25];
+        coverage[12] = 1f32;
+        let field = signed_distance_field(&coverage, [5, 5], 1f32);
+        assert_eq!(field[0], 0);
+        assert_eq!(field[12], 191);
+    }
+
+    #[test]
+    fn empty_coverage_is_outside() {
+        assert!(signed_distance_field(&[0f32; 4], [2, 2], 2f32).iter().all(|value| *value == 0));
+        assert!(signed_distance_field(&[1f32; 4], [2, 2], 2f32).iter().all(|value| *value == 255));
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let atlas = atlas();
+        assert_eq!(SdfAtlas::from_bytes(&atlas.to_bytes()).unwrap(), atlas);
+    }
+
+    #[test]
+    fn truncated_bytes_are_invalid() {
+        let bytes = atlas().to_bytes();
+        for length in [0, 3, 12, bytes.len() - 1] {
+            assert!(matches!(SdfAtlas::from_bytes(&bytes[..length]), Err(FontError::InvalidCache(_))), "{} bytes", length);
+        }
+    }
+
+    #[test]
+    fn wrong_magic_is_invalid() {
+        let mut bytes = atlas().to_bytes();
+        bytes[0] = b'X';
+        assert!(matches!(SdfAtlas::from_bytes(&bytes), Err(FontError::InvalidCache(_))));
+    }
+
+    #[test]
+    fn cached_keeps_cached_characters() {
+        let font = Font::load(concat!(env!("CARGO_MANIFEST_DIR"), "/res/fonts/mplus-1p-regular.ttf")).unwrap();
+        let directory = std::env::temp_dir().join(format!("sdf-cache-{}", std::process::id()));
+        let options = SdfOptions::new().with_glyph_size(16f32).with_atlas_size([128, 128]);
+
+        SdfAtlas::cached(&directory, &font, "ab".chars(), options).unwrap();
+        let atlas = SdfAtlas::cached(&directory, &font, "c".chars(), options).unwrap();
+        assert!("abc".chars().all(|character| atlas.glyph(character).is_some()));
+        let cached = SdfAtlas::load(SdfAtlas::cache_path(&directory, font.name(), &options)).unwrap();
+        assert_eq!(cached, atlas);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
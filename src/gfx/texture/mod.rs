@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::gfx::bindings::PixelType;
+
+pub mod texture_2d;
+pub mod texture_unit;
+
+///Layout of the pixel data handed to a texture, and the internal format it is stored as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PixelFormat {
+    Rgba8,
+    ///RGBA8 data stored in sRGB color space, converted to linear when sampled.
+    Srgb8Alpha8,
+    Rgb8,
+    R8,
+    Rgba32F,
+    R32F,
+}
+
+impl PixelFormat {
+    pub fn internal_format(&self) -> u32 {
+        match self {
+            PixelFormat::Rgba8 => gl::RGBA8,
+            PixelFormat::Srgb8Alpha8 => gl::SRGB8_ALPHA8,
+            PixelFormat::Rgb8 => gl::RGB8,
+            PixelFormat::R8 => gl::R8,
+            PixelFormat::Rgba32F => gl::RGBA32F,
+            PixelFormat::R32F => gl::R32F,
+        }
+    }
+
+    pub fn format(&self) -> u32 {
+        match self {
+            PixelFormat::Rgba8 | PixelFormat::Srgb8Alpha8 | PixelFormat::Rgba32F => gl::RGBA,
+            PixelFormat::Rgb8 => gl::RGB,
+            PixelFormat::R8 | PixelFormat::R32F => gl::RED,
+        }
+    }
+
+    pub fn pixel_type(&self) -> PixelType {
+        match self {
+            PixelFormat::Rgba8 | PixelFormat::Srgb8Alpha8 | PixelFormat::Rgb8 | PixelFormat::R8 => PixelType::UnsignedByte,
+            PixelFormat::Rgba32F | PixelFormat::R32F => PixelType::Float,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        match self {
+            PixelFormat::Rgba8 | PixelFormat::Srgb8Alpha8 | PixelFormat::Rgba32F => 4,
+            PixelFormat::Rgb8 => 3,
+            PixelFormat::R8 | PixelFormat::R32F => 1,
+        }
+    }
+
+    pub fn bytes_per_pixel(&self) -> usize {
+        self.channels() * self.pixel_type().size()
+    }
+}
+
+///Component types pixel data can be supplied in.
+pub trait TexelComponent: Copy {
+    fn pixel_type() -> PixelType;
+}
+
+impl TexelComponent for u8 {
+    fn pixel_type() -> PixelType {
+        PixelType::UnsignedByte
+    }
+}
+
+impl TexelComponent for f32 {
+    fn pixel_type() -> PixelType {
+        PixelType::Float
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TextureError {
+    ///Expected and actual amount of components.
+    DataLengthMismatch(usize, usize),
+    ///The component type of the data does not match the pixel format.
+    PixelTypeMismatch(PixelFormat, PixelType),
+}
+
+impl Display for TextureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextureError::DataLengthMismatch(expected, actual) => {
+                f.write_fmt(format_args!("Texture data length mismatch: expected {} components, got {}", expected, actual))
+            }
+            TextureError::PixelTypeMismatch(format, pixel_type) => {
+                f.write_fmt(format_args!("Pixel format {:?} cannot be created from {:?} data", format, pixel_type))
+            }
+        }
+    }
+}
+
+impl Error for TextureError {}
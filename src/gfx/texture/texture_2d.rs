@@ -0,0 +1,172 @@
+use std::ffi::c_void;
+use std::ptr::null;
+use crate::gfx::bindings::{TextureFilter, TextureParameter, TextureTarget, TextureWrap};
+use crate::gfx::bindings::textures::*;
+use crate::gfx::texture::{PixelFormat, TexelComponent, TextureError};
+use crate::gfx::texture::texture_unit::TextureUnit;
+
+#[derive(Debug)]
+pub struct Texture2D {
+    id: u32,
+    size: [u32; 2],
+    format: PixelFormat,
+    min_filter: TextureFilter,
+    mag_filter: TextureFilter,
+    wrap: [TextureWrap; 2],
+    mipmaps: bool,
+}
+
+impl Texture2D {
+    ///Creates a texture from tightly packed rows of `size[0]` pixels, starting with the bottom row.
+    pub fn new<T: TexelComponent>(size: [u32; 2], format: PixelFormat, pixels: &[T]) -> Result<Texture2D, TextureError> {
+        if T::pixel_type() != format.pixel_type() {
+            return Err(TextureError::PixelTypeMismatch(format, T::pixel_type()));
+        }
+        let expected = size[0] as usize * size[1] as usize * format.channels();
+        if pixels.len() != expected {
+            return Err(TextureError::DataLengthMismatch(expected, pixels.len()));
+        }
+        Ok(Self::allocate(size, format, pixels.as_ptr() as *const _))
+    }
+
+    ///Creates a texture with undefined contents, e.g. as a render target.
+    pub fn empty(size: [u32; 2], format: PixelFormat) -> Texture2D {
+        Self::allocate(size, format, null())
+    }
+
+    fn allocate(size: [u32; 2], format: PixelFormat, data: *const c_void) -> Texture2D {
+        let texture = Texture2D {
+            id: gen_textures(1),
+            size,
+            format,
+            min_filter: TextureFilter::Linear,
+            mag_filter: TextureFilter::Linear,
+            wrap: [TextureWrap::ClampToEdge; 2],
+            mipmaps: false,
+        };
+        texture.bind();
+        //Rows of RGB8 and R8 data are not necessarily 4 byte aligned.
+        unpack_alignment(1);
+        tex_image_2d(TextureTarget::Texture2D, 0, format.internal_format(), [size[0] as i32, size[1] as i32], format.format(), format.pixel_type(), data);
+        unpack_alignment(4);
+        texture.apply_parameters();
+        texture
+    }
+
+    fn apply_parameters(&self) {
+        tex_parameter_i(TextureTarget::Texture2D, TextureParameter::MinFilter, self.min_filter as u32);
+        tex_parameter_i(TextureTarget::Texture2D, TextureParameter::MagFilter, self.mag_filter as u32);
+        tex_parameter_i(TextureTarget::Texture2D, TextureParameter::WrapS, self.wrap[0] as u32);
+        tex_parameter_i(TextureTarget::Texture2D, TextureParameter::WrapT, self.wrap[1] as u32);
+    }
+
+    ///Replaces the contents of the whole texture. The data has to match the size and format it was created with.
+    pub fn upload<T: TexelComponent>(&mut self, pixels: &[T]) -> Result<(), TextureError> {
+        if T::pixel_type() != self.format.pixel_type() {
+            return Err(TextureError::PixelTypeMismatch(self.format, T::pixel_type()));
+        }
+        let expected = self.size[0] as usize * self.size[1] as usize * self.format.channels();
+        if pixels.len() != expected {
+            return Err(TextureError::DataLengthMismatch(expected, pixels.len()));
+        }
+        self.bind();
+        unpack_alignment(1);
+        tex_image_2d(TextureTarget::Texture2D, 0, self.format.internal_format(), [self.size[0] as i32, self.size[1] as i32], self.format.format(), self.format.pixel_type(), pixels.as_ptr() as *const _);
+        unpack_alignment(4);
+        if self.mipmaps {
+            generate_mipmap(TextureTarget::Texture2D);
+        }
+        Ok(())
+    }
+
+    pub fn bind(&self) {
+        bind_texture(TextureTarget::Texture2D, self.id);
+    }
+
+    pub fn unbind(&self) {
+        unbind_texture(TextureTarget::Texture2D);
+    }
+
+    ///Binds the texture to `unit`, which can then be handed to `Program::set_uniform_sampler`.
+    pub fn bind_to(&self, unit: &TextureUnit) {
+        unit.activate();
+        self.bind();
+    }
+
+    ///Sets the filters. A mipmapped minification filter generates the mipmaps if that has not happened yet.
+    pub fn set_filter(&mut self, min_filter: TextureFilter, mag_filter: TextureFilter) {
+        self.min_filter = min_filter;
+        //Magnification never samples mipmaps.
+        self.mag_filter = match mag_filter {
+            TextureFilter::Nearest | TextureFilter::NearestMipmapNearest | TextureFilter::NearestMipmapLinear => TextureFilter::Nearest,
+            _ => TextureFilter::Linear
+        };
+        self.bind();
+        if min_filter.uses_mipmaps() && !self.mipmaps {
+            self.generate_mipmaps();
+        }
+        self.apply_parameters();
+    }
+
+    pub fn with_filter(mut self, min_filter: TextureFilter, mag_filter: TextureFilter) -> Texture2D {
+        self.set_filter(min_filter, mag_filter);
+        self
+    }
+
+    pub fn set_wrap(&mut self, wrap_s: TextureWrap, wrap_t: TextureWrap) {
+        self.wrap = [wrap_s, wrap_t];
+        self.bind();
+        self.apply_parameters();
+    }
+
+    pub fn with_wrap(mut self, wrap_s: TextureWrap, wrap_t: TextureWrap) -> Texture2D {
+        self.set_wrap(wrap_s, wrap_t);
+        self
+    }
+
+    pub fn generate_mipmaps(&mut self) {
+        self.bind();
+        generate_mipmap(TextureTarget::Texture2D);
+        self.mipmaps = true;
+    }
+
+    ///Generates mipmaps and switches to trilinear minification.
+    pub fn with_mipmaps(self) -> Texture2D {
+        let mag_filter = self.mag_filter;
+        self.with_filter(TextureFilter::LinearMipmapLinear, mag_filter)
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+    pub fn width(&self) -> u32 {
+        self.size[0]
+    }
+    pub fn height(&self) -> u32 {
+        self.size[1]
+    }
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+    pub fn min_filter(&self) -> TextureFilter {
+        self.min_filter
+    }
+    pub fn mag_filter(&self) -> TextureFilter {
+        self.mag_filter
+    }
+    pub fn wrap(&self) -> [TextureWrap; 2] {
+        self.wrap
+    }
+    pub fn has_mipmaps(&self) -> bool {
+        self.mipmaps
+    }
+}
+
+impl Drop for Texture2D {
+    fn drop(&mut self) {
+        delete_texture(self.id);
+    }
+}
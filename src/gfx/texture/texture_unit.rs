@@ -0,0 +1,55 @@
+use crate::gfx::bindings::textures::active_texture;
+
+///Minimum number of fragment texture units every GL 3.3 implementation provides.
+pub const MIN_TEXTURE_UNITS: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureUnit(u32);
+
+impl TextureUnit {
+    pub fn new(index: u32) -> TextureUnit {
+        Self(index)
+    }
+    pub fn index(&self) -> u32 {
+        self.0
+    }
+    pub fn activate(&self) {
+        active_texture(self.0);
+    }
+}
+
+///Hands out texture units in order, e.g. once per draw call. Call `reset` before binding the textures of the next draw.
+#[derive(Debug, Clone)]
+pub struct TextureUnits {
+    next: u32,
+    max: u32,
+}
+
+impl TextureUnits {
+    pub fn new(max: u32) -> TextureUnits {
+        Self { next: 0, max }
+    }
+
+    pub fn allocate(&mut self) -> Option<TextureUnit> {
+        if self.next >= self.max {
+            return None;
+        }
+        let unit = TextureUnit(self.next);
+        self.next += 1;
+        Some(unit)
+    }
+
+    pub fn reset(&mut self) {
+        self.next = 0;
+    }
+
+    pub fn allocated(&self) -> u32 {
+        self.next
+    }
+}
+
+impl Default for TextureUnits {
+    fn default() -> Self {
+        TextureUnits::new(MIN_TEXTURE_UNITS)
+    }
+}
@@ -1,6 +1,30 @@
 use crate::{Slider, UIElement};
+use crate::gfx::ui::UiContext;
+use crate::gfx::ui::event::MouseEvent;
+use crate::gfx::ui::interactable::button::Button;
+use crate::gfx::ui::interactable::checkbox::Checkbox;
+use crate::gfx::ui::interactable::radio_group::RadioGroup;
+use crate::gfx::ui::interactable::text_input::TextInput;
+use crate::gfx::ui::interactable::toggle::Toggle;
 
 pub type OnSliderValueChanged = dyn FnMut(&mut Slider, f32, f32);
+pub type OnCheckboxValueChanged = dyn FnMut(&mut Checkbox, bool, bool);
+pub type OnToggleValueChanged = dyn FnMut(&mut Toggle, bool, bool);
+///Old and new index of the selected option.
+pub type OnRadioValueChanged = dyn FnMut(&mut RadioGroup, Option<usize>, Option<usize>);
 
-///Defined by the Component that is being dragged, the mouse_delta(the difference in mouse position since last position), the Offset(the distance from the position of the object at the start of the drag), and then the Reference Mouse position.
-pub type OnDrag = dyn FnMut(&mut Box<dyn UIElement>, [f64; 2], [f64; 2], [f64; 2]);
\ No newline at end of file
+///Defined by the context the Component lives in (without the Component itself, which is handed over separately), the Component that is being dragged, the mouse_delta(the difference in mouse position since last position), the Offset(the distance from the position of the object at the start of the drag), and then the Reference Mouse position.
+pub type OnDrag = dyn FnMut(&mut UiContext, &mut Box<dyn UIElement>, [f64; 2], [f64; 2], [f64; 2]);
+
+///Fired on the element gaining focus, or losing it for blur callbacks. The context is handed over without the element.
+pub type OnFocus = dyn FnMut(&mut UiContext, &mut Box<dyn UIElement>);
+
+///Fired in the target and bubble phases of a mouse event, see `UiContext::dispatch_mouse_event`.
+pub type OnMouseEvent = dyn FnMut(&mut UiContext, &mut Box<dyn UIElement>, &mut MouseEvent);
+
+///Fired when the primary mouse button is released over the button it was pressed on.
+pub type OnClick = dyn FnMut(&mut UiContext, &mut Button);
+
+///Old and new text, after every edit that changed it.
+pub type OnTextChanged = dyn FnMut(&mut TextInput, String, String);
+pub type OnSubmit = dyn FnMut(&mut TextInput, String);
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use crate::gfx::ui::container::{Container, ContainerData};
+use crate::gfx::ui::{quad_render_data, ui_counter, UiContext, UIElementData};
+use crate::{Camera, CustomUIProperty, UIElement};
+use crate::gfx::ui::layout::engine::{Arrangement, LayoutStyle, Size};
+use crate::gfx::ui::layout::Layout;
+use crate::gfx::ui::shape::children_hit;
+use crate::gfx::ui::style::Style;
+use crate::gfx::ui::style::stylesheet::Stylesheet;
+
+pub use crate::gfx::ui::layout::flex::{Align, FlexDirection, FlexLayout, FlexWrap, Justify};
+
+///How a child takes part in the distribution of the main axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexItem {
+    ///Share of the free space the child grows by.
+    pub grow: f32,
+    ///Weight, relative to its size, the child shrinks with when the line overflows.
+    pub shrink: f32,
+    ///Size before growing or shrinking. The size of the child, measured every layout, when `None`.
+    pub basis: Option<[u32; 2]>,
+}
+
+impl Default for FlexItem {
+    fn default() -> Self {
+        Self { grow: 0f32, shrink: 1f32, basis: None }
+    }
+}
+
+///Lays its children out along a row or column, with the gap taken from `ContainerData::spacing`, see
+///`FlexLayout`.
+#[derive(Debug)]
+pub struct Flex {
+    element_data: UIElementData,
+    container_data: ContainerData,
+    direction: FlexDirection,
+    wrap: FlexWrap,
+    justify: Justify,
+    align: Align,
+    items: HashMap<usize, FlexItem>,
+}
+
+impl Flex {
+    pub fn new(direction: FlexDirection) -> Flex {
+        Self {
+            element_data: UIElementData::new(ui_counter(), [0; 2], [0; 2]).with_render_data(quad_render_data()),
+            container_data: ContainerData::new(),
+            direction,
+            wrap: FlexWrap::NoWrap,
+            justify: Justify::Start,
+            align: Align::Start,
+            items: Default::default(),
+        }
+    }
+
+    pub fn row() -> Flex {
+        Self::new(FlexDirection::Row)
+    }
+
+    pub fn column() -> Flex {
+        Self::new(FlexDirection::Column)
+    }
+
+    pub fn with_wrap(mut self, wrap: FlexWrap) -> Flex {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_justify(mut self, justify: Justify) -> Flex {
+        self.justify = justify;
+        self
+    }
+
+    pub fn with_align(mut self, align: Align) -> Flex {
+        self.align = align;
+        self
+    }
+
+    pub fn with_horizontal(mut self, horizontal: Layout) -> Flex {
+        *self.horizontal_mut() = horizontal;
+        self
+    }
+
+    pub fn with_vertical(mut self, vertical: Layout) -> Flex {
+        *self.vertical_mut() = vertical;
+        self
+    }
+
+    pub fn with_size(mut self, size: [u32; 2]) -> Flex {
+        self.element_data.width = size[0];
+        self.element_data.height = size[1];
+        self
+    }
+
+    pub fn with_child<E: UIElement>(mut self, element: E) -> Flex {
+        self.add_child(Box::new(element));
+        self
+    }
+
+    ///Adds a child that grows by `grow` shares of the free space and shrinks with weight `shrink`.
+    pub fn with_flex_child<E: UIElement>(mut self, element: E, grow: f32, shrink: f32) -> Flex {
+        self.set_item(element.id(), FlexItem { grow, shrink, basis: None });
+        self.add_child(Box::new(element));
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: u32) -> Flex {
+        *self.spacing_mut() = spacing;
+        self
+    }
+
+    pub fn with_custom_property<T: ToString>(mut self, key: T, value: CustomUIProperty) -> Flex {
+        self.set_custom_property(&key.to_string(), value);
+        self
+    }
+
+    ///Sets the factors of a child, registered or not. They are dropped once the child leaves the flex.
+    pub fn set_item(&mut self, child: usize, item: FlexItem) {
+        self.items.insert(child, item);
+    }
+
+    pub fn item(&self, child: &usize) -> FlexItem {
+        self.items.get(child).cloned().unwrap_or_default()
+    }
+
+    pub fn direction(&self) -> FlexDirection {
+        self.direction
+    }
+    pub fn set_direction(&mut self, direction: FlexDirection) {
+        self.direction = direction;
+    }
+    pub fn wrap(&self) -> FlexWrap {
+        self.wrap
+    }
+    pub fn set_wrap(&mut self, wrap: FlexWrap) {
+        self.wrap = wrap;
+    }
+    pub fn justify(&self) -> Justify {
+        self.justify
+    }
+    pub fn set_justify(&mut self, justify: Justify) {
+        self.justify = justify;
+    }
+    pub fn align(&self) -> Align {
+        self.align
+    }
+    pub fn set_align(&mut self, align: Align) {
+        self.align = align;
+    }
+}
+
+impl Container for Flex {
+    fn container_data(&self) -> &ContainerData {
+        &self.container_data
+    }
+
+    fn container_data_mut(&mut self) -> &mut ContainerData {
+        &mut self.container_data
+    }
+}
+
+impl UIElement for Flex {
+    fn tag(&self) -> &'static str {
+        "Flex"
+    }
+
+    fn apply_style(&mut self, style: &Style, _stylesheet: &Stylesheet) {
+        self.apply_container_style(style);
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.element_data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.element_data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, _point: [f64; 2]) -> bool {
+        false
+    }
+
+    ///Covers what its children cover, gaps between them excluded.
+    fn hit_test(&self, ui: &UiContext, point: [f64; 2]) -> bool {
+        children_hit(ui, self.children(), point)
+    }
+
+    ///Lays the children out in lines, see `UiContext::apply_layout`.
+    fn layout_style(&self) -> Option<LayoutStyle> {
+        let flex = FlexLayout { direction: self.direction, wrap: self.wrap, justify: self.justify, align: self.align };
+        Some(self.container_layout_style(Arrangement::Flex(flex)))
+    }
+
+    fn child_layout_style(&self, child: &usize, style: LayoutStyle) -> LayoutStyle {
+        let item = self.item(child);
+        let style = self.apply_child_layout(style).with_flex(item.grow, item.shrink);
+        match item.basis {
+            None => style,
+            Some(basis) => style.with_size([Size::Fixed(basis[0] as f32), Size::Fixed(basis[1] as f32)])
+        }
+    }
+
+    fn on_child_detach(&mut self, _ui: &mut UiContext, child: usize) {
+        self.items.remove(&child);
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        for child in ui.children_in_draw_order(&self.id()) {
+            child.draw(ui, camera);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rectangle;
+    use crate::gfx::ui::tests::with_recording_context;
+
+    #[test]
+    fn items_are_dropped_when_children_leave() {
+        with_recording_context(|ui| {
+            let (first, second) = (Rectangle::default(), Rectangle::default());
+            let (first_id, second_id) = (first.id(), second.id());
+            let grown = FlexItem { grow: 1f32, shrink: 0f32, basis: None };
+            let flex = ui.register(Flex::row().with_flex_child(first, 1f32, 0f32).with_flex_child(second, 1f32, 0f32)).unwrap();
+            let item = |ui: &UiContext, child: &usize| ui.get_element_by_id(&flex).unwrap().downcast_ref::<Flex>().unwrap().item(child);
+            assert_eq!(item(ui, &first_id), grown);
+
+            let first = ui.take(&first_id).unwrap();
+            ui.reparent(&second_id, None).unwrap();
+            assert!(ui.get_element_by_id(&flex).unwrap().downcast_ref::<Flex>().unwrap().items.is_empty());
+
+            //Coming back does not bring the old factors along.
+            ui.add_child(&flex, *first.downcast::<Rectangle>().unwrap()).unwrap();
+            assert_eq!(item(ui, &first_id), FlexItem::default());
+        });
+    }
+}
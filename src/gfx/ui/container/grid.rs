@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use crate::gfx::ui::container::{Container, ContainerData};
+use crate::gfx::ui::{quad_render_data, ui_counter, UiContext, UIElementData};
+use crate::{Camera, CustomUIProperty, UIElement};
+use crate::gfx::ui::layout::engine::{Arrangement, LayoutStyle};
+use crate::gfx::ui::layout::grid::GridLayout;
+use crate::gfx::ui::layout::Layout;
+use crate::gfx::ui::shape::children_hit;
+use crate::gfx::ui::style::Style;
+use crate::gfx::ui::style::stylesheet::Stylesheet;
+
+pub use crate::gfx::ui::layout::grid::{GridCell, Track};
+
+///Lays its children out in rows and columns, separated by `ContainerData::spacing`, see `GridLayout`.
+#[derive(Debug)]
+pub struct Grid {
+    element_data: UIElementData,
+    container_data: ContainerData,
+    columns: Vec<Track>,
+    rows: Vec<Track>,
+    cells: HashMap<usize, GridCell>,
+}
+
+impl Grid {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Grid {
+        Self {
+            element_data: UIElementData::new(ui_counter(), [0; 2], [0; 2]).with_render_data(quad_render_data()),
+            container_data: ContainerData::new(),
+            columns,
+            rows,
+            cells: Default::default(),
+        }
+    }
+
+    pub fn with_horizontal(mut self, horizontal: Layout) -> Grid {
+        *self.horizontal_mut() = horizontal;
+        self
+    }
+
+    pub fn with_vertical(mut self, vertical: Layout) -> Grid {
+        *self.vertical_mut() = vertical;
+        self
+    }
+
+    pub fn with_size(mut self, size: [u32; 2]) -> Grid {
+        self.element_data.width = size[0];
+        self.element_data.height = size[1];
+        self
+    }
+
+    ///Adds a child to the next free cell.
+    pub fn with_child<E: UIElement>(mut self, element: E) -> Grid {
+        self.add_child(Box::new(element));
+        self
+    }
+
+    pub fn with_cell<E: UIElement>(mut self, element: E, cell: GridCell) -> Grid {
+        self.set_cell(element.id(), cell);
+        self.add_child(Box::new(element));
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: u32) -> Grid {
+        *self.spacing_mut() = spacing;
+        self
+    }
+
+    pub fn with_custom_property<T: ToString>(mut self, key: T, value: CustomUIProperty) -> Grid {
+        self.set_custom_property(&key.to_string(), value);
+        self
+    }
+
+    ///Places a child, registered or not. The cell is dropped once the child leaves the grid.
+    pub fn set_cell(&mut self, child: usize, cell: GridCell) {
+        self.cells.insert(child, cell);
+    }
+
+    pub fn cell(&self, child: &usize) -> Option<&GridCell> {
+        self.cells.get(child)
+    }
+
+    pub fn columns(&self) -> &Vec<Track> {
+        &self.columns
+    }
+    pub fn columns_mut(&mut self) -> &mut Vec<Track> {
+        &mut self.columns
+    }
+    pub fn rows(&self) -> &Vec<Track> {
+        &self.rows
+    }
+    pub fn rows_mut(&mut self) -> &mut Vec<Track> {
+        &mut self.rows
+    }
+}
+
+impl Container for Grid {
+    fn container_data(&self) -> &ContainerData {
+        &self.container_data
+    }
+
+    fn container_data_mut(&mut self) -> &mut ContainerData {
+        &mut self.container_data
+    }
+}
+
+impl UIElement for Grid {
+    fn tag(&self) -> &'static str {
+        "Grid"
+    }
+
+    fn apply_style(&mut self, style: &Style, _stylesheet: &Stylesheet) {
+        self.apply_container_style(style);
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.element_data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.element_data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, _point: [f64; 2]) -> bool {
+        false
+    }
+
+    ///Covers what its children cover, gaps between them excluded.
+    fn hit_test(&self, ui: &UiContext, point: [f64; 2]) -> bool {
+        children_hit(ui, self.children(), point)
+    }
+
+    ///Lays the children out in their cells, see `UiContext::apply_layout`.
+    fn layout_style(&self) -> Option<LayoutStyle> {
+        let grid = GridLayout::new(self.columns.clone(), self.rows.clone());
+        Some(self.container_layout_style(Arrangement::Grid(grid)))
+    }
+
+    fn child_layout_style(&self, child: &usize, style: LayoutStyle) -> LayoutStyle {
+        self.apply_child_layout(style).with_cell(self.cells.get(child).cloned())
+    }
+
+    fn on_child_detach(&mut self, _ui: &mut UiContext, child: usize) {
+        self.cells.remove(&child);
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        for child in ui.children_in_draw_order(&self.id()) {
+            child.draw(ui, camera);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rectangle;
+    use crate::gfx::ui::tests::with_recording_context;
+
+    #[test]
+    fn cells_are_dropped_when_children_leave() {
+        with_recording_context(|ui| {
+            let grid = ui.register(Grid::new(vec![Track::Auto; 2], vec![Track::Auto])).unwrap();
+            let child = ui.add_child(&grid, Rectangle::default()).unwrap();
+            let cell = |ui: &UiContext| ui.get_element_by_id(&grid).unwrap().downcast_ref::<Grid>().unwrap().cell(&child).cloned();
+            ui.get_element_by_id_mut(&grid).unwrap().downcast_mut::<Grid>().unwrap().set_cell(child, GridCell::new(0, 1));
+            assert_eq!(cell(ui), Some(GridCell::new(0, 1)));
+
+            //Back in the grid, the child is placed like any other child without a cell.
+            ui.reparent(&child, None).unwrap();
+            assert_eq!(cell(ui), None);
+            ui.reparent(&child, Some(grid)).unwrap();
+            assert_eq!(cell(ui), None);
+
+            ui.get_element_by_id_mut(&grid).unwrap().downcast_mut::<Grid>().unwrap().set_cell(child, GridCell::new(0, 1));
+            ui.remove(&child).unwrap();
+            assert!(ui.get_element_by_id(&grid).unwrap().downcast_ref::<Grid>().unwrap().cells.is_empty());
+        });
+    }
+}
@@ -0,0 +1,4 @@
+use crate::gfx::ui::container::stack::{Horizontal, StackBox};
+
+///Lines its children up from left to right, aligned to the top, see `StackBox`.
+pub type HBox = StackBox<Horizontal>;
@@ -1,7 +1,16 @@
+use crate::gfx::ui::layout::engine::{Arrangement, Insets, LayoutStyle};
 use crate::gfx::ui::layout::Layout;
+use crate::gfx::ui::style::{Style, Styled};
+use crate::gfx::ui::UiContext;
+use crate::math::clamp_u32;
 use crate::UIElement;
 
+pub mod stack;
 pub mod v_box;
+pub mod h_box;
+pub mod flex;
+pub mod grid;
+pub mod scroll_view;
 
 #[derive(Debug, Clone)]
 pub struct ContainerData {
@@ -18,6 +27,7 @@ pub struct ContainerData {
     child_horizontal: Layout,
     child_vertical: Layout,
     spacing: u32,
+    padding: Styled<Insets>,
 }
 
 pub trait Container: UIElement {
@@ -69,4 +79,92 @@ pub trait Container: UIElement {
 
     fn spacing(&self) -> u32 { self.container_data().spacing }
     fn spacing_mut(&mut self) -> &mut u32 { &mut self.container_data_mut().spacing }
+
+    ///Padding of the computed style, or the one set on the container.
+    fn padding(&self) -> Insets { *self.container_data().padding.get() }
+    fn padding_mut(&mut self) -> &mut Insets { self.container_data_mut().padding.own_mut() }
+
+    ///Puts the padding of a computed style on top of the own one, for `UIElement::apply_style`.
+    fn apply_container_style(&mut self, style: &Style) {
+        self.container_data_mut().padding.set_styled(style.padding());
+    }
+
+    ///Width and height according to `horizontal` and `vertical`, clamped to the minimum and maximum size.
+    ///`MatchParent` fills the parent, or the screen for roots, minus the padding on both sides.
+    fn resolve_size(&self, ui: &UiContext) -> [u32; 2] {
+        let available = match self.get_parent(ui) {
+            None => [ui.screen_size()[0].max(0) as u32, ui.screen_size()[1].max(0) as u32],
+            Some(parent) => [parent.element_data().width(), parent.element_data().height()]
+        };
+        let width = match self.horizontal() {
+            Layout::MatchParent(left, right) => available[0].saturating_sub(left + right),
+            Layout::Percent(percent) => (available[0] as f32 * percent / 100f32).max(0f32).round() as u32,
+            Layout::WrapContent | Layout::Absolute => self.element_data().width()
+        };
+        let height = match self.vertical() {
+            Layout::MatchParent(top, bottom) => available[1].saturating_sub(top + bottom),
+            Layout::Percent(percent) => (available[1] as f32 * percent / 100f32).max(0f32).round() as u32,
+            Layout::WrapContent | Layout::Absolute => self.element_data().height()
+        };
+        [
+            clamp_u32(width, self.min_width(), self.max_width().unwrap_or(u32::MAX)),
+            clamp_u32(height, self.min_height(), self.max_height().unwrap_or(u32::MAX)),
+        ]
+    }
+
+    ///Style for the layout engine of a container placing its children by `arrangement`.
+    ///`MatchParent` padding becomes the margin of the container.
+    fn container_layout_style(&self, arrangement: Arrangement) -> LayoutStyle {
+        let (width, left, right) = self.horizontal().size(self.element_data().width());
+        let (height, top, bottom) = self.vertical().size(self.element_data().height());
+        LayoutStyle::new()
+            .with_size([width, height])
+            .with_min([self.min_width() as f32, self.min_height() as f32])
+            .with_max([self.max_width().map(|width| width as f32), self.max_height().map(|height| height as f32)])
+            .with_padding(self.padding())
+            .with_margin(Insets::new(left, right, top, bottom))
+            .with_arrangement(arrangement)
+            .with_spacing(self.spacing() as f32)
+    }
+
+    ///Overrides size and margins of a child with `child_horizontal` and `child_vertical`, unless they are `Absolute`.
+    fn apply_child_layout(&self, mut style: LayoutStyle) -> LayoutStyle {
+        if self.child_horizontal() != Layout::Absolute {
+            let (width, left, right) = self.child_horizontal().size(0);
+            style.size[0] = width;
+            style.margin.left = left;
+            style.margin.right = right;
+        }
+        if self.child_vertical() != Layout::Absolute {
+            let (height, top, bottom) = self.child_vertical().size(0);
+            style.size[1] = height;
+            style.margin.top = top;
+            style.margin.bottom = bottom;
+        }
+        style
+    }
+}
+
+impl ContainerData {
+    pub fn new() -> ContainerData {
+        Self {
+            min_width: 0,
+            max_width: None,
+            min_height: 0,
+            max_height: None,
+            debug_flag: false,
+            horizontal: Layout::Absolute,
+            vertical: Layout::Absolute,
+            child_horizontal: Layout::Absolute,
+            child_vertical: Layout::Absolute,
+            spacing: 0,
+            padding: Default::default(),
+        }
+    }
+}
+
+impl Default for ContainerData {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,394 @@
+use glfw::{Action, MouseButton};
+use vecmath::Vector4;
+use crate::gfx::ui::container::{Container, ContainerData};
+use crate::gfx::ui::{ui_counter, UiContext, UIElementData};
+use crate::gfx::ui::event::{EventPhase, MouseEvent, MouseEventKind};
+use crate::gfx::ui::interactable::slider::SliderTrack;
+use crate::gfx::ui::shape::shape_contains;
+use crate::gfx::ui::layout::engine::{Arrangement, Insets, LayoutStyle};
+use crate::gfx::ui::layout::Layout;
+use crate::gfx::ui::markup::reload::ElementState;
+use crate::gfx::ui::style::{Style, Styled};
+use crate::gfx::ui::style::stylesheet::Stylesheet;
+use crate::{Camera, CustomUIProperty, Rectangle, UIElement};
+use crate::math::clamp;
+
+///Thickness of the scrollbars.
+const SCROLLBAR_WIDTH: u32 = 8;
+///Shortest a scrollbar handle gets.
+const MIN_HANDLE_LENGTH: f32 = 16f32;
+///Distance the cursor has to move before pressing on the content turns into dragging it.
+const DRAG_THRESHOLD: f64 = 4f64;
+
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    ///Pressed on the content, dragging once the cursor moved far enough.
+    Content { cursor: [f64; 2], scroll: [f32; 2], dragging: bool },
+    ///Pressed on the handle of the scrollbar along `axis`, `grab` from its position.
+    Handle { axis: usize, grab: [f64; 2] },
+}
+
+///Track and handle of one scrollbar, moved like the handle of a slider, see `SliderTrack`.
+#[derive(Debug)]
+struct Scrollbar {
+    track: Rectangle,
+    handle: Rectangle,
+    ///Colors of track and handle, as built and as styled.
+    colors: [Styled<Vector4<f32>>; 2],
+    visible: bool,
+}
+
+impl Scrollbar {
+    fn new() -> Scrollbar {
+        let colors = [[0.85, 0.85, 0.85, 1.0], [0.5, 0.5, 0.5, 1.0]];
+        let part = |color: Vector4<f32>, class: &str| Rectangle::new([0, 0], [0f32; 2])
+            .with_custom_property(&"color", CustomUIProperty::Vec4(color))
+            .with_corner_radius(SCROLLBAR_WIDTH as f32 / 2f32)
+            .with_class(class);
+        Self {
+            track: part(colors[0], "scrollbar-track"),
+            handle: part(colors[1], "scrollbar-handle"),
+            colors: colors.map(Styled::new),
+            visible: false,
+        }
+    }
+}
+
+///Shows a window onto children that can be larger than itself, scrolled with the mouse wheel, by dragging the content
+///or by dragging the handles of its scrollbars. Children are drawn and hit only within its bounds.
+///
+///Children are placed in the top left corner, so a `VBox` with `Layout::WrapContent` makes a scrolling list.
+///Element positions can not go below the bottom of the screen, so keep the view clear of it.
+#[derive(Debug)]
+pub struct ScrollView {
+    element_data: UIElementData,
+    container_data: ContainerData,
+    ///Scrollable along x and y.
+    axes: [bool; 2],
+    scroll: [f32; 2],
+    ///Size of the children from the last layout, padding included.
+    content: [f32; 2],
+    ///Pixels per step of the mouse wheel.
+    scroll_speed: f32,
+    scrollbars: Option<[Scrollbar; 2]>,
+    drag: Option<Drag>,
+}
+
+impl ScrollView {
+    pub fn new(size: [u32; 2]) -> ScrollView {
+        Self {
+            element_data: UIElementData::new(ui_counter(), [0; 2], size),
+            container_data: ContainerData::new(),
+            axes: [false, true],
+            scroll: [0f32; 2],
+            content: [0f32; 2],
+            scroll_speed: 40f32,
+            scrollbars: Some([Scrollbar::new(), Scrollbar::new()]),
+            drag: None,
+        }
+    }
+
+    pub fn with_position(mut self, position: [u32; 2]) -> ScrollView {
+        self.set_position(position);
+        self
+    }
+
+    ///Which axes scroll, x and y. Only vertical scrolling by default.
+    pub fn with_axes(mut self, horizontal: bool, vertical: bool) -> ScrollView {
+        self.axes = [horizontal, vertical];
+        self
+    }
+
+    pub fn with_scrollbars(mut self, scrollbars: bool) -> ScrollView {
+        if !scrollbars {
+            self.scrollbars = None;
+        }
+        self
+    }
+
+    pub fn with_scroll_speed(mut self, scroll_speed: f32) -> ScrollView {
+        self.scroll_speed = scroll_speed;
+        self
+    }
+
+    pub fn with_horizontal(mut self, horizontal: Layout) -> ScrollView {
+        *self.horizontal_mut() = horizontal;
+        self
+    }
+
+    pub fn with_vertical(mut self, vertical: Layout) -> ScrollView {
+        *self.vertical_mut() = vertical;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Insets) -> ScrollView {
+        *self.padding_mut() = padding;
+        self
+    }
+
+    pub fn with_child<E: UIElement>(mut self, element: E) -> ScrollView {
+        self.add_child(Box::new(element));
+        self
+    }
+
+    pub fn with_custom_property<T: ToString>(mut self, key: T, value: CustomUIProperty) -> ScrollView {
+        self.set_custom_property(&key.to_string(), value);
+        self
+    }
+
+    ///Distance the content is scrolled left and up.
+    pub fn scroll(&self) -> [f32; 2] {
+        self.scroll
+    }
+
+    ///Clamped to the content, on the next update.
+    pub fn set_scroll(&mut self, scroll: [f32; 2]) {
+        self.scroll = scroll;
+    }
+
+    pub fn scroll_by(&mut self, delta: [f32; 2]) {
+        self.scroll = [self.scroll[0] + delta[0], self.scroll[1] + delta[1]];
+        self.clamp_scroll();
+    }
+
+    ///Furthest the content can be scrolled along x and y.
+    pub fn max_scroll(&self) -> [f32; 2] {
+        let mut max = [0f32; 2];
+        for axis in 0..2 {
+            if self.axes[axis] {
+                max[axis] = (self.content[axis] - self.viewport()[axis]).max(0f32);
+            }
+        }
+        max
+    }
+
+    fn viewport(&self) -> [f32; 2] {
+        [self.element_data.width as f32, self.element_data.height as f32]
+    }
+
+    fn clamp_scroll(&mut self) {
+        let max = self.max_scroll();
+        self.scroll = [clamp(self.scroll[0], 0f32, max[0]), clamp(self.scroll[1], 0f32, max[1])];
+    }
+
+    ///Length of the handle along `axis`.
+    fn handle_length(&self, axis: usize) -> f32 {
+        let viewport = self.viewport()[axis];
+        match self.content[axis] > 0f32 {
+            true => (viewport * viewport / self.content[axis]).max(MIN_HANDLE_LENGTH).min(viewport),
+            false => viewport
+        }
+    }
+
+    ///Position and size of the track along `axis`, x, y, width and height, at the bottom or right edge.
+    fn track_area(&self, axis: usize) -> [f32; 4] {
+        let position = [self.element_data.position[0] as f32, self.element_data.position[1] as f32];
+        let [width, height] = self.viewport();
+        let thickness = SCROLLBAR_WIDTH as f32;
+        match axis {
+            0 => [position[0], position[1], width, thickness],
+            _ => [position[0] + (width - thickness).max(0f32), position[1], thickness, height]
+        }
+    }
+
+    fn track(&self, axis: usize) -> SliderTrack {
+        SliderTrack::within(self.track_area(axis), self.handle_length(axis), axis)
+    }
+
+    ///Scroll along `axis` as a value from 0 to 1.
+    fn scroll_value(&self, axis: usize) -> f32 {
+        match self.max_scroll()[axis] > 0f32 {
+            true => self.scroll[axis] / self.max_scroll()[axis],
+            false => 0f32
+        }
+    }
+
+    ///Places track and handle of both scrollbars along the right and bottom edge.
+    fn place_scrollbars(&mut self) {
+        let max = self.max_scroll();
+        let placed = [0, 1].map(|axis| {
+            let track = self.track_area(axis);
+            let mut handle = track;
+            handle[axis] = self.track(axis).handle_start(self.scroll_value(axis));
+            handle[axis + 2] = self.handle_length(axis);
+            (track, handle)
+        });
+        let scrollbars = match &mut self.scrollbars {
+            None => return,
+            Some(scrollbars) => scrollbars
+        };
+
+        for (axis, (scrollbar, (track, handle))) in scrollbars.iter_mut().zip(placed).enumerate() {
+            scrollbar.visible = max[axis] > 0f32;
+            for (rectangle, area) in [(&mut scrollbar.track, track), (&mut scrollbar.handle, handle)] {
+                rectangle.set_position([area[0] as u32, area[1] as u32]);
+                rectangle.element_data_mut().width = area[2] as u32;
+                rectangle.element_data_mut().height = area[3] as u32;
+            }
+        }
+    }
+
+    ///Axis of the visible scrollbar handle under `point`.
+    fn handle_at(&self, point: [f64; 2]) -> Option<usize> {
+        let scrollbars = self.scrollbars.as_ref()?;
+        (0..2).find(|axis| {
+            let handle = &scrollbars[*axis].handle;
+            let position = handle.position();
+            scrollbars[*axis].visible
+                && point[0] >= position[0] as f64 && point[0] <= (position[0] + handle.element_data().width()) as f64
+                && point[1] >= position[1] as f64 && point[1] <= (position[1] + handle.element_data().height()) as f64
+        })
+    }
+}
+
+impl Container for ScrollView {
+    fn container_data(&self) -> &ContainerData {
+        &self.container_data
+    }
+
+    fn container_data_mut(&mut self) -> &mut ContainerData {
+        &mut self.container_data
+    }
+}
+
+impl UIElement for ScrollView {
+    fn tag(&self) -> &'static str {
+        "ScrollView"
+    }
+
+    ///Also styles the scrollbars, which are drawn without being registered, by their classes.
+    fn apply_style(&mut self, style: &Style, stylesheet: &Stylesheet) {
+        self.apply_container_style(style);
+        if let Some(scrollbars) = &mut self.scrollbars {
+            for scrollbar in scrollbars.iter_mut() {
+                for (part, color) in [&mut scrollbar.track, &mut scrollbar.handle].into_iter().zip(&mut scrollbar.colors) {
+                    let part_style = stylesheet.compute(&*part, &[], Some(style));
+                    //Parts are not registered, so their color is set directly, falling back to the built one.
+                    if color.set_styled(part_style.background()) {
+                        part.set_color(*color.get());
+                    }
+                    part.element_data_mut().corner_radius.set_styled(part_style.corner_radius());
+                }
+            }
+        }
+    }
+
+    fn save_state(&self) -> Option<ElementState> {
+        Some(ElementState::Scroll(self.scroll))
+    }
+
+    fn restore_state(&mut self, state: ElementState) {
+        if let ElementState::Scroll(scroll) = state {
+            self.set_scroll(scroll);
+        }
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.element_data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.element_data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, point: [f64; 2]) -> bool {
+        shape_contains(self, point)
+    }
+
+    fn clips_children(&self) -> bool {
+        true
+    }
+
+    ///Overlays the children in the top left corner, moved by the scroll offset.
+    fn layout_style(&self) -> Option<LayoutStyle> {
+        Some(self.container_layout_style(Arrangement::Overlay).with_scroll(self.scroll))
+    }
+
+    fn child_layout_style(&self, _child: &usize, style: LayoutStyle) -> LayoutStyle {
+        self.apply_child_layout(style)
+    }
+
+    ///Wheel events and presses on the content bubble up to the scroll view. Wheel events it cannot scroll
+    ///for bubble on to outer scroll views.
+    fn on_mouse_event(&mut self, _ui: &mut UiContext, event: &mut MouseEvent) {
+        if event.phase() == EventPhase::Capture {
+            return;
+        }
+        match event.kind() {
+            MouseEventKind::Wheel(offset) => {
+                if self.max_scroll() == [0f32; 2] {
+                    return;
+                }
+                //Wheel offsets are positive upwards and to the right, scrolling runs downwards and to the right.
+                self.scroll_by([offset[0] as f32 * self.scroll_speed, -offset[1] as f32 * self.scroll_speed]);
+                event.stop_propagation();
+            }
+            MouseEventKind::Down(MouseButton::Button1) => {
+                let cursor = event.position();
+                let scroll = self.scroll;
+                self.drag = match self.handle_at(cursor) {
+                    Some(axis) => {
+                        let handle = self.scrollbars.as_ref().map(|scrollbars| *scrollbars[axis].handle.position()).unwrap_or_default();
+                        Some(Drag::Handle { axis, grab: [cursor[0] - handle[0] as f64, cursor[1] - handle[1] as f64] })
+                    }
+                    None => Some(Drag::Content { cursor, scroll, dragging: false }),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn update_mouse(&mut self, _ui: &mut UiContext, mouse_button: MouseButton, action: Action) {
+        if mouse_button == MouseButton::Button1 && action == Action::Release {
+            self.drag = None;
+        }
+    }
+
+    fn update_cursor(&mut self, _ui: &mut UiContext, cursor: [f64; 2]) {
+        let scroll = match self.drag {
+            None => return,
+            Some(Drag::Content { cursor: start, scroll, dragging }) => {
+                let delta = [cursor[0] - start[0], cursor[1] - start[1]];
+                if !dragging && delta[0].abs().max(delta[1].abs()) < DRAG_THRESHOLD {
+                    return;
+                }
+                self.drag = Some(Drag::Content { cursor: start, scroll, dragging: true });
+                //The content follows the cursor.
+                [scroll[0] - delta[0] as f32, scroll[1] + delta[1] as f32]
+            }
+            Some(Drag::Handle { axis, grab }) => {
+                let mut scroll = self.scroll;
+                scroll[axis] = self.track(axis).drag_value(cursor, grab) * self.max_scroll()[axis];
+                scroll
+            }
+        };
+        self.scroll = scroll;
+        self.clamp_scroll();
+    }
+
+    fn update(&mut self, ui: &mut UiContext, _delta: f32) {
+        if let Some(content) = ui.layout().content_size(&self.id()) {
+            let padding = self.padding().sum();
+            self.content = [content[0] + padding[0], content[1] + padding[1]];
+        }
+        self.clamp_scroll();
+        self.place_scrollbars();
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        let position = self.element_data.position;
+        ui.push_clip([position[0] as i32, position[1] as i32, self.element_data.width as i32, self.element_data.height as i32]);
+        for child in ui.children_in_draw_order(&self.id()) {
+            child.draw(ui, camera);
+        }
+        if let Some(scrollbars) = &self.scrollbars {
+            for scrollbar in scrollbars.iter().filter(|scrollbar| scrollbar.visible) {
+                scrollbar.track.draw(ui, camera);
+                scrollbar.handle.draw(ui, camera);
+            }
+        }
+        ui.pop_clip();
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use crate::gfx::ui::container::{Container, ContainerData};
+use crate::gfx::ui::{quad_render_data, ui_counter, UiContext, UIElementData};
+use crate::{Camera, CustomUIProperty, UIElement};
+use crate::gfx::ui::layout::engine::{Arrangement, Insets, LayoutStyle};
+use crate::gfx::ui::layout::Layout;
+use crate::gfx::ui::shape::children_hit;
+use crate::gfx::ui::style::Style;
+use crate::gfx::ui::style::stylesheet::Stylesheet;
+
+///Axis a `StackBox` lines its children up along, see `Vertical` and `Horizontal`.
+pub trait StackAxis: Debug + 'static {
+    ///`UIElement::tag` of the container.
+    const TAG: &'static str;
+    const ARRANGEMENT: Arrangement;
+}
+
+///Stacks the children from the top down.
+#[derive(Debug)]
+pub struct Vertical;
+
+impl StackAxis for Vertical {
+    const TAG: &'static str = "VBox";
+    const ARRANGEMENT: Arrangement = Arrangement::Vertical;
+}
+
+///Lines the children up from left to right, aligned to the top.
+#[derive(Debug)]
+pub struct Horizontal;
+
+impl StackAxis for Horizontal {
+    const TAG: &'static str = "HBox";
+    const ARRANGEMENT: Arrangement = Arrangement::Horizontal;
+}
+
+///Container placing its children one after another along `A`, with `spacing` between them. Used as `VBox`
+///and `HBox`.
+#[derive(Debug)]
+pub struct StackBox<A: StackAxis> {
+    element_data: UIElementData,
+    container_data: ContainerData,
+    axis: PhantomData<A>,
+}
+
+impl<A: StackAxis> StackBox<A> {
+    pub fn new() -> StackBox<A> {
+        Self {
+            element_data: UIElementData::new(ui_counter(), [0; 2], [0; 2]).with_render_data(quad_render_data()),
+            container_data: ContainerData::new(),
+            axis: PhantomData,
+        }
+    }
+
+    pub fn with_horizontal(mut self, horizontal: Layout) -> StackBox<A> {
+        *self.horizontal_mut() = horizontal;
+        self
+    }
+
+    pub fn with_vertical(mut self, vertical: Layout) -> StackBox<A> {
+        *self.vertical_mut() = vertical;
+        self
+    }
+
+    pub fn with_child_vertical(mut self, vertical: Layout) -> StackBox<A> {
+        *self.child_vertical_mut() = vertical;
+        self
+    }
+
+    pub fn with_child_horizontal(mut self, horizontal: Layout) -> StackBox<A> {
+        *self.child_horizontal_mut() = horizontal;
+        self
+    }
+
+    pub fn with_child<E: UIElement>(mut self, element: E) -> StackBox<A> {
+        self.add_child(Box::new(element));
+        self
+    }
+
+    pub fn with_children<E: UIElement>(mut self, element: Vec<E>) -> StackBox<A> {
+        for child in element {
+            self.add_child(Box::new(child));
+        }
+        self
+    }
+
+    pub fn with_min_width(mut self, width: u32) -> StackBox<A> {
+        *self.min_width_mut() = width;
+        self
+    }
+    pub fn with_max_width(mut self, width: u32) -> StackBox<A> {
+        *self.max_width_mut() = Some(width);
+        self
+    }
+
+    pub fn with_min_height(mut self, height: u32) -> StackBox<A> {
+        *self.min_height_mut() = height;
+        self
+    }
+    pub fn with_max_height(mut self, height: u32) -> StackBox<A> {
+        *self.max_height_mut() = Some(height);
+        self
+    }
+
+    pub fn with_custom_property<T: ToString>(mut self, key: T, value: CustomUIProperty) -> StackBox<A> {
+        self.set_custom_property(&key.to_string(), value);
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: u32) -> StackBox<A> {
+        *self.spacing_mut() = spacing;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Insets) -> StackBox<A> {
+        *self.padding_mut() = padding;
+        self
+    }
+}
+
+impl<A: StackAxis> Default for StackBox<A> {
+    fn default() -> Self {
+        StackBox::new()
+    }
+}
+
+impl<A: StackAxis> Container for StackBox<A> {
+    fn container_data(&self) -> &ContainerData {
+        &self.container_data
+    }
+
+    fn container_data_mut(&mut self) -> &mut ContainerData {
+        &mut self.container_data
+    }
+}
+
+impl<A: StackAxis> UIElement for StackBox<A> {
+    fn tag(&self) -> &'static str {
+        A::TAG
+    }
+
+    fn apply_style(&mut self, style: &Style, _stylesheet: &Stylesheet) {
+        self.apply_container_style(style);
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.element_data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.element_data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, _point: [f64; 2]) -> bool {
+        false
+    }
+
+    ///Covers what its children cover, gaps between them excluded.
+    fn hit_test(&self, ui: &UiContext, point: [f64; 2]) -> bool {
+        children_hit(ui, self.children(), point)
+    }
+
+    ///Places the children along `A`, see `UiContext::apply_layout`.
+    fn layout_style(&self) -> Option<LayoutStyle> {
+        Some(self.container_layout_style(A::ARRANGEMENT))
+    }
+
+    fn child_layout_style(&self, _child: &usize, style: LayoutStyle) -> LayoutStyle {
+        self.apply_child_layout(style)
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        for child in ui.children_in_draw_order(&self.id()) {
+            child.draw(ui, camera);
+        }
+    }
+}
\ No newline at end of file
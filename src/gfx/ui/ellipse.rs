@@ -0,0 +1,61 @@
+use crate::gfx::ui::{UIElement, UIElementData};
+use crate::gfx::ui::custom_ui_property::CustomUIProperty;
+use crate::gfx::ui::rectangle::Rectangle;
+use crate::gfx::ui::shape::{Shape, shape_contains};
+
+///The ellipse touching the sides of its bounds, only hit inside the ellipse.
+#[derive(Debug)]
+pub struct Ellipse(Rectangle);
+
+impl Ellipse {
+    pub fn with_position(self, position: [u32; 2]) -> Ellipse {
+        Ellipse(self.0.with_position(position))
+    }
+    pub fn with_custom_property(mut self, property: &dyn ToString, value: CustomUIProperty) -> Ellipse {
+        self.0.set_custom_property(property, value);
+        self
+    }
+
+    ///`position` is the bottom left corner of the bounds.
+    pub fn new(position: [u32; 2], size: [f32; 2]) -> Self {
+        Self(Rectangle::new(position, size))
+    }
+    pub fn circle(position: [u32; 2], diameter: f32) -> Self {
+        Self::new(position, [diameter; 2])
+    }
+    pub fn data(&self) -> &UIElementData {
+        self.0.data()
+    }
+}
+
+impl Default for Ellipse {
+    fn default() -> Self {
+        Ellipse::circle([0; 2], 100f32)
+    }
+}
+
+impl UIElement for Ellipse {
+    fn tag(&self) -> &'static str {
+        "Ellipse"
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        self.0.element_data()
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        self.0.element_data_mut()
+    }
+
+    fn resize(&mut self, size: [i32; 2]) {
+        self.0.resize(size);
+    }
+
+    fn contains_point(&self, point: [f64; 2]) -> bool {
+        shape_contains(self, point)
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Ellipse
+    }
+}
@@ -0,0 +1,464 @@
+use std::time::{Duration, Instant};
+use glfw::{Action, Key, Modifiers, MouseButton};
+use crate::gfx::ui::{UiContext, UIElement};
+use crate::Input;
+
+///Longest time between two clicks of the same button on the same element to make a double click.
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+///Farthest the cursor may move between the two clicks of a double click, in pixels.
+pub const DOUBLE_CLICK_DISTANCE: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseEventKind {
+    Down(MouseButton),
+    Up(MouseButton),
+    ///The cursor moved onto the element or one of its descendants. Does not bubble.
+    Enter,
+    ///The cursor moved off the element and all of its descendants. Does not bubble.
+    Leave,
+    ///A scroll wheel offset, positive upwards and to the right.
+    Wheel([f64; 2]),
+    ///A press and release of the same button. Targets the deepest element containing both.
+    Click(MouseButton),
+    ///Fired after the second of two quick clicks on the same element.
+    DoubleClick(MouseButton),
+}
+
+impl MouseEventKind {
+    ///Whether the event runs through the ancestors of its target, capture phase first.
+    pub fn bubbles(&self) -> bool {
+        !matches!(self, MouseEventKind::Enter | MouseEventKind::Leave)
+    }
+}
+
+///Where an event is on its way through the tree, see `UiContext::dispatch_mouse_event`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventPhase {
+    ///From the root down to the parent of the target.
+    Capture,
+    Target,
+    ///From the parent of the target back up to the root.
+    Bubble,
+}
+
+///A mouse event on its way through the tree. Every element on the path gets the same event and can stop it
+///from travelling further.
+#[derive(Debug, Clone)]
+pub struct MouseEvent {
+    kind: MouseEventKind,
+    target: usize,
+    current: usize,
+    phase: EventPhase,
+    ///Cursor in UI coordinates.
+    position: [f64; 2],
+    modifiers: Modifiers,
+    stopped: bool,
+}
+
+impl MouseEvent {
+    pub fn new(kind: MouseEventKind, target: usize, position: [f64; 2], modifiers: Modifiers) -> MouseEvent {
+        Self { kind, target, current: target, phase: EventPhase::Target, position, modifiers, stopped: false }
+    }
+
+    pub fn kind(&self) -> MouseEventKind {
+        self.kind
+    }
+
+    ///The deepest element the event is about.
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    ///The element currently handling the event.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn phase(&self) -> EventPhase {
+        self.phase
+    }
+
+    ///Whether the element handling the event is its target.
+    pub fn at_target(&self) -> bool {
+        self.phase == EventPhase::Target
+    }
+
+    pub fn position(&self) -> [f64; 2] {
+        self.position
+    }
+
+    ///Cursor relative to the bottom left corner of `element`.
+    pub fn local_position(&self, element: &dyn UIElement) -> [f64; 2] {
+        element.relative_mouse_pos(self.position)
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    ///Elements after the current one no longer get the event. The remaining callbacks of the current element
+    ///still run.
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+///Modifier keys currently held.
+pub fn held_modifiers() -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    let held = |left, right| Input::is_key_held(left) || Input::is_key_held(right);
+    modifiers.set(Modifiers::Shift, held(Key::LeftShift, Key::RightShift));
+    modifiers.set(Modifiers::Control, held(Key::LeftControl, Key::RightControl));
+    modifiers.set(Modifiers::Alt, held(Key::LeftAlt, Key::RightAlt));
+    modifiers.set(Modifiers::Super, held(Key::LeftSuper, Key::RightSuper));
+    modifiers
+}
+
+///Pointer state the context needs to turn raw input into enter, leave, click and double click events.
+#[derive(Debug, Default)]
+pub(crate) struct PointerState {
+    ///Deepest element under the cursor.
+    pub(crate) hovered: Option<usize>,
+    ///Element each button went down on.
+    pub(crate) pressed: Vec<(MouseButton, usize)>,
+    ///Button, target, position and time of the last click.
+    pub(crate) last_click: Option<(MouseButton, usize, [f64; 2], Instant)>,
+}
+
+///Typed mouse events, dispatched DOM style. Raw button input still reaches the elements along the press through
+///`UIElement::update_mouse`, e.g. to end a drag released anywhere, and the cursor every element through
+///`UIElement::update_cursor`.
+impl UiContext {
+    ///Sends `event` from the root down to its target and, if it bubbles, back up again, calling
+    ///`UIElement::on_mouse_event` on the way. Mouse event callbacks run in the target and bubble phases.
+    ///Returns the event, to see whether it was stopped.
+    pub fn dispatch_mouse_event(&mut self, mut event: MouseEvent) -> MouseEvent {
+        //Handlers may press or release elements, which `:pressed` selectors see.
+        self.styles_dirty = true;
+        let mut path = self.ancestry(Some(event.target));
+        path.reverse();
+        let ancestors = path.len().saturating_sub(1);
+
+        let mut steps = vec![];
+        if event.kind.bubbles() {
+            steps.extend(path[..ancestors].iter().map(|id| (*id, EventPhase::Capture)));
+        }
+        steps.push((event.target, EventPhase::Target));
+        if event.kind.bubbles() {
+            steps.extend(path[..ancestors].iter().rev().map(|id| (*id, EventPhase::Bubble)));
+        }
+
+        for (id, phase) in steps {
+            event.current = id;
+            event.phase = phase;
+            self.with_element(&id, |element, ui| {
+                element.on_mouse_event(ui, &mut event);
+                if phase != EventPhase::Capture {
+                    let mut callbacks = std::mem::take(&mut element.element_data_mut().callbacks.on_mouse_event);
+                    for callback in &mut callbacks {
+                        (callback)(ui, element, &mut event);
+                    }
+                    //Keep callbacks that were registered while dispatching.
+                    callbacks.append(&mut element.element_data_mut().callbacks.on_mouse_event);
+                    element.element_data_mut().callbacks.on_mouse_event = callbacks;
+                }
+            });
+            if event.stopped {
+                break;
+            }
+        }
+        event
+    }
+
+    ///The deepest element under the cursor, as of the last `update_cursor`.
+    pub fn hovered(&self) -> Option<usize> {
+        self.pointer.hovered
+    }
+
+    ///The element itself and its ancestors, deepest first.
+    fn ancestry(&self, id: Option<usize>) -> Vec<usize> {
+        let mut ancestry = vec![];
+        let mut current = id;
+        while let Some(id) = current {
+            ancestry.push(id);
+            current = self.get_element_by_id(&id).and_then(|element| element.parent());
+        }
+        ancestry
+    }
+
+    ///Leave events for the elements the cursor left, deepest first, then enter events for the ones it entered,
+    ///outermost first.
+    pub(crate) fn move_pointer(&mut self, hovered: Option<usize>, position: [f64; 2]) {
+        if self.pointer.hovered == hovered {
+            return;
+        }
+        let old = self.ancestry(self.pointer.hovered);
+        let new = self.ancestry(hovered);
+        self.pointer.hovered = hovered;
+        let modifiers = held_modifiers();
+        for id in old.iter().filter(|id| !new.contains(id)) {
+            self.dispatch_mouse_event(MouseEvent::new(MouseEventKind::Leave, *id, position, modifiers));
+        }
+        for id in new.iter().rev().filter(|id| !old.contains(id)) {
+            self.dispatch_mouse_event(MouseEvent::new(MouseEventKind::Enter, *id, position, modifiers));
+        }
+    }
+
+    ///Drops removed elements from the pointer state. The cursor stays over `parent`, which it already entered.
+    pub(crate) fn forget_pointer(&mut self, removed: &Vec<usize>, parent: Option<usize>) {
+        if self.pointer.hovered.map(|hovered| removed.contains(&hovered)).unwrap_or(false) {
+            self.pointer.hovered = parent;
+        }
+        self.pointer.pressed.retain(|(_, pressed)| !removed.contains(pressed));
+        if self.pointer.last_click.map(|(_, clicked, _, _)| removed.contains(&clicked)).unwrap_or(false) {
+            self.pointer.last_click = None;
+        }
+    }
+
+    ///Elements that get the raw button input: the element under the cursor and its ancestors, deepest first,
+    ///followed on release by the ones the button went down on that are not among them yet.
+    pub(crate) fn pointer_path(&self, button: MouseButton, action: Action, position: [f64; 2]) -> Vec<usize> {
+        let mut path = self.ancestry(self.element_at(position));
+        if action == Action::Release {
+            let pressed = self.pointer.pressed.iter().find(|(pressed, _)| *pressed == button).map(|(_, id)| *id);
+            for id in self.ancestry(pressed) {
+                if !path.contains(&id) {
+                    path.push(id);
+                }
+            }
+        }
+        path
+    }
+
+    pub(crate) fn press_pointer(&mut self, button: MouseButton, position: [f64; 2]) {
+        self.pointer.pressed.retain(|(pressed, _)| *pressed != button);
+        if let Some(target) = self.element_at(position) {
+            self.pointer.pressed.push((button, target));
+            self.dispatch_mouse_event(MouseEvent::new(MouseEventKind::Down(button), target, position, held_modifiers()));
+        }
+    }
+
+    ///Up on the element under the cursor, then a click on the deepest element containing both ends of the press.
+    pub(crate) fn release_pointer(&mut self, button: MouseButton, position: [f64; 2]) {
+        let modifiers = held_modifiers();
+        let target = self.element_at(position);
+        if let Some(target) = target {
+            self.dispatch_mouse_event(MouseEvent::new(MouseEventKind::Up(button), target, position, modifiers));
+        }
+        let pressed = self.pointer.pressed.iter().position(|(pressed, _)| *pressed == button)
+            .map(|index| self.pointer.pressed.remove(index).1);
+        let released = self.ancestry(target);
+        let clicked = match self.ancestry(pressed).into_iter().find(|id| released.contains(id)) {
+            None => return,
+            Some(clicked) => clicked
+        };
+        self.dispatch_mouse_event(MouseEvent::new(MouseEventKind::Click(button), clicked, position, modifiers));
+
+        let now = Instant::now();
+        let double = match self.pointer.last_click {
+            Some((last_button, last_target, last_position, time)) => last_button == button && last_target == clicked
+                && now.duration_since(time) <= DOUBLE_CLICK_INTERVAL
+                && (position[0] - last_position[0]).hypot(position[1] - last_position[1]) <= DOUBLE_CLICK_DISTANCE,
+            None => false
+        };
+        match double {
+            true => {
+                //A third click starts over instead of making another double click.
+                self.pointer.last_click = None;
+                self.dispatch_mouse_event(MouseEvent::new(MouseEventKind::DoubleClick(button), clicked, position, modifiers));
+            }
+            false => self.pointer.last_click = Some((button, clicked, position, now))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::*;
+    use crate::gfx::ui::{ui_counter, UIElementData};
+    use crate::gfx::ui::shape::shape_contains;
+    use crate::gfx::ui::tests::with_recording_context;
+
+    type Log = Rc<RefCell<Vec<(usize, EventPhase, MouseEventKind)>>>;
+
+    ///Logs every event reaching it and stops the ones it gets in phase `stop_in`.
+    #[derive(Debug)]
+    struct Probe {
+        data: UIElementData,
+        log: Log,
+        stop_in: Option<EventPhase>,
+    }
+
+    impl Probe {
+        fn new(log: &Log, position: [u32; 2], size: u32) -> Probe {
+            Self { data: UIElementData::new(ui_counter(), position, [size; 2]), log: log.clone(), stop_in: None }
+        }
+
+        fn with_child(mut self, child: Probe) -> Probe {
+            self.add_child(Box::new(child));
+            self
+        }
+    }
+
+    impl UIElement for Probe {
+        fn tag(&self) -> &'static str {
+            "Probe"
+        }
+
+        fn element_data(&self) -> &UIElementData {
+            &self.data
+        }
+
+        fn element_data_mut(&mut self) -> &mut UIElementData {
+            &mut self.data
+        }
+
+        fn resize(&mut self, _size: [i32; 2]) {}
+
+        fn contains_point(&self, point: [f64; 2]) -> bool {
+            shape_contains(self, point)
+        }
+
+        fn on_mouse_event(&mut self, _ui: &mut UiContext, event: &mut MouseEvent) {
+            self.log.borrow_mut().push((self.id(), event.phase(), event.kind()));
+            if self.stop_in == Some(event.phase()) {
+                event.stop_propagation();
+            }
+        }
+    }
+
+    ///Ids of `outer`, `middle` and `inner`, nested in that order, and of `other`, a second child of `outer`.
+    fn register_tree(ui: &mut UiContext, log: &Log) -> [usize; 4] {
+        let inner = Probe::new(log, [20, 20], 10);
+        let middle = Probe::new(log, [10, 10], 40);
+        let other = Probe::new(log, [60, 60], 30);
+        let (middle_id, inner_id, other_id) = (middle.id(), inner.id(), other.id());
+        let outer = Probe::new(log, [0, 0], 100).with_child(middle.with_child(inner)).with_child(other);
+        [ui.register(outer).unwrap(), middle_id, inner_id, other_id]
+    }
+
+    ///Kind and target of the events that reached their target, in order.
+    fn targets(log: &Log) -> Vec<(MouseEventKind, usize)> {
+        log.borrow().iter().filter(|(_, phase, _)| *phase == EventPhase::Target).map(|(id, _, kind)| (*kind, *id)).collect()
+    }
+
+    #[test]
+    fn bubbling_events_run_capture_target_and_bubble() {
+        with_recording_context(|ui| {
+            let log = Log::default();
+            let [outer, middle, inner, _] = register_tree(ui, &log);
+            let down = MouseEventKind::Down(MouseButton::Button1);
+
+            ui.press_pointer(MouseButton::Button1, [25.0, 25.0]);
+            assert_eq!(*log.borrow(), vec![
+                (outer, EventPhase::Capture, down),
+                (middle, EventPhase::Capture, down),
+                (inner, EventPhase::Target, down),
+                (middle, EventPhase::Bubble, down),
+                (outer, EventPhase::Bubble, down),
+            ]);
+        });
+    }
+
+    #[test]
+    fn stop_propagation_ends_the_event_at_the_current_element() {
+        with_recording_context(|ui| {
+            let log = Log::default();
+            let [outer, middle, inner, _] = register_tree(ui, &log);
+            let down = MouseEventKind::Down(MouseButton::Button1);
+
+            ui.get_element_by_id_mut(&middle).unwrap().downcast_mut::<Probe>().unwrap().stop_in = Some(EventPhase::Capture);
+            let event = ui.dispatch_mouse_event(MouseEvent::new(down, inner, [25.0, 25.0], Modifiers::empty()));
+            assert!(event.is_propagation_stopped());
+            assert_eq!(event.current(), middle);
+            assert_eq!(*log.borrow(), vec![(outer, EventPhase::Capture, down), (middle, EventPhase::Capture, down)]);
+
+            log.borrow_mut().clear();
+            ui.get_element_by_id_mut(&middle).unwrap().downcast_mut::<Probe>().unwrap().stop_in = Some(EventPhase::Bubble);
+            ui.dispatch_mouse_event(MouseEvent::new(down, inner, [25.0, 25.0], Modifiers::empty()));
+            assert_eq!(log.borrow().last(), Some(&(middle, EventPhase::Bubble, down)));
+            assert!(!log.borrow().iter().any(|(id, phase, _)| *id == outer && *phase == EventPhase::Bubble));
+        });
+    }
+
+    #[test]
+    fn moving_the_cursor_leaves_deepest_first_and_enters_outermost_first() {
+        with_recording_context(|ui| {
+            let log = Log::default();
+            let [outer, middle, inner, other] = register_tree(ui, &log);
+
+            ui.update_cursor([25.0, 25.0]);
+            assert_eq!(ui.hovered(), Some(inner));
+            assert_eq!(*log.borrow(), vec![
+                (outer, EventPhase::Target, MouseEventKind::Enter),
+                (middle, EventPhase::Target, MouseEventKind::Enter),
+                (inner, EventPhase::Target, MouseEventKind::Enter),
+            ]);
+
+            log.borrow_mut().clear();
+            ui.update_cursor([65.0, 65.0]);
+            assert_eq!(*log.borrow(), vec![
+                (inner, EventPhase::Target, MouseEventKind::Leave),
+                (middle, EventPhase::Target, MouseEventKind::Leave),
+                (other, EventPhase::Target, MouseEventKind::Enter),
+            ]);
+
+            log.borrow_mut().clear();
+            ui.update_cursor([66.0, 66.0]);
+            assert!(log.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn click_targets_the_deepest_element_containing_press_and_release() {
+        with_recording_context(|ui| {
+            let log = Log::default();
+            let [outer, _, inner, other] = register_tree(ui, &log);
+            let button = MouseButton::Button1;
+
+            ui.press_pointer(button, [25.0, 25.0]);
+            ui.release_pointer(button, [65.0, 65.0]);
+            assert_eq!(targets(&log), vec![
+                (MouseEventKind::Down(button), inner),
+                (MouseEventKind::Up(button), other),
+                (MouseEventKind::Click(button), outer),
+            ]);
+
+            //Released off every element, so there is no up event and no click.
+            log.borrow_mut().clear();
+            ui.press_pointer(button, [25.0, 25.0]);
+            ui.release_pointer(button, [150.0, 150.0]);
+            assert_eq!(targets(&log), vec![(MouseEventKind::Down(button), inner)]);
+        });
+    }
+
+    #[test]
+    fn two_quick_clicks_make_a_double_click_and_a_third_starts_over() {
+        with_recording_context(|ui| {
+            let log = Log::default();
+            let [_, _, inner, _] = register_tree(ui, &log);
+            let button = MouseButton::Button1;
+
+            for _ in 0..3 {
+                ui.press_pointer(button, [25.0, 25.0]);
+                ui.release_pointer(button, [26.0, 25.0]);
+            }
+            let clicks: Vec<MouseEventKind> = targets(&log).into_iter()
+                .filter(|(kind, id)| *id == inner && matches!(kind, MouseEventKind::Click(_) | MouseEventKind::DoubleClick(_)))
+                .map(|(kind, _)| kind)
+                .collect();
+            assert_eq!(clicks, vec![
+                MouseEventKind::Click(button),
+                MouseEventKind::Click(button),
+                MouseEventKind::DoubleClick(button),
+                MouseEventKind::Click(button),
+            ]);
+        });
+    }
+}
@@ -0,0 +1,399 @@
+use vecmath::Vector4;
+use crate::gfx::ui::{UiContext, UIElement};
+use crate::gfx::ui::callbacks::OnFocus;
+use crate::gfx::ui::tree::UiTreeError;
+use crate::{Camera, CustomUIProperty, Rectangle};
+
+///Color of the focus ring around elements without a "focus:color" property.
+pub const DEFAULT_FOCUS_COLOR: Vector4<f32> = [0.25, 0.55, 1.0, 1.0];
+///Thickness of the focus ring in pixels.
+pub const FOCUS_RING_WIDTH: u32 = 2;
+///Gap between the focused element and its ring in pixels.
+pub const FOCUS_RING_OFFSET: u32 = 2;
+
+///Outline drawn around the focused element, one rectangle per edge.
+pub struct FocusRing {
+    edges: [Rectangle; 4],
+    ///The element the edges were placed around, if it is still focused.
+    target: Option<usize>,
+}
+
+impl FocusRing {
+    ///Requires a current GL context.
+    pub fn new() -> FocusRing {
+        Self {
+            edges: [Rectangle::default(), Rectangle::default(), Rectangle::default(), Rectangle::default()],
+            target: None,
+        }
+    }
+
+    ///Surrounds the rectangle at `position` of `size`, keeping the ring on screen.
+    pub fn place(&mut self, target: usize, position: [u32; 2], size: [u32; 2], color: Vector4<f32>) {
+        let inset = FOCUS_RING_WIDTH + FOCUS_RING_OFFSET;
+        let x = position[0].saturating_sub(inset);
+        let y = position[1].saturating_sub(inset);
+        let width = position[0] + size[0] + inset - x;
+        let height = position[1] + size[1] + inset - y;
+        let edges = [
+            ([x, y], [width, FOCUS_RING_WIDTH]),
+            ([x, y + height - FOCUS_RING_WIDTH], [width, FOCUS_RING_WIDTH]),
+            ([x, y], [FOCUS_RING_WIDTH, height]),
+            ([x + width - FOCUS_RING_WIDTH, y], [FOCUS_RING_WIDTH, height]),
+        ];
+        for (edge, (position, size)) in self.edges.iter_mut().zip(edges) {
+            edge.set_position(position);
+            edge.element_data_mut().width = size[0];
+            edge.element_data_mut().height = size[1];
+            edge.set_color(color);
+        }
+        self.target = Some(target);
+    }
+
+    pub fn hide(&mut self) {
+        self.target = None;
+    }
+
+    pub fn target(&self) -> Option<usize> {
+        self.target
+    }
+
+    pub fn draw(&self, ui: &UiContext, camera: &Camera) {
+        if self.target.is_some() {
+            for edge in &self.edges {
+                edge.draw(ui, camera);
+            }
+        }
+    }
+}
+
+///Keyboard focus. At most one element is focused. It receives keys and typed characters, see
+///`UiContext::update_key`, and is outlined by the focus ring.
+impl UiContext {
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    pub fn is_focused(&self, id: &usize) -> bool {
+        self.focused == Some(*id)
+    }
+
+    ///Moves the focus to the element, notifying the previously focused element first.
+    ///An element currently taken out by `with_element` is not notified, see `UIElement::on_focus`.
+    pub fn focus(&mut self, id: &usize) -> Result<(), UiTreeError> {
+        match self.get_element_by_id(id) {
+            None => Err(UiTreeError::UnknownElement(*id)),
+            Some(element) if !element.focusable() => Err(UiTreeError::NotFocusable(*id)),
+            Some(_) => {
+                self.change_focus(Some(*id));
+                Ok(())
+            }
+        }
+    }
+
+    ///Clears the focus, so keys go nowhere until an element is focused again.
+    pub fn blur(&mut self) {
+        self.change_focus(None);
+    }
+
+    ///Focuses the next focusable element in tree order, wrapping around. Bound to tab.
+    pub fn focus_next(&mut self) {
+        self.step_focus(false);
+    }
+
+    ///Focuses the previous focusable element in tree order, wrapping around. Bound to shift and tab.
+    pub fn focus_previous(&mut self) {
+        self.step_focus(true);
+    }
+
+    ///Focusable elements in tree order: each parent before its children, siblings in child order.
+    ///Unlike `draw_order`, z-indices do not matter.
+    pub fn focus_order(&self) -> Vec<usize> {
+        let mut order = vec![];
+        for root in &self.roots {
+            self.push_focus_order(root, &mut order);
+        }
+        order
+    }
+
+    fn push_focus_order(&self, id: &usize, order: &mut Vec<usize>) {
+        if let Some(element) = self.get_element_by_id(id) {
+            if element.focusable() {
+                order.push(*id);
+            }
+            for child in element.children() {
+                self.push_focus_order(child, order);
+            }
+        }
+    }
+
+    fn step_focus(&mut self, backwards: bool) {
+        let order = self.focus_order();
+        if order.is_empty() {
+            return;
+        }
+        let next = match self.focused.and_then(|focused| order.iter().position(|id| *id == focused)) {
+            None if backwards => order.len() - 1,
+            None => 0,
+            Some(index) if backwards => (index + order.len() - 1) % order.len(),
+            Some(index) => (index + 1) % order.len(),
+        };
+        self.change_focus(Some(order[next]));
+    }
+
+    ///The element itself or its closest focusable ancestor, e.g. the widget a clicked part belongs to.
+    pub(crate) fn focus_target(&self, id: Option<usize>) -> Option<usize> {
+        let mut current = id;
+        while let Some(id) = current {
+            let element = self.get_element_by_id(&id)?;
+            if element.focusable() {
+                return Some(id);
+            }
+            current = element.parent();
+        }
+        None
+    }
+
+    pub(crate) fn change_focus(&mut self, focused: Option<usize>) {
+        if self.focused == focused {
+            return;
+        }
+        let old = std::mem::replace(&mut self.focused, focused);
+        self.styles_dirty = true;
+        if let Some(old) = old {
+            self.notify_focus(&old, false);
+        }
+        if let Some(focused) = focused {
+            self.notify_focus(&focused, true);
+        }
+    }
+
+    fn notify_focus(&mut self, id: &usize, focused: bool) {
+        self.with_element(id, |element, ui| {
+            match focused {
+                true => element.on_focus(ui),
+                false => element.on_blur(ui),
+            }
+            let mut callbacks = std::mem::take(focus_callbacks(element, focused));
+            for callback in &mut callbacks {
+                (callback)(ui, element);
+            }
+            //Keep callbacks that were registered while dispatching.
+            callbacks.append(focus_callbacks(element, focused));
+            *focus_callbacks(element, focused) = callbacks;
+        });
+    }
+
+    ///Places the focus ring around the focused element, in the color of its "focus:color" property.
+    pub(crate) fn place_focus_ring(&mut self) {
+        let focused = match self.focused.and_then(|id| self.get_element_by_id(&id)) {
+            None => {
+                self.focus_ring.hide();
+                return;
+            }
+            Some(focused) => focused
+        };
+        let color = match focused.get_custom_property(&"focus:color") {
+            None => DEFAULT_FOCUS_COLOR,
+            Some(CustomUIProperty::Vec4(color)) => *color
+        };
+        let data = focused.element_data();
+        let (id, position, size) = (data.id(), data.position(), [data.width(), data.height()]);
+        self.focus_ring.place(id, position, size, color);
+    }
+
+    ///Draws the focus ring on top of everything, clipped like the focused element.
+    pub(crate) fn draw_focus_ring(&self, camera: &Camera) {
+        //The ring is placed in `update`, the focus may have moved since.
+        let target = match self.focus_ring.target().filter(|target| self.is_focused(target)) {
+            None => return,
+            Some(target) => target
+        };
+        let mut clips = 0;
+        let mut ancestor = self.get_element_by_id(&target).and_then(|element| element.parent());
+        while let Some(id) = ancestor {
+            let element = match self.get_element_by_id(&id) {
+                None => break,
+                Some(element) => element
+            };
+            if element.clips_children() {
+                let data = element.element_data();
+                self.push_clip([data.position()[0] as i32, data.position()[1] as i32, data.width() as i32, data.height() as i32]);
+                clips += 1;
+            }
+            ancestor = element.parent();
+        }
+        self.focus_ring.draw(self, camera);
+        for _ in 0..clips {
+            self.pop_clip();
+        }
+    }
+}
+
+fn focus_callbacks(element: &mut Box<dyn UIElement>, focused: bool) -> &mut Vec<Box<OnFocus>> {
+    let callbacks = &mut element.element_data_mut().callbacks;
+    match focused {
+        true => &mut callbacks.on_focus,
+        false => &mut callbacks.on_blur,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use glfw::{Action, Key};
+    use super::*;
+    use crate::gfx::ui::{ui_counter, UIElementData};
+    use crate::gfx::ui::tests::with_recording_context;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Seen {
+        Key(Key),
+        Focus,
+        Blur,
+    }
+
+    type Log = Rc<RefCell<Vec<(usize, Seen)>>>;
+
+    ///Logs keys and focus changes, and handles `handles`.
+    #[derive(Debug)]
+    struct Field {
+        data: UIElementData,
+        log: Log,
+        focusable: bool,
+        handles: Option<Key>,
+    }
+
+    impl Field {
+        fn new(log: &Log, focusable: bool) -> Field {
+            Self { data: UIElementData::new(ui_counter(), [0; 2], [10; 2]), log: log.clone(), focusable, handles: None }
+        }
+
+        fn handling(mut self, key: Key) -> Field {
+            self.handles = Some(key);
+            self
+        }
+
+        fn with_child(mut self, child: Field) -> Field {
+            self.add_child(Box::new(child));
+            self
+        }
+    }
+
+    impl UIElement for Field {
+        fn tag(&self) -> &'static str {
+            "Field"
+        }
+
+        fn element_data(&self) -> &UIElementData {
+            &self.data
+        }
+
+        fn element_data_mut(&mut self) -> &mut UIElementData {
+            &mut self.data
+        }
+
+        fn resize(&mut self, _size: [i32; 2]) {}
+
+        fn contains_point(&self, _point: [f64; 2]) -> bool {
+            false
+        }
+
+        fn focusable(&self) -> bool {
+            self.focusable
+        }
+
+        fn on_focus(&mut self, _ui: &mut UiContext) {
+            self.log.borrow_mut().push((self.id(), Seen::Focus));
+        }
+
+        fn on_blur(&mut self, _ui: &mut UiContext) {
+            self.log.borrow_mut().push((self.id(), Seen::Blur));
+        }
+
+        fn update_key(&mut self, _ui: &mut UiContext, key: Key, _code: Action, _delta: f32) -> bool {
+            self.log.borrow_mut().push((self.id(), Seen::Key(key)));
+            self.handles == Some(key)
+        }
+    }
+
+    #[test]
+    fn tab_cycles_through_focusable_elements_in_tree_order() {
+        with_recording_context(|ui| {
+            let log = Log::default();
+            let nested = Field::new(&log, true);
+            let skipped = Field::new(&log, false);
+            let last = Field::new(&log, true);
+            let ids = [nested.id(), last.id()];
+            let first = ui.register(Field::new(&log, true).with_child(skipped.with_child(nested))).unwrap();
+            ui.register(last).unwrap();
+            assert_eq!(ui.focus_order(), vec![first, ids[0], ids[1]]);
+
+            let mut visited = vec![];
+            for _ in 0..4 {
+                ui.update_key(Key::Tab, Action::Press, 0f32);
+                visited.push(ui.focused().unwrap());
+            }
+            assert_eq!(visited, vec![first, ids[0], ids[1], first]);
+
+            ui.update_key(Key::Tab, Action::Release, 0f32);
+            assert_eq!(ui.focused(), Some(first));
+            ui.focus_previous();
+            assert_eq!(ui.focused(), Some(ids[1]));
+            ui.focus_previous();
+            assert_eq!(ui.focused(), Some(ids[0]));
+
+            ui.blur();
+            ui.focus_previous();
+            assert_eq!(ui.focused(), Some(ids[1]));
+        });
+    }
+
+    #[test]
+    fn keys_bubble_from_the_focused_element_until_handled() {
+        with_recording_context(|ui| {
+            let log = Log::default();
+            let inner = Field::new(&log, true).handling(Key::Tab);
+            let middle = Field::new(&log, false);
+            let ids = [inner.id(), middle.id()];
+            let outer = ui.register(Field::new(&log, true).handling(Key::Enter).with_child(middle.with_child(inner))).unwrap();
+            ui.focus(&ids[0]).unwrap();
+            log.borrow_mut().clear();
+
+            ui.update_key(Key::Enter, Action::Press, 0f32);
+            assert_eq!(*log.borrow(), vec![(ids[0], Seen::Key(Key::Enter)), (ids[1], Seen::Key(Key::Enter)), (outer, Seen::Key(Key::Enter))]);
+
+            //Handled by the focused element, so the focus stays.
+            log.borrow_mut().clear();
+            ui.update_key(Key::Tab, Action::Press, 0f32);
+            assert_eq!(*log.borrow(), vec![(ids[0], Seen::Key(Key::Tab))]);
+            assert_eq!(ui.focused(), Some(ids[0]));
+
+            assert_eq!(ui.focus(&ids[1]).unwrap_err(), UiTreeError::NotFocusable(ids[1]));
+        });
+    }
+
+    #[test]
+    fn removing_the_focused_subtree_blurs() {
+        with_recording_context(|ui| {
+            let log = Log::default();
+            let inner = Field::new(&log, true);
+            let middle = Field::new(&log, false);
+            let ids = [inner.id(), middle.id()];
+            let outer = ui.register(Field::new(&log, true).with_child(middle.with_child(inner))).unwrap();
+
+            ui.focus(&outer).unwrap();
+            ui.focus(&ids[0]).unwrap();
+            assert_eq!(*log.borrow(), vec![(outer, Seen::Focus), (outer, Seen::Blur), (ids[0], Seen::Focus)]);
+
+            log.borrow_mut().clear();
+            ui.remove(&ids[1]).unwrap();
+            assert_eq!(ui.focused(), None);
+            assert_eq!(*log.borrow(), vec![(ids[0], Seen::Blur)]);
+
+            //With nothing focused, tab starts over at the first element.
+            ui.update_key(Key::Tab, Action::Press, 0f32);
+            assert_eq!(ui.focused(), Some(outer));
+        });
+    }
+}
@@ -0,0 +1,211 @@
+use std::env;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use crate::Camera;
+use crate::gfx::bindings::{ColorFlags, RenderbufferFormat};
+use crate::gfx::bindings::graphics::{clear, clear_color};
+use crate::gfx::image::{decoder, encoder, Image, ImageError};
+use crate::gfx::image::compare::compare;
+use crate::gfx::image::decoder::ImageOptions;
+use crate::gfx::render::framebuffer::{Framebuffer, FramebufferError};
+use crate::gfx::texture::PixelFormat;
+use crate::gfx::ui::UiContext;
+
+///Setting this environment variable makes [`GoldenTest::check`] (re)write the reference images instead of failing.
+pub const UPDATE_GOLDEN_VARIABLE: &'static str = "UPDATE_GOLDEN";
+///Relative to the working directory, which `cargo test` sets to the package root. Failure output written next to
+///the references is ignored by git.
+pub const DEFAULT_GOLDEN_DIRECTORY: &'static str = "test-fixtures/golden";
+
+#[derive(Debug)]
+pub enum GoldenError {
+    Framebuffer(FramebufferError),
+    Image(ImageError),
+    ///No reference image exists yet. The rendered image was written next to where it is expected.
+    MissingReference(PathBuf),
+    ///Expected and actual size.
+    SizeMismatch([u32; 2], [u32; 2]),
+    ///Number of mismatched pixels, the largest channel difference and the path of the written diff image.
+    Mismatch(usize, u8, PathBuf),
+}
+
+impl Display for GoldenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenError::Framebuffer(error) => f.write_fmt(format_args!("{}", error)),
+            GoldenError::Image(error) => f.write_fmt(format_args!("{}", error)),
+            GoldenError::MissingReference(path) => {
+                f.write_fmt(format_args!("No reference image at {}. Run with {}=1 to create it.", path.display(), UPDATE_GOLDEN_VARIABLE))
+            }
+            GoldenError::SizeMismatch(expected, actual) => {
+                f.write_fmt(format_args!("Reference image is {}x{}, rendered image is {}x{}", expected[0], expected[1], actual[0], actual[1]))
+            }
+            GoldenError::Mismatch(pixels, difference, diff) => {
+                f.write_fmt(format_args!("{} pixels differ (max channel difference {}), see {}", pixels, difference, diff.display()))
+            }
+        }
+    }
+}
+
+impl Error for GoldenError {}
+
+impl From<FramebufferError> for GoldenError {
+    fn from(error: FramebufferError) -> Self {
+        GoldenError::Framebuffer(error)
+    }
+}
+
+impl From<ImageError> for GoldenError {
+    fn from(error: ImageError) -> Self {
+        GoldenError::Image(error)
+    }
+}
+
+///Renders a UI context offscreen at a fixed size and compares it against `<directory>/<name>.png`.
+///On failure `<name>.actual.png` and `<name>.diff.png` are written next to the reference.
+///
+///Needs a current GL context (a hidden window on a software driver such as Mesa works) and an initialized `Input`.
+#[derive(Debug, Clone)]
+pub struct GoldenTest {
+    name: String,
+    size: [u32; 2],
+    tolerance: u8,
+    directory: PathBuf,
+    clear_color: [f32; 4],
+}
+
+impl GoldenTest {
+    pub fn new<T: ToString>(name: T, size: [u32; 2]) -> GoldenTest {
+        Self {
+            name: name.to_string(),
+            size,
+            tolerance: 2,
+            directory: PathBuf::from(DEFAULT_GOLDEN_DIRECTORY),
+            clear_color: [1.0; 4],
+        }
+    }
+
+    ///The largest difference allowed per channel, to absorb rounding differences between drivers.
+    pub fn with_tolerance(mut self, tolerance: u8) -> GoldenTest {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_directory<P: AsRef<Path>>(mut self, directory: P) -> GoldenTest {
+        self.directory = directory.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn with_clear_color(mut self, clear_color: [f32; 4]) -> GoldenTest {
+        self.clear_color = clear_color;
+        self
+    }
+
+    pub fn reference_path(&self) -> PathBuf {
+        self.directory.join(format!("{}.png", self.name))
+    }
+
+    fn output_path(&self, suffix: &str) -> PathBuf {
+        self.directory.join(format!("{}.{}.png", self.name, suffix))
+    }
+
+    ///Lays out and draws the UI into an offscreen target and reads it back in top-down order.
+    pub fn render(&self, ui: &mut UiContext) -> Result<Image, GoldenError> {
+        let target = Framebuffer::new(self.size)
+            .with_color_attachment(PixelFormat::Rgba8)
+            .with_depth_stencil(RenderbufferFormat::Depth24Stencil8)
+            .build()?;
+        let draw_size = [self.size[0] as i32, self.size[1] as i32];
+        let camera = Camera::new(90f32, 100f32, 0.1f32, draw_size);
+
+        ui.update_screen_size(draw_size);
+        ui.update(0f32);
+
+        target.render(|| {
+            clear_color(self.clear_color);
+            clear(ColorFlags::ColorBufferBit | ColorFlags::DepthBufferBit);
+            ui.draw(&camera);
+        });
+        Ok(target.read_pixels(0))
+    }
+
+    pub fn check(&self, ui: &mut UiContext) -> Result<(), GoldenError> {
+        let actual = self.render(ui)?;
+        let reference = self.reference_path();
+        let update = env::var_os(UPDATE_GOLDEN_VARIABLE).is_some();
+
+        if !reference.exists() {
+            if update {
+                encoder::save_png(&actual, &reference)?;
+                return Ok(());
+            }
+            encoder::save_png(&actual, self.output_path("actual"))?;
+            return Err(GoldenError::MissingReference(reference));
+        }
+
+        let expected = decoder::load(&reference, &ImageOptions::new().with_flip_vertically(false))?;
+        let comparison = match compare(&expected, &actual, self.tolerance) {
+            None => {
+                if update {
+                    encoder::save_png(&actual, &reference)?;
+                    return Ok(());
+                }
+                return Err(GoldenError::SizeMismatch(expected.size(), actual.size()));
+            }
+            Some(comparison) => comparison
+        };
+        if comparison.matches() {
+            return Ok(());
+        }
+        if update {
+            encoder::save_png(&actual, &reference)?;
+            return Ok(());
+        }
+
+        let diff = self.output_path("diff");
+        encoder::save_png(&actual, self.output_path("actual"))?;
+        encoder::save_png(comparison.diff(), &diff)?;
+        Err(GoldenError::Mismatch(comparison.mismatched_pixels(), comparison.max_difference(), diff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomUIProperty, Input, Rectangle};
+    use crate::gfx::ui::container::v_box::VBox;
+    use crate::gfx::ui::interactable::slider::Slider;
+    use crate::gfx::ui::layout::Layout;
+    use crate::gfx::ui::layout::engine::Insets;
+    use crate::glfw_support::headless_context;
+
+    const FIXTURES: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/test-fixtures/golden");
+
+    #[test]
+    #[ignore = "needs a GL 3.3 context, run with --ignored on a machine with a (software) driver"]
+    fn vbox_with_rounded_rectangle_and_slider() {
+        let size = [300, 140];
+        let (_glfw, _window) = headless_context(size).unwrap();
+        Input::init();
+
+        let mut ui = UiContext::new();
+        let rectangle = Rectangle::new([0; 2], [120f32, 40f32])
+            .with_corner_radius(12f32)
+            .with_custom_property(&"color", CustomUIProperty::Vec4([0.2, 0.4, 0.8, 1.0]));
+        let vbox = VBox::new()
+            .with_horizontal(Layout::WrapContent)
+            .with_vertical(Layout::WrapContent)
+            .with_padding(Insets::all(10f32))
+            .with_spacing(10)
+            .with_child(rectangle)
+            .with_child(Slider::default().with_value(0.5));
+        ui.register(vbox).unwrap();
+
+        let result = GoldenTest::new("vbox_rectangle_slider", size)
+            .with_directory(FIXTURES)
+            .check(&mut ui);
+        drop(ui);
+        result.unwrap();
+    }
+}
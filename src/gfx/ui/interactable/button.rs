@@ -0,0 +1,271 @@
+use std::fmt::{Debug, Formatter};
+use glfw::{Action, Key, MouseButton};
+use vecmath::Vector4;
+use crate::gfx::ui::{DEFAULT_COLOR, quad_render_data, ui_counter, UiContext, UIElement, UIElementData};
+use crate::gfx::ui::callbacks::OnClick;
+use crate::gfx::ui::event::{EventPhase, MouseEvent, MouseEventKind};
+use crate::gfx::ui::shape::shape_contains;
+use crate::gfx::ui::interactable::{child_hovered, is_activation_key};
+use crate::gfx::ui::label::Label;
+use crate::{Camera, CustomUIProperty};
+
+///Gap between icon and label.
+const ICON_SPACING: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ButtonState {
+    Normal,
+    Hovered,
+    ///Pressed with the cursor still over the button.
+    Pressed,
+    Disabled,
+}
+
+impl ButtonState {
+    ///Custom property holding the color of this state. States without one fall back to `color`.
+    pub fn color_property(&self) -> &'static str {
+        match self {
+            ButtonState::Normal => "color",
+            ButtonState::Hovered => "hover:color",
+            ButtonState::Pressed => "pressed:color",
+            ButtonState::Disabled => "disabled:color",
+        }
+    }
+}
+
+///A clickable rectangle with an optional label and icon, centered next to each other with the icon first.
+///A click is a press and release of the primary mouse button, both over the button, or space or enter while focused.
+pub struct Button {
+    data: UIElementData,
+    label: Option<usize>,
+    icon: Option<usize>,
+    pressed: bool,
+    disabled: bool,
+    on_click: Vec<Box<OnClick>>,
+}
+
+impl Debug for Button {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Button [state: {:?}, data: {:?}, callbacks: {}]", self.state(), self.data, self.on_click.len()))
+    }
+}
+
+impl Button {
+    pub fn new(size: [u32; 2]) -> Button {
+        Self {
+            data: UIElementData::new(ui_counter(), [0; 2], size).with_render_data(quad_render_data()),
+            label: None,
+            icon: None,
+            pressed: false,
+            disabled: false,
+            on_click: vec![],
+        }
+    }
+
+    pub fn with_position(mut self, position: [u32; 2]) -> Button {
+        self.set_position(position);
+        self
+    }
+
+    ///Replaces the label. Only works before the button is registered.
+    pub fn with_label(mut self, label: Label) -> Button {
+        self.label = Some(label.id());
+        self.add_child(Box::new(label));
+        self
+    }
+
+    pub fn with_text<T: ToString>(self, text: T, font_size: f32) -> Button {
+        self.with_label(Label::new(text, font_size))
+    }
+
+    ///Replaces the icon. Only works before the button is registered.
+    pub fn with_icon<E: UIElement>(mut self, icon: E) -> Button {
+        self.icon = Some(icon.id());
+        self.add_child(Box::new(icon));
+        self
+    }
+
+    pub fn with_on_click(mut self, callback: Box<OnClick>) -> Button {
+        self.register_on_click(callback);
+        self
+    }
+
+    pub fn with_state_color(mut self, state: ButtonState, color: Vector4<f32>) -> Button {
+        self.set_custom_property(&state.color_property(), CustomUIProperty::Vec4(color));
+        self
+    }
+
+    pub fn with_corner_radius(mut self, corner_radius: f32) -> Button {
+        self.data.set_corner_radius(corner_radius);
+        self
+    }
+
+    pub fn with_disabled(mut self, disabled: bool) -> Button {
+        self.set_disabled(disabled);
+        self
+    }
+
+    pub fn register_on_click(&mut self, callback: Box<OnClick>) {
+        self.on_click.push(callback);
+    }
+
+    ///Fires the click callbacks, as if the button was clicked. Does nothing while disabled.
+    pub fn click(&mut self, ui: &mut UiContext) {
+        if self.disabled {
+            return;
+        }
+        let mut callbacks = std::mem::take(&mut self.on_click);
+        for callback in &mut callbacks {
+            (callback)(ui, self);
+        }
+        callbacks.append(&mut self.on_click);
+        self.on_click = callbacks;
+    }
+
+    pub fn state(&self) -> ButtonState {
+        match (self.disabled, self.pressed, self.data.hover_flag) {
+            (true, _, _) => ButtonState::Disabled,
+            (false, true, true) => ButtonState::Pressed,
+            (false, _, true) => ButtonState::Hovered,
+            (false, _, false) => ButtonState::Normal,
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    ///Disabling also cancels a press in progress.
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if disabled {
+            self.pressed = false;
+        }
+        self.data.mark_style_dirty();
+    }
+
+    pub fn label<'a>(&self, ui: &'a UiContext) -> Option<&'a Label> {
+        ui.get_element_by_id(self.label.as_ref()?)?.downcast_ref::<Label>()
+    }
+
+    pub fn label_mut<'a>(&self, ui: &'a mut UiContext) -> Option<&'a mut Label> {
+        ui.get_element_by_id_mut(self.label.as_ref()?)?.downcast_mut::<Label>()
+    }
+
+    pub fn icon<'a>(&self, ui: &'a UiContext) -> Option<&'a Box<dyn UIElement>> {
+        ui.get_element_by_id(self.icon.as_ref()?)
+    }
+
+    ///Centers icon and label, next to each other.
+    fn arrange_content(&self, ui: &mut UiContext) {
+        let size = |id: &Option<usize>| id.and_then(|id| ui.get_element_by_id(&id))
+            .map(|element| [element.element_data().width(), element.element_data().height()]);
+        let icon = size(&self.icon);
+        let label = size(&self.label);
+        let gap = match (icon, label) {
+            (Some(_), Some(_)) => ICON_SPACING,
+            _ => 0
+        };
+        let content = icon.map(|icon| icon[0]).unwrap_or(0) + gap + label.map(|label| label[0]).unwrap_or(0);
+
+        let position = self.data.position;
+        let mut x = position[0] + self.data.width.saturating_sub(content) / 2;
+        for (id, size) in [(self.icon, icon), (self.label, label)] {
+            if let (Some(id), Some(size)) = (id, size) {
+                if let Some(element) = ui.get_element_by_id_mut(&id) {
+                    element.set_position([x, position[1] + self.data.height.saturating_sub(size[1]) / 2]);
+                }
+                x += size[0] + gap;
+            }
+        }
+    }
+}
+
+impl UIElement for Button {
+    fn tag(&self) -> &'static str {
+        "Button"
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, point: [f64; 2]) -> bool {
+        shape_contains(self, point)
+    }
+
+    fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    ///The cursor being over the label or icon counts as being over the button.
+    fn update_cursor(&mut self, ui: &mut UiContext, _cursor: [f64; 2]) {
+        if child_hovered(ui, &self.data.children) {
+            self.data.hover_flag = true;
+        }
+    }
+
+    ///Presses and clicks on the label or icon bubble up to the button.
+    fn on_mouse_event(&mut self, ui: &mut UiContext, event: &mut MouseEvent) {
+        if self.disabled || event.phase() == EventPhase::Capture {
+            return;
+        }
+        match event.kind() {
+            MouseEventKind::Down(MouseButton::Button1) => self.pressed = true,
+            MouseEventKind::Click(MouseButton::Button1) => {
+                self.click(ui);
+                event.stop_propagation();
+            }
+            _ => {}
+        }
+    }
+
+    ///The press ends wherever the button is released.
+    fn update_mouse(&mut self, _ui: &mut UiContext, mouse_button: MouseButton, action: Action) {
+        if mouse_button == MouseButton::Button1 && action == Action::Release {
+            self.pressed = false;
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn update_key(&mut self, ui: &mut UiContext, key: Key, code: Action, _delta: f32) -> bool {
+        if !is_activation_key(key) {
+            return false;
+        }
+        if code == Action::Press {
+            self.click(ui);
+        }
+        true
+    }
+
+    fn update(&mut self, ui: &mut UiContext, _delta: f32) {
+        self.arrange_content(ui);
+    }
+
+    fn color(&self) -> Vector4<f32> {
+        match self.get_custom_property(&self.state().color_property()).or_else(|| self.get_custom_property(&"color")) {
+            None => DEFAULT_COLOR,
+            Some(CustomUIProperty::Vec4(color)) => *color
+        }
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        self.draw_self(ui, camera);
+        for child in ui.children_in_draw_order(&self.id()) {
+            child.draw(ui, camera);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt::{Debug, Formatter};
+use glfw::{Action, Key, MouseButton};
+use vecmath::Vector4;
+use crate::gfx::ui::{ui_counter, UiContext, UIElement, UIElementData};
+use crate::gfx::ui::event::{EventPhase, MouseEvent, MouseEventKind};
+use crate::gfx::ui::shape::shape_contains;
+use crate::gfx::ui::callbacks::OnCheckboxValueChanged;
+use crate::gfx::ui::interactable::{arrange_indicator_row, child_hovered, is_activation_key};
+use crate::gfx::ui::label::Label;
+use crate::gfx::ui::markup::reload::ElementState;
+use crate::{Camera, CustomUIProperty, Rectangle};
+
+///A box with a mark drawn while it is checked, and an optional label to its right.
+///Toggled by clicking the box or the label, or with space or enter while focused.
+pub struct Checkbox {
+    data: UIElementData,
+    checked: bool,
+    frame: usize,
+    mark: usize,
+    label: Option<usize>,
+    on_value_changed: Vec<Box<OnCheckboxValueChanged>>,
+}
+
+impl Debug for Checkbox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Checkbox [checked: {}, data: {:?}, callbacks: {}]", self.checked, self.data, self.on_value_changed.len()))
+    }
+}
+
+impl Checkbox {
+    ///A box of `size` pixels.
+    pub fn new(size: u32) -> Checkbox {
+        let frame = Rectangle::new([0, 0], [size as f32; 2])
+            .with_custom_property(&"color", CustomUIProperty::Vec4([0.6, 0.6, 0.6, 1.0]))
+            .with_corner_radius(size as f32 / 6f32)
+            .with_class("checkbox-frame");
+        let mark_size = (size / 2) as f32;
+        let mark = Rectangle::new([0, 0], [mark_size; 2])
+            .with_custom_property(&"color", CustomUIProperty::Vec4([0.2, 0.2, 0.2, 1.0]))
+            .with_corner_radius(mark_size / 6f32)
+            .with_class("checkbox-mark");
+
+        let mut checkbox = Self {
+            data: UIElementData::new(ui_counter(), [0; 2], [size, size]),
+            checked: false,
+            frame: frame.id(),
+            mark: mark.id(),
+            label: None,
+            on_value_changed: vec![],
+        };
+        checkbox.add_child(Box::new(frame));
+        checkbox.add_child(Box::new(mark));
+        checkbox
+    }
+
+    pub fn with_position(mut self, position: [u32; 2]) -> Checkbox {
+        self.set_position(position);
+        self
+    }
+
+    ///Replaces the label. Only works before the checkbox is registered.
+    pub fn with_label(mut self, label: Label) -> Checkbox {
+        self.label = Some(label.id());
+        self.add_child(Box::new(label));
+        self
+    }
+
+    pub fn with_text<T: ToString>(self, text: T, font_size: f32) -> Checkbox {
+        self.with_label(Label::new(text, font_size))
+    }
+
+    ///Sets the initial state without firing callbacks.
+    pub fn with_checked(mut self, checked: bool) -> Checkbox {
+        self.checked = checked;
+        self
+    }
+
+    ///Colors of the box and the mark. Only works before the checkbox is registered.
+    pub fn with_colors(mut self, frame: Vector4<f32>, mark: Vector4<f32>) -> Checkbox {
+        let (frame_id, mark_id) = (self.frame, self.mark);
+        for child in self.child_buffer() {
+            match child.id() {
+                id if id == frame_id => child.set_color(frame),
+                id if id == mark_id => child.set_color(mark),
+                _ => {}
+            }
+        }
+        self
+    }
+
+    pub fn with_on_value_changed(mut self, callback: Box<OnCheckboxValueChanged>) -> Checkbox {
+        self.register_on_value_changed(callback);
+        self
+    }
+
+    pub fn register_on_value_changed(&mut self, callback: Box<OnCheckboxValueChanged>) {
+        self.on_value_changed.push(callback);
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        if self.checked == checked {
+            return;
+        }
+        let old = self.checked;
+        self.checked = checked;
+
+        let mut callbacks = std::mem::take(&mut self.on_value_changed);
+        for callback in &mut callbacks {
+            (callback)(self, old, checked);
+        }
+        callbacks.append(&mut self.on_value_changed);
+        self.on_value_changed = callbacks;
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_checked(!self.checked);
+    }
+
+    pub fn label<'a>(&self, ui: &'a UiContext) -> Option<&'a Label> {
+        ui.get_element_by_id(self.label.as_ref()?)?.downcast_ref::<Label>()
+    }
+
+    pub fn label_mut<'a>(&self, ui: &'a mut UiContext) -> Option<&'a mut Label> {
+        ui.get_element_by_id_mut(self.label.as_ref()?)?.downcast_mut::<Label>()
+    }
+}
+
+impl UIElement for Checkbox {
+    fn tag(&self) -> &'static str {
+        "Checkbox"
+    }
+
+    fn save_state(&self) -> Option<ElementState> {
+        Some(ElementState::Checked(self.checked))
+    }
+
+    fn restore_state(&mut self, state: ElementState) {
+        if let ElementState::Checked(checked) = state {
+            self.checked = checked;
+        }
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, point: [f64; 2]) -> bool {
+        shape_contains(self, point)
+    }
+
+    fn update_cursor(&mut self, ui: &mut UiContext, _cursor: [f64; 2]) {
+        if child_hovered(ui, &self.data.children) {
+            self.data.hover_flag = true;
+        }
+    }
+
+    ///Clicks on the parts bubble up to the widget itself.
+    fn on_mouse_event(&mut self, _ui: &mut UiContext, event: &mut MouseEvent) {
+        if event.phase() != EventPhase::Capture && event.kind() == MouseEventKind::Click(MouseButton::Button1) {
+            self.toggle();
+            event.stop_propagation();
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn update_key(&mut self, _ui: &mut UiContext, key: Key, code: Action, _delta: f32) -> bool {
+        if !is_activation_key(key) {
+            return false;
+        }
+        if code == Action::Press {
+            self.toggle();
+        }
+        true
+    }
+
+    fn update(&mut self, ui: &mut UiContext, _delta: f32) {
+        let size = arrange_indicator_row(ui, self.data.position, &self.frame, &self.mark, self.label.as_ref());
+        self.data.width = size[0];
+        self.data.height = size[1];
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        let mut parts = vec![self.frame];
+        if self.checked {
+            parts.push(self.mark);
+        }
+        parts.extend(self.label);
+        for part in parts {
+            if let Some(part) = ui.get_element_by_id(&part) {
+                part.draw(ui, camera);
+            }
+        }
+    }
+}
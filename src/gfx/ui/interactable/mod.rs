@@ -1 +1,68 @@
-pub mod slider;
\ No newline at end of file
+use glfw::Key;
+use crate::gfx::ui::UiContext;
+
+pub mod slider;
+pub mod button;
+pub mod checkbox;
+pub mod toggle;
+pub mod radio_group;
+pub mod text_edit;
+pub mod text_input;
+
+///Gap between an indicator, like the box of a checkbox, and its label.
+pub const LABEL_SPACING: u32 = 6;
+
+///Keys activating the focused widget.
+pub fn is_activation_key(key: Key) -> bool {
+    matches!(key, Key::Space | Key::Enter)
+}
+
+///Whether one of `children` is hovered. Widgets count the cursor over their parts as over themselves.
+///Children are dispatched before their parent in `UiContext::update_cursor`, so their hover flags are up to date.
+pub fn child_hovered(ui: &UiContext, children: &Vec<usize>) -> bool {
+    children.iter()
+        .filter_map(|child| ui.get_element_by_id(child))
+        .any(|child| child.element_data().hover_flag())
+}
+
+fn element_size(ui: &UiContext, id: &usize) -> [u32; 2] {
+    ui.get_element_by_id(id)
+        .map(|element| [element.element_data().width(), element.element_data().height()])
+        .unwrap_or([0; 2])
+}
+
+///Size of an indicator followed by an optional label.
+pub fn indicator_row_size(ui: &UiContext, indicator: &usize, label: Option<&usize>) -> [u32; 2] {
+    let indicator = element_size(ui, indicator);
+    match label {
+        None => indicator,
+        Some(label) => {
+            let label = element_size(ui, label);
+            [indicator[0] + LABEL_SPACING + label[0], indicator[1].max(label[1])]
+        }
+    }
+}
+
+///Lines up an indicator with `mark` centered in it and an optional label to its right, both vertically centered
+///in the row starting at `position`. Returns the size of the row.
+pub fn arrange_indicator_row(ui: &mut UiContext, position: [u32; 2], indicator: &usize, mark: &usize, label: Option<&usize>) -> [u32; 2] {
+    let size = indicator_row_size(ui, indicator, label);
+    let indicator_size = element_size(ui, indicator);
+    let mark_size = element_size(ui, mark);
+    let indicator_position = [position[0], position[1] + size[1].saturating_sub(indicator_size[1]) / 2];
+    let mark_position = [
+        indicator_position[0] + indicator_size[0].saturating_sub(mark_size[0]) / 2,
+        indicator_position[1] + indicator_size[1].saturating_sub(mark_size[1]) / 2,
+    ];
+    let mut positions = vec![(*indicator, indicator_position), (*mark, mark_position)];
+    if let Some(label) = label {
+        let label_size = element_size(ui, label);
+        positions.push((*label, [position[0] + indicator_size[0] + LABEL_SPACING, position[1] + size[1].saturating_sub(label_size[1]) / 2]));
+    }
+    for (id, position) in positions {
+        if let Some(element) = ui.get_element_by_id_mut(&id) {
+            element.set_position(position);
+        }
+    }
+    size
+}
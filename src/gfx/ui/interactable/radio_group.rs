@@ -0,0 +1,268 @@
+use std::fmt::{Debug, Formatter};
+use glfw::{Action, Key, MouseButton};
+use crate::gfx::ui::{ui_counter, UiContext, UIElement, UIElementData};
+use crate::gfx::ui::callbacks::OnRadioValueChanged;
+use crate::gfx::ui::event::{EventPhase, MouseEvent, MouseEventKind};
+use crate::gfx::ui::shape::shape_contains;
+use crate::gfx::ui::interactable::{arrange_indicator_row, child_hovered, indicator_row_size, is_activation_key};
+use crate::gfx::ui::label::Label;
+use crate::gfx::ui::markup::reload::ElementState;
+use crate::{Camera, CustomUIProperty, Rectangle};
+
+///Ids of the parts of one option.
+#[derive(Debug, Clone, Copy)]
+struct RadioOption {
+    ring: usize,
+    dot: usize,
+    label: usize,
+}
+
+impl RadioOption {
+    fn contains(&self, id: &usize) -> bool {
+        self.ring == *id || self.dot == *id || self.label == *id
+    }
+}
+
+///Options stacked from the top down, each a round indicator and a label, of which at most one is selected.
+///An option is selected by clicking it. While the group is focused, up and down move a highlight, starting at
+///the selected option, and space or enter selects the highlighted one. The ring of the highlighted option has
+///the class `highlighted` while the group is focused.
+pub struct RadioGroup {
+    data: UIElementData,
+    options: Vec<RadioOption>,
+    selected: Option<usize>,
+    ///Option the keyboard selects, see `update_key`.
+    highlighted: Option<usize>,
+    pressed: Option<usize>,
+    spacing: u32,
+    on_value_changed: Vec<Box<OnRadioValueChanged>>,
+}
+
+impl Debug for RadioGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("RadioGroup [selected: {:?}, options: {}, data: {:?}, callbacks: {}]", self.selected, self.options.len(), self.data, self.on_value_changed.len()))
+    }
+}
+
+impl RadioGroup {
+    ///One option per entry of `options`, with indicators of `size` pixels.
+    pub fn new<T: ToString>(options: Vec<T>, size: u32, font_size: f32) -> RadioGroup {
+        let mut group = Self {
+            data: UIElementData::new(ui_counter(), [0; 2], [0; 2]),
+            options: vec![],
+            selected: None,
+            highlighted: None,
+            pressed: None,
+            spacing: 4,
+            on_value_changed: vec![],
+        };
+
+        let dot_size = (size / 2) as f32;
+        for option in options {
+            let ring = Rectangle::new([0, 0], [size as f32; 2])
+                .with_custom_property(&"color", CustomUIProperty::Vec4([0.6, 0.6, 0.6, 1.0]))
+                .with_corner_radius(size as f32 / 2f32)
+                .with_class("radio-ring");
+            let dot = Rectangle::new([0, 0], [dot_size; 2])
+                .with_custom_property(&"color", CustomUIProperty::Vec4([0.2, 0.2, 0.2, 1.0]))
+                .with_corner_radius(dot_size / 2f32)
+                .with_class("radio-dot");
+            let label = Label::new(option, font_size);
+            group.options.push(RadioOption { ring: ring.id(), dot: dot.id(), label: label.id() });
+            group.add_child(Box::new(ring));
+            group.add_child(Box::new(dot));
+            group.add_child(Box::new(label));
+        }
+        group
+    }
+
+    pub fn with_position(mut self, position: [u32; 2]) -> RadioGroup {
+        self.set_position(position);
+        self
+    }
+
+    ///Sets the initial selection without firing callbacks.
+    pub fn with_selected(mut self, selected: Option<usize>) -> RadioGroup {
+        self.selected = selected.filter(|index| *index < self.options.len());
+        self
+    }
+
+    ///Vertical space between options.
+    pub fn with_spacing(mut self, spacing: u32) -> RadioGroup {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_on_value_changed(mut self, callback: Box<OnRadioValueChanged>) -> RadioGroup {
+        self.register_on_value_changed(callback);
+        self
+    }
+
+    pub fn register_on_value_changed(&mut self, callback: Box<OnRadioValueChanged>) {
+        self.on_value_changed.push(callback);
+    }
+
+    pub fn option_count(&self) -> usize {
+        self.options.len()
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn highlighted(&self) -> Option<usize> {
+        self.highlighted
+    }
+
+    ///Indices past the last option clear the selection.
+    pub fn select(&mut self, selected: Option<usize>) {
+        let selected = selected.filter(|index| *index < self.options.len());
+        if self.selected == selected {
+            return;
+        }
+        let old = self.selected;
+        self.selected = selected;
+
+        let mut callbacks = std::mem::take(&mut self.on_value_changed);
+        for callback in &mut callbacks {
+            (callback)(self, old, selected);
+        }
+        callbacks.append(&mut self.on_value_changed);
+        self.on_value_changed = callbacks;
+    }
+
+    pub fn label<'a>(&self, ui: &'a UiContext, index: usize) -> Option<&'a Label> {
+        ui.get_element_by_id(&self.options.get(index)?.label)?.downcast_ref::<Label>()
+    }
+
+    pub fn label_mut<'a>(&self, ui: &'a mut UiContext, index: usize) -> Option<&'a mut Label> {
+        ui.get_element_by_id_mut(&self.options.get(index)?.label)?.downcast_mut::<Label>()
+    }
+}
+
+impl UIElement for RadioGroup {
+    fn tag(&self) -> &'static str {
+        "RadioGroup"
+    }
+
+    fn save_state(&self) -> Option<ElementState> {
+        Some(ElementState::Selected(self.selected))
+    }
+
+    ///A selection past the options of the new group is dropped.
+    fn restore_state(&mut self, state: ElementState) {
+        if let ElementState::Selected(selected) = state {
+            self.selected = selected.filter(|index| *index < self.options.len());
+            self.highlighted = self.selected;
+        }
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, point: [f64; 2]) -> bool {
+        shape_contains(self, point)
+    }
+
+    fn update_cursor(&mut self, ui: &mut UiContext, _cursor: [f64; 2]) {
+        if child_hovered(ui, &self.data.children) {
+            self.data.hover_flag = true;
+        }
+    }
+
+    ///An option is clicked when the press and release land on its parts, even on different ones.
+    fn on_mouse_event(&mut self, _ui: &mut UiContext, event: &mut MouseEvent) {
+        if event.phase() == EventPhase::Capture {
+            return;
+        }
+        let option = self.options.iter().position(|option| option.contains(&event.target()));
+        match event.kind() {
+            MouseEventKind::Down(MouseButton::Button1) => self.pressed = option,
+            MouseEventKind::Up(MouseButton::Button1) => {
+                let pressed = self.pressed.take();
+                if pressed.is_some() && pressed == option {
+                    self.highlighted = pressed;
+                    self.select(pressed);
+                    event.stop_propagation();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn update_mouse(&mut self, _ui: &mut UiContext, mouse_button: MouseButton, action: Action) {
+        if mouse_button == MouseButton::Button1 && action == Action::Release {
+            self.pressed = None;
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn update_key(&mut self, _ui: &mut UiContext, key: Key, code: Action, _delta: f32) -> bool {
+        if !matches!(key, Key::Up | Key::Down) && !is_activation_key(key) {
+            return false;
+        }
+        if code != Action::Press || self.options.is_empty() {
+            return true;
+        }
+        let last = self.options.len() - 1;
+        let current = self.highlighted.or(self.selected);
+        match key {
+            Key::Up => self.highlighted = Some(current.map(|index| index.saturating_sub(1)).unwrap_or(0)),
+            Key::Down => self.highlighted = Some(current.map(|index| (index + 1).min(last)).unwrap_or(0)),
+            _ => self.select(current.or(Some(0)))
+        }
+        true
+    }
+
+    fn update(&mut self, ui: &mut UiContext, _delta: f32) {
+        let sizes: Vec<[u32; 2]> = self.options.iter()
+            .map(|option| indicator_row_size(ui, &option.ring, Some(&option.label)))
+            .collect();
+        let spacing = self.spacing * self.options.len().saturating_sub(1) as u32;
+        self.data.width = sizes.iter().map(|size| size[0]).max().unwrap_or(0);
+        self.data.height = sizes.iter().map(|size| size[1]).sum::<u32>() + spacing;
+
+        let focused = ui.is_focused(&self.id());
+        for (index, option) in self.options.iter().enumerate() {
+            if let Some(ring) = ui.get_element_by_id_mut(&option.ring) {
+                match focused && self.highlighted == Some(index) {
+                    true => ring.add_class("highlighted"),
+                    false => ring.remove_class("highlighted")
+                }
+            }
+        }
+
+        //Rows run downwards from the top, UI positions upwards from the bottom.
+        let mut top = self.data.position[1] + self.data.height;
+        for (option, size) in self.options.iter().zip(sizes) {
+            top = top.saturating_sub(size[1]);
+            arrange_indicator_row(ui, [self.data.position[0], top], &option.ring, &option.dot, Some(&option.label));
+            top = top.saturating_sub(self.spacing);
+        }
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        for (index, option) in self.options.iter().enumerate() {
+            let mut parts = vec![option.ring];
+            if self.selected == Some(index) {
+                parts.push(option.dot);
+            }
+            parts.push(option.label);
+            for part in parts {
+                if let Some(part) = ui.get_element_by_id(&part) {
+                    part.draw(ui, camera);
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,65 @@
 use std::fmt::{Debug, Formatter};
-use glfw::{Key};
-use crate::gfx::ui::{Callbacks, ui_counter, UIElement, UIElementData};
-use crate::{Camera, CustomUIProperty, Input, Rectangle, UI};
+use glfw::{Action, Key};
+use crate::gfx::ui::{ui_counter, UiContext, UIElement, UIElementData};
+use crate::{Camera, CustomUIProperty, Input, Rectangle};
 use crate::gfx::ui::callbacks::OnSliderValueChanged;
-use crate::math::{clamp, inverse_lerp_f64};
+use crate::gfx::ui::markup::reload::ElementState;
+use crate::gfx::ui::shape::children_hit;
+use crate::math::clamp;
+
+///How the handle of a slider or scrollbar moves along one axis: its start runs from `start` at value 0 over
+///`travel` pixels to value 1. Vertical tracks run downwards, like scrolling, so their travel is negative.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SliderTrack {
+    pub axis: usize,
+    pub start: f32,
+    pub travel: f32,
+}
+
+impl SliderTrack {
+    ///The handle centred on the ends of `area`, x, y, width and height, at 0 and 1, as a `Slider` moves it.
+    pub fn centered(area: [f32; 4], handle_size: [f32; 2], axis: usize) -> SliderTrack {
+        let start = area[axis] - handle_size[axis] / 2f32;
+        let travel = area[axis + 2];
+        match axis {
+            0 => Self { axis, start, travel },
+            _ => Self { axis, start: start + travel, travel: -travel }
+        }
+    }
+
+    ///The handle kept within `area`, as a scrollbar moves it.
+    pub fn within(area: [f32; 4], handle_length: f32, axis: usize) -> SliderTrack {
+        let travel = (area[axis + 2] - handle_length).max(0f32);
+        match axis {
+            0 => Self { axis, start: area[0], travel },
+            _ => Self { axis, start: area[1] + area[3] - handle_length, travel: -travel }
+        }
+    }
+
+    ///Position of the start of the handle along the axis.
+    pub fn handle_start(&self, value: f32) -> f32 {
+        self.start + self.travel * clamp(value, 0f32, 1f32)
+    }
+
+    ///Value that puts the start of the handle at `position`, or 0 when the handle can not move.
+    pub fn value_at(&self, position: f32) -> f32 {
+        match self.travel == 0f32 {
+            true => 0f32,
+            false => clamp((position - self.start) / self.travel, 0f32, 1f32)
+        }
+    }
+
+    ///Value while dragging the handle with the cursor at `cursor`, having grabbed it `grab` from its position.
+    pub fn drag_value(&self, cursor: [f64; 2], grab: [f64; 2]) -> f32 {
+        self.value_at((cursor[self.axis] - grab[self.axis]) as f32)
+    }
+}
+
+///Position and size of `rectangle` as x, y, width and height, as a layout last left them.
+fn bounds(rectangle: &Rectangle) -> [f32; 4] {
+    let data = rectangle.element_data();
+    [data.position[0] as f32, data.position[1] as f32, data.width as f32, data.height as f32]
+}
 
 pub struct Slider {
     value: f32,
@@ -38,16 +94,32 @@ impl Slider {
         self
     }
 
-    pub fn calculate_slider_position(area: [f32; 4], v: f32, handle_size: [f32; 2]) -> [u32; 2] {
-        let (area_x, area_y) = (area[0], area[1]);
-        let (area_w, area_h) = (area[2] + area_x, area[3] + area_y);
+    ///Sets the initial value without firing callbacks. Only moves the handle while the slider has not been
+    ///registered yet. Use `set_value` afterwards.
+    pub fn with_value(mut self, value: f32) -> Slider {
+        self.place_pending(value);
+        self
+    }
+
+    fn place_pending(&mut self, value: f32) {
+        self.value = clamp(value, 0f32, 1f32);
+        let area = self.data.tmp_children.get(0)
+            .and_then(|area| area.downcast_ref::<Rectangle>())
+            .map(bounds);
+        let handle = self.data.tmp_children.get_mut(1).and_then(|handle| handle.downcast_mut::<Rectangle>());
+        if let (Some(area), Some(handle)) = (area, handle) {
+            handle.set_position(Slider::calculate_slider_position(area, self.value, handle.size()));
+        }
+    }
 
-        let h_x = area_w - (area[2] - area[2] * v) - (handle_size[0] / 2f32);
-        let h_y = area_h - (area[3] / 2f32) - (handle_size[1] / 2f32);
+    ///Handle position for value `v`, centred on the area vertically, see `SliderTrack::centered`.
+    pub fn calculate_slider_position(area: [f32; 4], v: f32, handle_size: [f32; 2]) -> [u32; 2] {
+        let h_x = SliderTrack::centered(area, handle_size, 0).handle_start(v);
+        let h_y = area[1] + area[3] / 2f32 - handle_size[1] / 2f32;
         [h_x as u32, h_y as u32]
     }
 
-    pub fn set_value(&mut self, value: f32) {
+    pub fn set_value(&mut self, ui: &mut UiContext, value: f32) {
         if self.value == value || (value > 1f32 && self.value == 1f32) || (value < 0f32 && self.value == 0f32) {
             return;
         }
@@ -55,67 +127,83 @@ impl Slider {
         self.value = clamp(value, 0f32, 1f32);
         let value = self.value.clone();
 
-        let area = {
-            let slider = self.area();
-            [
-                slider.position()[0] as f32, slider.position()[1] as f32,
-                slider.size()[0], slider.size()[1]
-            ]
-        };
-        let handle = self.handle_mut();
+        let area = self.area(ui).map(bounds);
+        //The handle is missing while it is being dragged, in which case the drag callback positions it.
+        if let (Some(area), Some(handle)) = (area, self.handle_mut(ui)) {
+            handle.set_position(Slider::calculate_slider_position(area, value, handle.size()));
+        }
 
-        handle.set_position(Slider::calculate_slider_position(area, value, handle.size()));
-        let me: &mut Slider = self.self_mut().unwrap().downcast_mut().unwrap();
         let nv = self.value;
-        for callback in &mut self.on_value_changed {
-            (callback)(me, old, nv);
+        let mut callbacks = std::mem::take(&mut self.on_value_changed);
+        for callback in &mut callbacks {
+            (callback)(self, old, nv);
         }
+        callbacks.append(&mut self.on_value_changed);
+        self.on_value_changed = callbacks;
     }
 
-    pub fn increment(&mut self, v: f32) {
-        self.set_value(self.value + v);
+    pub fn increment(&mut self, ui: &mut UiContext, v: f32) {
+        self.set_value(ui, self.value + v);
     }
 
-    pub fn decrement(&mut self, v: f32) {
-        self.increment(-v);
+    pub fn decrement(&mut self, ui: &mut UiContext, v: f32) {
+        self.increment(ui, -v);
     }
 
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    ///Only affects the area while the slider has not been registered yet. Use `set_corner_radius` afterwards.
     pub fn with_corner_radius(mut self, corner_radius: f32) -> Slider {
-        self.set_corner_radius(corner_radius);
+        self.data.set_corner_radius(corner_radius);
+        if !self.element_data().tmp_children.is_empty() {
+            self.element_data_mut().tmp_children[0].element_data_mut().set_corner_radius(corner_radius);
+        }
         self
     }
 
-    pub fn set_corner_radius(&mut self, corner_radius: f32) {
-        self.data.corner_radius = corner_radius;
-        //self.area_mut().element_data_mut().corner_radius = corner_radius;
+    pub fn set_corner_radius(&mut self, ui: &mut UiContext, corner_radius: f32) {
+        self.data.set_corner_radius(corner_radius);
         if !self.element_data().tmp_children.is_empty() {
-            self.element_data_mut().tmp_children[0].element_data_mut().corner_radius = corner_radius;
-        } else {
-            self.area_mut().element_data_mut().corner_radius = corner_radius;
+            self.element_data_mut().tmp_children[0].element_data_mut().set_corner_radius(corner_radius);
+        } else if let Some(area) = self.area_mut(ui) {
+            area.element_data_mut().set_corner_radius(corner_radius);
         }
     }
 
-    fn area(&self) -> &Rectangle {
-        UI::get_element_by_id(&self.children()[0]).unwrap().downcast_ref::<Rectangle>().unwrap()
+    ///Fits the area to the slider and centres the handle on it for the current value.
+    fn arrange(&mut self, ui: &mut UiContext) {
+        let position = self.data.position;
+        let size = [self.data.width, self.data.height];
+        if let Some(area) = self.area_mut(ui) {
+            area.set_position(position);
+            area.element_data_mut().width = size[0];
+            area.element_data_mut().height = size[1];
+        }
+        let area = [position[0] as f32, position[1] as f32, size[0] as f32, size[1] as f32];
+        let value = self.value;
+        //The handle is missing while it is being dragged, in which case the drag callback positions it.
+        if let Some(handle) = self.handle_mut(ui) {
+            let handle_size = [handle.element_data().width as f32, handle.element_data().height as f32];
+            handle.set_position(Slider::calculate_slider_position(area, value, handle_size));
+        }
     }
 
-    fn area_mut(&mut self) -> &mut Rectangle {
-        self.area_mut_checked().unwrap()
+    fn area<'a>(&self, ui: &'a UiContext) -> Option<&'a Rectangle> {
+        ui.get_element_by_id(self.children().get(0)?)?.downcast_ref::<Rectangle>()
     }
 
-    fn area_mut_checked(&mut self) -> Option<&mut Rectangle> {
-        if self.children().len() == 0 {
-            return None;
-        }
-        UI::get_element_by_id_mut(&self.children()[0]).unwrap().downcast_mut::<Rectangle>()
+    fn area_mut<'a>(&self, ui: &'a mut UiContext) -> Option<&'a mut Rectangle> {
+        ui.get_element_by_id_mut(self.children().get(0)?)?.downcast_mut::<Rectangle>()
     }
 
-    fn handle(&self) -> &Rectangle {
-        UI::get_element_by_id(&self.children()[1]).unwrap().downcast_ref::<Rectangle>().unwrap()
+    fn handle<'a>(&self, ui: &'a UiContext) -> Option<&'a Rectangle> {
+        ui.get_element_by_id(self.children().get(1)?)?.downcast_ref::<Rectangle>()
     }
 
-    fn handle_mut(&mut self) -> &mut Rectangle {
-        UI::get_element_by_id_mut(&self.children()[1]).unwrap().downcast_mut::<Rectangle>().unwrap()
+    fn handle_mut<'a>(&self, ui: &'a mut UiContext) -> Option<&'a mut Rectangle> {
+        ui.get_element_by_id_mut(self.children().get(1)?)?.downcast_mut::<Rectangle>()
     }
 }
 
@@ -133,65 +221,37 @@ impl Default for Slider {
         let slider = Rectangle::new(
             area_position,
             [area[2], area[3]],
-        ).with_custom_property(&"color", CustomUIProperty::Vec4([0.6, 0.6, 0.6, 1.0]));
+        ).with_custom_property(&"color", CustomUIProperty::Vec4([0.6, 0.6, 0.6, 1.0])).with_class("slider-track");
 
         let mut handle = Rectangle::new(
             handle_position,
             handle_size,
-        ).with_custom_property(&"color", CustomUIProperty::Vec4([0.3, 0.3, 0.3, 1.0]));
-        handle.register_on_drag(Box::new(move |handle, _, mouse, _relative_mouse| {
-            let slider = handle.get_parent_mut().unwrap().downcast_mut::<Slider>().unwrap();
-            let area = slider.area();
-            let area = [
-                (area.position()[0] - handle.element_data().width / 2) as f64,
-                area_position[1] as f64,
-                ((area.position()[0] + area.size()[0] as u32) - handle.element_data().width / 2) as f64,
-                (area.position()[1] + area.size()[1] as u32) as f64
-            ];
-
-            let pos = handle.position();
-            let ui_mouse = Input::ui_cursor();
-
-            let mut x = ui_mouse[0] - mouse[0];
-
-            if x <= area[0] {
-                x = area[0];
-            } else if x >= area[2] {
-                x = area[2];
+        ).with_custom_property(&"color", CustomUIProperty::Vec4([0.3, 0.3, 0.3, 1.0])).with_class("slider-handle");
+        handle.register_on_drag(Box::new(move |ui, handle, cursor, grab, _relative_mouse| {
+            let parent = match handle.parent() {
+                None => return,
+                Some(parent) => parent
+            };
+            let pos = *handle.position();
+            let handle_size = [handle.element_data().width as f32, handle.element_data().height as f32];
+            let x = ui.with_element(&parent, |slider, ui| {
+                let slider = slider.downcast_mut::<Slider>()?;
+                let track = SliderTrack::centered(bounds(slider.area(ui)?), handle_size, 0);
+                let value = track.drag_value(cursor, grab);
+                slider.set_value(ui, value);
+                Some(track.handle_start(value))
+            }).flatten();
+
+            if let Some(x) = x {
+                handle.set_position([x as u32, pos[1]]);
             }
-
-            let a_x = area[0];
-            let a_w = area[2] - (handle.element_data().width) as f64;
-
-            let a_xw = a_x + a_w;
-
-            let i_lerp = inverse_lerp_f64(a_x, a_xw, x);
-
-            let ui_mouse = [x as u32, pos[1]];
-            handle.set_position(ui_mouse);
-            slider.set_value(i_lerp as f32);
         }));
 
+        let mut data = UIElementData::new(id, area_position, [area[2] as u32, area[3] as u32]);
+        data.tmp_children = vec![Box::new(slider), Box::new(handle)];
         Self {
             value: 0f32,
-            data: UIElementData {
-                id,
-                parent: None,
-                children: vec![],
-                position: area_position,
-                width: area[2] as u32,
-                height: area[3] as u32,
-                tmp_children: vec![
-                    Box::new(slider),
-                    Box::new(handle),
-                ],
-                render_data: None,
-                hover_flag: false,
-                custom_properties: Default::default(),
-                corner_radius: 0.0,
-                drag_offset: None,
-                callbacks: Callbacks { on_drag: vec![] },
-            },
+            data,
             on_value_changed: vec![],
         }
     }
@@ -212,12 +272,39 @@ impl UIElement for Slider {
         false
     }
 
-    fn draw(&self, camera: &Camera) {
-        self.area().draw(camera);
-        self.handle().draw(camera);
+    ///Covers what its children cover, gaps between them excluded.
+    fn hit_test(&self, ui: &UiContext, point: [f64; 2]) -> bool {
+        children_hit(ui, self.children(), point)
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        if let Some(area) = self.area(ui) {
+            area.draw(ui, camera);
+        }
+        if let Some(handle) = self.handle(ui) {
+            handle.draw(ui, camera);
+        }
     }
 
-    fn update(&mut self, delta: f32) {
+    ///A layout only moves the slider itself, so the area and handle are placed on it here.
+    fn layout_changed(&mut self, ui: &mut UiContext) {
+        self.arrange(ui);
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    ///Left and right are polled in `update`, so they are only claimed here.
+    fn update_key(&mut self, _ui: &mut UiContext, key: Key, _code: Action, _delta: f32) -> bool {
+        matches!(key, Key::Left | Key::Right)
+    }
+
+    ///Moves the value while left or right is held and the slider is focused.
+    fn update(&mut self, ui: &mut UiContext, delta: f32) {
+        if !ui.is_focused(&self.id()) {
+            return;
+        }
         let value = match Input::is_key_held(Key::Left) {
             true => -delta,
             false => {
@@ -227,7 +314,17 @@ impl UIElement for Slider {
                 }
             }
         };
-        self.increment(value);
+        self.increment(ui, value);
+    }
+
+    fn save_state(&self) -> Option<ElementState> {
+        Some(ElementState::Value(self.value))
+    }
+
+    fn restore_state(&mut self, state: ElementState) {
+        if let ElementState::Value(value) = state {
+            self.place_pending(value);
+        }
     }
 
     fn tag(&self) -> &'static str {
@@ -0,0 +1,421 @@
+use std::ops::Range;
+
+///Text with a caret, an optional selection and undo history, without anything to draw it.
+///Indices are byte offsets into the text, always on character boundaries.
+#[derive(Debug, Clone)]
+pub struct TextEdit {
+    text: String,
+    caret: usize,
+    ///Other end of the selection, if any.
+    anchor: Option<usize>,
+    multiline: bool,
+    undo: Vec<(String, usize)>,
+    redo: Vec<(String, usize)>,
+    ///Consecutive insertions are undone together.
+    typing: bool,
+}
+
+impl TextEdit {
+    ///Puts the caret at the end of `text`. Single line edits drop line breaks.
+    pub fn new<T: ToString>(text: T, multiline: bool) -> TextEdit {
+        let text = sanitize(&text.to_string(), multiline);
+        Self { caret: text.len(), text, anchor: None, multiline, undo: vec![], redo: vec![], typing: false }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    ///Selected range, empty selections excluded.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        match anchor == self.caret {
+            true => None,
+            false => Some(anchor.min(self.caret)..anchor.max(self.caret))
+        }
+    }
+
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|range| &self.text[range])
+    }
+
+    ///Replaces the text as a single undoable edit.
+    pub fn set_text<T: ToString>(&mut self, text: T) {
+        let text = sanitize(&text.to_string(), self.multiline);
+        if text == self.text {
+            return;
+        }
+        self.snapshot();
+        self.text = text;
+        self.caret = self.text.len();
+        self.anchor = None;
+    }
+
+    ///Moves the caret to `index`, extending the selection when `select` is set, or clearing it otherwise.
+    pub fn move_to(&mut self, index: usize, select: bool) {
+        let index = floor_boundary(&self.text, index.min(self.text.len()));
+        match select {
+            true => {
+                self.anchor.get_or_insert(self.caret);
+            }
+            false => self.anchor = None,
+        }
+        self.caret = index;
+        self.typing = false;
+    }
+
+    ///Without `select`, a selection collapses to its start instead.
+    pub fn move_left(&mut self, select: bool, word: bool) {
+        let target = match (self.selection(), select, word) {
+            (Some(range), false, false) => range.start,
+            (_, _, true) => word_start(&self.text, self.caret),
+            _ => previous_boundary(&self.text, self.caret),
+        };
+        self.move_to(target, select);
+    }
+
+    ///Without `select`, a selection collapses to its end instead.
+    pub fn move_right(&mut self, select: bool, word: bool) {
+        let target = match (self.selection(), select, word) {
+            (Some(range), false, false) => range.end,
+            (_, _, true) => word_end(&self.text, self.caret),
+            _ => next_boundary(&self.text, self.caret),
+        };
+        self.move_to(target, select);
+    }
+
+    ///To the start of the line, or of the text when `whole`.
+    pub fn move_home(&mut self, select: bool, whole: bool) {
+        let target = if whole { 0 } else { line_start(&self.text, self.caret) };
+        self.move_to(target, select);
+    }
+
+    ///To the end of the line, or of the text when `whole`.
+    pub fn move_end(&mut self, select: bool, whole: bool) {
+        let target = if whole { self.text.len() } else { line_end(&self.text, self.caret) };
+        self.move_to(target, select);
+    }
+
+    ///Moves up (negative) or down by `lines`, keeping the column in characters where the target line is long enough.
+    pub fn move_lines(&mut self, lines: isize, select: bool) {
+        let start = line_start(&self.text, self.caret);
+        let column = self.text[start..self.caret].chars().count();
+        let mut target = start;
+        if lines < 0 {
+            for _ in 0..lines.unsigned_abs() {
+                if target == 0 {
+                    break;
+                }
+                target = line_start(&self.text, target - 1);
+            }
+        } else {
+            for _ in 0..lines {
+                let end = line_end(&self.text, target);
+                if end == self.text.len() {
+                    break;
+                }
+                target = end + 1;
+            }
+        }
+        let end = line_end(&self.text, target);
+        let offset = self.text[target..end].char_indices().nth(column).map(|(offset, _)| offset).unwrap_or(end - target);
+        self.move_to(target + offset, select);
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.caret = self.text.len();
+        self.typing = false;
+    }
+
+    ///Replaces the selection, or inserts at the caret. Typing one character after another is undone at once.
+    pub fn insert(&mut self, text: &str) {
+        let text = sanitize(text, self.multiline);
+        if text.is_empty() && self.selection().is_none() {
+            return;
+        }
+        let typing = text.chars().count() == 1 && self.selection().is_none();
+        if !(typing && self.typing) {
+            self.snapshot();
+        }
+        let range = self.selection().unwrap_or(self.caret..self.caret);
+        self.text.replace_range(range.clone(), &text);
+        self.caret = range.start + text.len();
+        self.anchor = None;
+        self.typing = typing;
+    }
+
+    ///Deletes the selection, or the character or word before the caret.
+    pub fn backspace(&mut self, word: bool) {
+        let range = match self.selection() {
+            Some(range) => range,
+            None if word => word_start(&self.text, self.caret)..self.caret,
+            None => previous_boundary(&self.text, self.caret)..self.caret,
+        };
+        self.delete_range(range);
+    }
+
+    ///Deletes the selection, or the character or word after the caret.
+    pub fn delete(&mut self, word: bool) {
+        let range = match self.selection() {
+            Some(range) => range,
+            None if word => self.caret..word_end(&self.text, self.caret),
+            None => self.caret..next_boundary(&self.text, self.caret),
+        };
+        self.delete_range(range);
+    }
+
+    ///Removes the selection and returns it.
+    pub fn cut(&mut self) -> Option<String> {
+        let range = self.selection()?;
+        let text = self.text[range.clone()].to_string();
+        self.delete_range(range);
+        Some(text)
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            None => false,
+            Some((text, caret)) => {
+                self.redo.push((std::mem::replace(&mut self.text, text), self.caret));
+                self.restore(caret);
+                true
+            }
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            None => false,
+            Some((text, caret)) => {
+                self.undo.push((std::mem::replace(&mut self.text, text), self.caret));
+                self.restore(caret);
+                true
+            }
+        }
+    }
+
+    fn delete_range(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            self.anchor = None;
+            return;
+        }
+        self.snapshot();
+        self.text.replace_range(range.clone(), "");
+        self.caret = range.start;
+        self.anchor = None;
+    }
+
+    fn restore(&mut self, caret: usize) {
+        self.caret = floor_boundary(&self.text, caret.min(self.text.len()));
+        self.anchor = None;
+        self.typing = false;
+    }
+
+    ///Records the current state for `undo`. Any new edit drops what could be redone.
+    fn snapshot(&mut self) {
+        self.undo.push((self.text.clone(), self.caret));
+        self.redo.clear();
+        self.typing = false;
+    }
+}
+
+fn sanitize(text: &str, multiline: bool) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    match multiline {
+        true => text,
+        false => text.replace('\n', " ")
+    }
+}
+
+fn floor_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn previous_boundary(text: &str, index: usize) -> usize {
+    text[..index].char_indices().next_back().map(|(index, _)| index).unwrap_or(0)
+}
+
+fn next_boundary(text: &str, index: usize) -> usize {
+    text[index..].chars().next().map(|character| index + character.len_utf8()).unwrap_or(index)
+}
+
+///Start of the word before `index`, skipping whitespace first.
+fn word_start(text: &str, index: usize) -> usize {
+    let mut characters = text[..index].char_indices().rev().skip_while(|(_, character)| character.is_whitespace()).peekable();
+    let mut start = characters.peek().map(|(index, _)| *index).unwrap_or(0);
+    for (index, character) in characters {
+        if character.is_whitespace() {
+            break;
+        }
+        start = index;
+    }
+    start
+}
+
+///End of the word after `index`, skipping whitespace first.
+fn word_end(text: &str, index: usize) -> usize {
+    let mut characters = text[index..].char_indices().skip_while(|(_, character)| character.is_whitespace());
+    match characters.find(|(_, character)| character.is_whitespace()) {
+        None => text.len(),
+        Some((offset, _)) => index + offset
+    }
+}
+
+fn line_start(text: &str, index: usize) -> usize {
+    text[..index].rfind('\n').map(|newline| newline + 1).unwrap_or(0)
+}
+
+fn line_end(text: &str, index: usize) -> usize {
+    text[index..].find('\n').map(|newline| index + newline).unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_byte_characters_are_moved_over_and_deleted_whole() {
+        //One, two, three and four bytes long.
+        let mut edit = TextEdit::new("aé€😀", false);
+        assert_eq!(edit.caret(), 10);
+
+        edit.move_left(false, false);
+        assert_eq!(edit.caret(), 6);
+        edit.backspace(false);
+        assert_eq!((edit.text(), edit.caret()), ("aé😀", 3));
+        edit.delete(false);
+        assert_eq!((edit.text(), edit.caret()), ("aé", 3));
+
+        //Inside the two bytes of é.
+        edit.move_to(2, false);
+        assert_eq!(edit.caret(), 1);
+        edit.move_right(true, false);
+        assert_eq!(edit.selected_text(), Some("é"));
+        edit.move_to(100, false);
+        assert_eq!(edit.caret(), 3);
+    }
+
+    #[test]
+    fn words_end_at_whitespace_runs() {
+        let text = "one   two\tthree  ";
+        assert_eq!(word_start(text, 17), 10);
+        assert_eq!(word_start(text, 10), 6);
+        assert_eq!(word_start(text, 8), 6);
+        assert_eq!(word_start(text, 6), 0);
+        assert_eq!(word_start(text, 0), 0);
+        assert_eq!(word_start("   ", 3), 0);
+
+        assert_eq!(word_end(text, 0), 3);
+        assert_eq!(word_end(text, 3), 9);
+        assert_eq!(word_end(text, 7), 9);
+        assert_eq!(word_end(text, 15), 17);
+
+        let mut edit = TextEdit::new("one   two", false);
+        edit.backspace(true);
+        assert_eq!(edit.text(), "one   ");
+        edit.move_home(false, false);
+        edit.delete(true);
+        assert_eq!(edit.text(), "   ");
+    }
+
+    #[test]
+    fn moving_without_select_collapses_the_selection() {
+        let mut edit = TextEdit::new("hello world", false);
+        edit.move_to(2, false);
+        edit.move_to(7, true);
+        assert_eq!(edit.selection(), Some(2..7));
+
+        edit.move_left(false, false);
+        assert_eq!((edit.caret(), edit.selection()), (2, None));
+
+        edit.select_all();
+        edit.move_right(false, false);
+        assert_eq!((edit.caret(), edit.selection()), (11, None));
+
+        edit.move_left(true, true);
+        assert_eq!(edit.selected_text(), Some("world"));
+        edit.insert("there");
+        assert_eq!((edit.text(), edit.caret(), edit.selection()), ("hello there", 11, None));
+    }
+
+    #[test]
+    fn typed_characters_are_undone_together() {
+        let mut edit = TextEdit::new("", false);
+        for character in ["a", "b", "c"] {
+            edit.insert(character);
+        }
+        //Moving ends the group, and pasting several characters is an edit of its own.
+        edit.move_left(false, false);
+        edit.insert("x");
+        edit.insert("yz");
+        assert_eq!(edit.text(), "abxyzc");
+
+        assert!(edit.undo());
+        assert_eq!(edit.text(), "abxc");
+        assert!(edit.undo());
+        assert_eq!(edit.text(), "abc");
+        assert!(edit.undo());
+        assert_eq!((edit.text(), edit.caret()), ("", 0));
+        assert!(!edit.undo());
+
+        //The caret goes back to where it was, which the second undo left before the c.
+        assert!(edit.redo());
+        assert_eq!((edit.text(), edit.caret()), ("abc", 2));
+    }
+
+    #[test]
+    fn a_new_edit_clears_redo() {
+        let mut edit = TextEdit::new("", false);
+        edit.insert("ab");
+        edit.insert("cd");
+        assert!(edit.undo());
+        assert_eq!(edit.text(), "ab");
+
+        edit.backspace(false);
+        assert!(!edit.redo());
+        assert_eq!(edit.text(), "a");
+        assert!(edit.undo());
+        assert_eq!(edit.text(), "ab");
+    }
+
+    #[test]
+    fn move_lines_keeps_the_column_in_characters() {
+        let mut edit = TextEdit::new("abcd\nx\nwxyz", true);
+        edit.move_to(3, false);
+        edit.move_lines(1, false);
+        assert_eq!(edit.caret(), 6);
+        edit.move_to(3, false);
+        edit.move_lines(2, false);
+        assert_eq!(edit.caret(), 10);
+        edit.move_lines(-5, true);
+        assert_eq!((edit.caret(), edit.selection()), (3, Some(3..10)));
+
+        let mut edit = TextEdit::new("é€\nabc", true);
+        edit.move_to(5, false);
+        edit.move_lines(1, false);
+        assert_eq!(edit.caret(), 8);
+        edit.move_lines(-1, false);
+        assert_eq!(edit.caret(), 5);
+    }
+
+    #[test]
+    fn single_line_edits_replace_line_breaks() {
+        assert_eq!(TextEdit::new("a\r\nb\rc", false).text(), "a b c");
+        assert_eq!(TextEdit::new("a\r\nb\rc", true).text(), "a\nb\nc");
+        let mut edit = TextEdit::new("", false);
+        edit.insert("x\ny");
+        assert_eq!(edit.text(), "x y");
+    }
+}
@@ -0,0 +1,477 @@
+use std::fmt::{Debug, Formatter};
+use glfw::{Action, Key, Modifiers, MouseButton};
+use vecmath::Vector4;
+use crate::gfx::text::TextAlignment;
+use crate::gfx::text::renderer::TextMesh;
+use crate::gfx::ui::{quad_render_data, ui_counter, UiContext, UIElement, UIElementData};
+use crate::gfx::ui::callbacks::{OnSubmit, OnTextChanged};
+use crate::gfx::ui::event::{MouseEvent, MouseEventKind};
+use crate::gfx::ui::shape::shape_contains;
+use crate::gfx::ui::interactable::text_edit::TextEdit;
+use crate::gfx::ui::markup::reload::ElementState;
+use crate::gfx::ui::style::{Style, Styled};
+use crate::gfx::ui::style::stylesheet::Stylesheet;
+use crate::{Camera, CustomUIProperty, Input, Rectangle};
+
+///Space between the border and the text.
+const PADDING: u32 = 4;
+///Seconds the caret stays visible, then hidden.
+const BLINK_INTERVAL: f32 = 0.5;
+const CARET_WIDTH: u32 = 2;
+
+///Position a caret can be placed at, left of the character at `index`.
+#[derive(Debug, Clone, Copy)]
+struct CaretStop {
+    index: usize,
+    line: usize,
+    x: f32,
+}
+
+///An editable text field, single line by default. Clicking or tabbing to it focuses it, clicking elsewhere or escape
+///unfocuses it.
+///
+///While focused it takes characters from `UiContext::update_char` and supports the usual desktop keys: arrows,
+///home and end, control to jump words, shift to select, backspace and delete, control with A, C, X and V for
+///selection and clipboard, and control with Z and Y for undo and redo. Enter submits a single line input,
+///control and enter a multi-line one.
+pub struct TextInput {
+    data: UIElementData,
+    edit: TextEdit,
+    font: Styled<Option<String>>,
+    font_size: Styled<f32>,
+    text_color: Styled<Vector4<f32>>,
+    focused: bool,
+    ///Selecting by dragging.
+    pressed: bool,
+    mesh: Option<TextMesh>,
+    stops: Vec<CaretStop>,
+    line_height: f32,
+    dirty: bool,
+    caret: Rectangle,
+    selection: Vec<Rectangle>,
+    selection_color: Vector4<f32>,
+    blink: f32,
+    on_text_changed: Vec<Box<OnTextChanged>>,
+    on_submit: Vec<Box<OnSubmit>>,
+}
+
+impl Debug for TextInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("TextInput [text: {:?}, focused: {}, data: {:?}, callbacks: {}]", self.edit.text(), self.focused, self.data, self.on_text_changed.len() + self.on_submit.len()))
+    }
+}
+
+impl TextInput {
+    pub fn new(size: [u32; 2], font_size: f32) -> TextInput {
+        let text_color = [0.1, 0.1, 0.1, 1.0];
+        let mut data = UIElementData::new(ui_counter(), [0; 2], size).with_render_data(quad_render_data());
+        data.custom_properties.insert("color".to_string(), CustomUIProperty::Vec4([0.95, 0.95, 0.95, 1.0]));
+        Self {
+            data,
+            edit: TextEdit::new("", false),
+            font: Styled::new(None),
+            font_size: Styled::new(font_size),
+            text_color: Styled::new(text_color),
+            focused: false,
+            pressed: false,
+            mesh: None,
+            stops: vec![],
+            line_height: font_size,
+            dirty: true,
+            caret: Rectangle::new([0, 0], [CARET_WIDTH as f32, font_size])
+                .with_custom_property(&"color", CustomUIProperty::Vec4(text_color)),
+            selection: vec![],
+            selection_color: [0.6, 0.75, 0.95, 1.0],
+            blink: 0f32,
+            on_text_changed: vec![],
+            on_submit: vec![],
+        }
+    }
+
+    pub fn with_position(mut self, position: [u32; 2]) -> TextInput {
+        self.set_position(position);
+        self
+    }
+
+    ///Accepts line breaks. Only keeps the undo history when set before any edit.
+    pub fn with_multiline(mut self, multiline: bool) -> TextInput {
+        self.edit = TextEdit::new(self.edit.text(), multiline);
+        self
+    }
+
+    ///Sets the initial text without firing callbacks.
+    pub fn with_text<T: ToString>(mut self, text: T) -> TextInput {
+        self.edit = TextEdit::new(text, self.edit.is_multiline());
+        self
+    }
+
+    pub fn with_font<T: ToString>(mut self, font: T) -> TextInput {
+        self.font.set_own(Some(font.to_string()));
+        self
+    }
+
+    pub fn with_text_color(mut self, color: Vector4<f32>) -> TextInput {
+        self.text_color.set_own(color);
+        self.caret.set_color(*self.text_color.get());
+        self
+    }
+
+    pub fn with_selection_color(mut self, color: Vector4<f32>) -> TextInput {
+        self.selection_color = color;
+        self
+    }
+
+    pub fn with_custom_property(mut self, property: &dyn ToString, value: CustomUIProperty) -> TextInput {
+        self.set_custom_property(property, value);
+        self
+    }
+
+    pub fn with_on_text_changed(mut self, callback: Box<OnTextChanged>) -> TextInput {
+        self.register_on_text_changed(callback);
+        self
+    }
+
+    pub fn with_on_submit(mut self, callback: Box<OnSubmit>) -> TextInput {
+        self.register_on_submit(callback);
+        self
+    }
+
+    pub fn register_on_text_changed(&mut self, callback: Box<OnTextChanged>) {
+        self.on_text_changed.push(callback);
+    }
+
+    pub fn register_on_submit(&mut self, callback: Box<OnSubmit>) {
+        self.on_submit.push(callback);
+    }
+
+    pub fn text(&self) -> &str {
+        self.edit.text()
+    }
+
+    ///Replaces the text as one undoable edit, firing the text changed callbacks.
+    pub fn set_text<T: ToString>(&mut self, text: T) {
+        self.apply(|edit| edit.set_text(text));
+    }
+
+    pub fn edit(&self) -> &TextEdit {
+        &self.edit
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    ///Mirrors the focus of the context, see `UiContext::focus`.
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.pressed &= focused;
+        self.blink = 0f32;
+    }
+
+    pub fn submit(&mut self) {
+        let text = self.edit.text().to_string();
+        let mut callbacks = std::mem::take(&mut self.on_submit);
+        for callback in &mut callbacks {
+            (callback)(self, text.clone());
+        }
+        callbacks.append(&mut self.on_submit);
+        self.on_submit = callbacks;
+    }
+
+    ///Runs an edit, then fires the text changed callbacks if the text changed.
+    fn apply<F: FnOnce(&mut TextEdit)>(&mut self, f: F) {
+        let old = self.edit.text().to_string();
+        f(&mut self.edit);
+        self.blink = 0f32;
+        if old == self.edit.text() {
+            return;
+        }
+        self.dirty = true;
+
+        let new = self.edit.text().to_string();
+        let mut callbacks = std::mem::take(&mut self.on_text_changed);
+        for callback in &mut callbacks {
+            (callback)(self, old.clone(), new.clone());
+        }
+        callbacks.append(&mut self.on_text_changed);
+        self.on_text_changed = callbacks;
+    }
+
+    ///Top of the first line of text in UI coordinates.
+    fn text_top(&self) -> f32 {
+        (self.data.position[1] + self.data.height.saturating_sub(PADDING)) as f32
+    }
+
+    fn text_left(&self) -> f32 {
+        (self.data.position[0] + PADDING) as f32
+    }
+
+    fn stop(&self, index: usize) -> CaretStop {
+        self.stops.iter().find(|stop| stop.index == index).cloned().unwrap_or(CaretStop { index, line: 0, x: 0f32 })
+    }
+
+    ///The caret position closest to `point`.
+    fn index_at(&self, point: [f64; 2]) -> usize {
+        let lines = self.stops.last().map(|stop| stop.line + 1).unwrap_or(1);
+        let line = ((self.text_top() - point[1] as f32) / self.line_height).floor().max(0f32) as usize;
+        let line = line.min(lines - 1);
+        let x = point[0] as f32 - self.text_left();
+        self.stops.iter()
+            .filter(|stop| stop.line == line)
+            .min_by(|a, b| (a.x - x).abs().total_cmp(&(b.x - x).abs()))
+            .map(|stop| stop.index)
+            .unwrap_or(self.edit.text().len())
+    }
+
+    ///Rebuilds the mesh and caret stops from the current text.
+    fn rebuild(&mut self, ui: &mut UiContext) {
+        let text = ui.text_mut();
+        if let Err(error) = text.prepare_program() {
+            eprintln!("{}", error);
+            return;
+        }
+        let (font_name, font_size) = (self.font.get().as_deref(), *self.font_size.get());
+        let font = match text.resolve(font_name) {
+            Ok(font) => font,
+            Err(error) => {
+                eprintln!("Could not lay out text input: {}", error);
+                return;
+            }
+        };
+        self.line_height = font.line_height(font_size);
+        let layout = match text.layout(font_name, self.edit.text(), font_size, TextAlignment::Left, None) {
+            Ok(layout) => layout,
+            Err(error) => {
+                eprintln!("Could not lay out text input: {}", error);
+                return;
+            }
+        };
+
+        //Line breaks have no glyph, every other character has one, in order.
+        self.stops.clear();
+        let mut glyphs = layout.glyphs().iter();
+        let (mut line, mut x) = (0, 0f32);
+        for (index, character) in self.edit.text().char_indices() {
+            if character == '\n' {
+                self.stops.push(CaretStop { index, line, x });
+                line += 1;
+                x = 0f32;
+                continue;
+            }
+            if let Some(glyph) = glyphs.next() {
+                self.stops.push(CaretStop { index, line, x: glyph.position[0] });
+                x = glyph.position[0] + glyph.advance;
+            }
+        }
+        self.stops.push(CaretStop { index: self.edit.text().len(), line, x });
+
+        self.mesh = match text.build_mesh(font_name, self.edit.text(), font_size, TextAlignment::Left, None) {
+            Ok(mesh) => Some(mesh),
+            Err(error) => {
+                eprintln!("Could not lay out text input: {}", error);
+                None
+            }
+        };
+        self.caret.element_data_mut().height = self.line_height.ceil() as u32;
+        self.dirty = false;
+    }
+
+    ///Places the caret and one highlight per selected line.
+    fn place_overlays(&mut self) {
+        let (left, top) = (self.text_left(), self.text_top());
+        let line_height = self.line_height;
+        let to_ui = |stop: &CaretStop| [left + stop.x, top - (stop.line + 1) as f32 * line_height];
+
+        let caret = to_ui(&self.stop(self.edit.caret()));
+        self.caret.set_position([caret[0].max(0f32) as u32, caret[1].max(0f32) as u32]);
+
+        let mut spans = vec![];
+        if let Some(range) = self.edit.selection() {
+            let mut start = self.stop(range.start);
+            for stop in self.stops.iter().filter(|stop| stop.index > range.start && stop.index <= range.end) {
+                if stop.line != start.line || stop.index == range.end {
+                    //Selected line breaks show as a space.
+                    let end = match stop.line != start.line {
+                        true => self.stops.iter().filter(|other| other.line == start.line).last().map(|last| last.x + *self.font_size.get() / 3f32).unwrap_or(start.x),
+                        false => stop.x
+                    };
+                    spans.push((to_ui(&start), end - start.x));
+                    start = *stop;
+                }
+            }
+        }
+        while self.selection.len() < spans.len() {
+            self.selection.push(Rectangle::new([0, 0], [0f32; 2]).with_custom_property(&"color", CustomUIProperty::Vec4(self.selection_color)));
+        }
+        self.selection.truncate(spans.len());
+        for (rectangle, (position, width)) in self.selection.iter_mut().zip(spans) {
+            rectangle.set_position([position[0].max(0f32) as u32, position[1].max(0f32) as u32]);
+            rectangle.element_data_mut().width = width.max(0f32).ceil() as u32;
+            rectangle.element_data_mut().height = line_height.ceil() as u32;
+        }
+    }
+}
+
+impl UIElement for TextInput {
+    fn tag(&self) -> &'static str {
+        "TextInput"
+    }
+
+    fn save_state(&self) -> Option<ElementState> {
+        Some(ElementState::Text(self.text().to_string()))
+    }
+
+    fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    ///Puts text color, font and font size of the style on top of its own.
+    fn apply_style(&mut self, style: &Style, _stylesheet: &Stylesheet) {
+        if self.text_color.set_styled(style.text_color()) {
+            self.caret.set_color(*self.text_color.get());
+        }
+        let font = self.font.set_styled(style.font().map(|font| Some(font.to_string())));
+        let font_size = self.font_size.set_styled(style.font_size());
+        if font || font_size {
+            self.dirty = true;
+        }
+    }
+
+    ///Starts a fresh undo history, like `with_text`.
+    fn restore_state(&mut self, state: ElementState) {
+        if let ElementState::Text(text) = state {
+            self.edit = TextEdit::new(text, self.edit.is_multiline());
+        }
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, point: [f64; 2]) -> bool {
+        shape_contains(self, point)
+    }
+
+    ///Extends the selection while dragging.
+    fn update_cursor(&mut self, _ui: &mut UiContext, cursor: [f64; 2]) {
+        if self.pressed {
+            let index = self.index_at(cursor);
+            self.edit.move_to(index, true);
+        }
+    }
+
+    ///Places the caret where the input is pressed. The context focused it before dispatching the press.
+    fn on_mouse_event(&mut self, _ui: &mut UiContext, event: &mut MouseEvent) {
+        if event.at_target() && event.kind() == MouseEventKind::Down(MouseButton::Button1) && self.focused {
+            let shift = event.modifiers().contains(Modifiers::Shift);
+            let index = self.index_at(event.position());
+            self.edit.move_to(index, shift);
+            self.pressed = true;
+        }
+    }
+
+    fn update_mouse(&mut self, _ui: &mut UiContext, mouse_button: MouseButton, action: Action) {
+        if mouse_button == MouseButton::Button1 && action == Action::Release {
+            self.pressed = false;
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn on_focus(&mut self, _ui: &mut UiContext) {
+        self.set_focused(true);
+    }
+
+    fn on_blur(&mut self, _ui: &mut UiContext) {
+        self.set_focused(false);
+    }
+
+    ///Handles every key it has a binding for, leaving the others, like tab, to its ancestors.
+    fn update_key(&mut self, ui: &mut UiContext, key: Key, code: Action, _delta: f32) -> bool {
+        if code == Action::Release {
+            return false;
+        }
+        let control = Input::is_key_held(Key::LeftControl) || Input::is_key_held(Key::RightControl);
+        let shift = Input::is_key_held(Key::LeftShift) || Input::is_key_held(Key::RightShift);
+        let multiline = self.edit.is_multiline();
+        self.blink = 0f32;
+        match key {
+            Key::Left => self.edit.move_left(shift, control),
+            Key::Right => self.edit.move_right(shift, control),
+            Key::Up if multiline => self.edit.move_lines(-1, shift),
+            Key::Down if multiline => self.edit.move_lines(1, shift),
+            Key::Home => self.edit.move_home(shift, control),
+            Key::End => self.edit.move_end(shift, control),
+            Key::Backspace => self.apply(|edit| edit.backspace(control)),
+            Key::Delete => self.apply(|edit| edit.delete(control)),
+            Key::Enter | Key::KpEnter if multiline && !control => self.apply(|edit| edit.insert("\n")),
+            Key::Enter | Key::KpEnter => self.submit(),
+            //The context cannot notify the input while it is taken out for this call.
+            Key::Escape => {
+                self.set_focused(false);
+                ui.blur();
+            }
+            Key::A if control => self.edit.select_all(),
+            Key::C if control => {
+                if let Some(text) = self.edit.selected_text() {
+                    ui.set_clipboard_text(text);
+                }
+            }
+            Key::X if control => {
+                let mut cut = None;
+                self.apply(|edit| cut = edit.cut());
+                if let Some(text) = cut {
+                    ui.set_clipboard_text(&text);
+                }
+            }
+            Key::V if control => {
+                if let Some(text) = ui.clipboard_text() {
+                    self.apply(|edit| edit.insert(&text));
+                }
+            }
+            Key::Z if control && shift => self.apply(|edit| { edit.redo(); }),
+            Key::Z if control => self.apply(|edit| { edit.undo(); }),
+            Key::Y if control => self.apply(|edit| { edit.redo(); }),
+            _ => return false,
+        }
+        true
+    }
+
+    fn update_char(&mut self, _ui: &mut UiContext, character: char) {
+        if self.focused && !character.is_control() {
+            self.apply(|edit| edit.insert(&character.to_string()));
+        }
+    }
+
+    fn update(&mut self, ui: &mut UiContext, delta: f32) {
+        if self.dirty {
+            self.rebuild(ui);
+        }
+        self.place_overlays();
+        self.blink = (self.blink + delta) % (BLINK_INTERVAL * 2f32);
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        self.draw_self(ui, camera);
+        if self.focused {
+            for rectangle in &self.selection {
+                rectangle.draw(ui, camera);
+            }
+        }
+        if let Some(mesh) = &self.mesh {
+            let origin = [self.text_left(), self.text_top() - mesh.size()[1]];
+            ui.text().draw(mesh, camera.last_orthographic(), origin, self.text_color.get());
+        }
+        if self.focused && self.blink < BLINK_INTERVAL {
+            self.caret.draw(ui, camera);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt::{Debug, Formatter};
+use glfw::{Action, Key, MouseButton};
+use vecmath::Vector4;
+use crate::gfx::ui::{ui_counter, UiContext, UIElement, UIElementData};
+use crate::gfx::ui::event::{EventPhase, MouseEvent, MouseEventKind};
+use crate::gfx::ui::shape::shape_contains;
+use crate::gfx::ui::callbacks::OnToggleValueChanged;
+use crate::gfx::ui::interactable::{arrange_indicator_row, child_hovered, is_activation_key};
+use crate::gfx::ui::label::Label;
+use crate::gfx::ui::markup::reload::ElementState;
+use crate::{Camera, CustomUIProperty, Rectangle};
+
+///Space between the knob and the ends of the track.
+const KNOB_INSET: u32 = 3;
+
+///A switch: a rounded track with a knob on its left while off and on its right while on, and an optional label.
+///Switched by clicking the track or the label, or with space or enter while focused.
+pub struct Toggle {
+    data: UIElementData,
+    on: bool,
+    track: usize,
+    knob: usize,
+    label: Option<usize>,
+    off_color: Vector4<f32>,
+    on_color: Vector4<f32>,
+    on_value_changed: Vec<Box<OnToggleValueChanged>>,
+}
+
+impl Debug for Toggle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Toggle [on: {}, data: {:?}, callbacks: {}]", self.on, self.data, self.on_value_changed.len()))
+    }
+}
+
+impl Toggle {
+    ///A track of `size` pixels, with a round knob as high as the track minus its inset.
+    pub fn new(size: [u32; 2]) -> Toggle {
+        let off_color = [0.6, 0.6, 0.6, 1.0];
+        let track = Rectangle::new([0, 0], [size[0] as f32, size[1] as f32])
+            .with_custom_property(&"color", CustomUIProperty::Vec4(off_color))
+            .with_corner_radius(size[1] as f32 / 2f32)
+            .with_class("toggle-track");
+        let knob_size = size[1].saturating_sub(2 * KNOB_INSET) as f32;
+        let knob = Rectangle::new([0, 0], [knob_size; 2])
+            .with_custom_property(&"color", CustomUIProperty::Vec4([1.0, 1.0, 1.0, 1.0]))
+            .with_corner_radius(knob_size / 2f32)
+            .with_class("toggle-knob");
+
+        let mut toggle = Self {
+            data: UIElementData::new(ui_counter(), [0; 2], size),
+            on: false,
+            track: track.id(),
+            knob: knob.id(),
+            label: None,
+            off_color,
+            on_color: [0.25, 0.55, 0.9, 1.0],
+            on_value_changed: vec![],
+        };
+        toggle.add_child(Box::new(track));
+        toggle.add_child(Box::new(knob));
+        toggle
+    }
+
+    pub fn with_position(mut self, position: [u32; 2]) -> Toggle {
+        self.set_position(position);
+        self
+    }
+
+    ///Replaces the label. Only works before the toggle is registered.
+    pub fn with_label(mut self, label: Label) -> Toggle {
+        self.label = Some(label.id());
+        self.add_child(Box::new(label));
+        self
+    }
+
+    pub fn with_text<T: ToString>(self, text: T, font_size: f32) -> Toggle {
+        self.with_label(Label::new(text, font_size))
+    }
+
+    ///Sets the initial state without firing callbacks.
+    pub fn with_on(mut self, on: bool) -> Toggle {
+        self.on = on;
+        self
+    }
+
+    ///Colors of the track while off and on.
+    pub fn with_colors(mut self, off: Vector4<f32>, on: Vector4<f32>) -> Toggle {
+        self.off_color = off;
+        self.on_color = on;
+        self
+    }
+
+    pub fn with_on_value_changed(mut self, callback: Box<OnToggleValueChanged>) -> Toggle {
+        self.register_on_value_changed(callback);
+        self
+    }
+
+    pub fn register_on_value_changed(&mut self, callback: Box<OnToggleValueChanged>) {
+        self.on_value_changed.push(callback);
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    pub fn set_on(&mut self, on: bool) {
+        if self.on == on {
+            return;
+        }
+        let old = self.on;
+        self.on = on;
+
+        let mut callbacks = std::mem::take(&mut self.on_value_changed);
+        for callback in &mut callbacks {
+            (callback)(self, old, on);
+        }
+        callbacks.append(&mut self.on_value_changed);
+        self.on_value_changed = callbacks;
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_on(!self.on);
+    }
+
+    pub fn label<'a>(&self, ui: &'a UiContext) -> Option<&'a Label> {
+        ui.get_element_by_id(self.label.as_ref()?)?.downcast_ref::<Label>()
+    }
+
+    pub fn label_mut<'a>(&self, ui: &'a mut UiContext) -> Option<&'a mut Label> {
+        ui.get_element_by_id_mut(self.label.as_ref()?)?.downcast_mut::<Label>()
+    }
+}
+
+impl UIElement for Toggle {
+    fn tag(&self) -> &'static str {
+        "Toggle"
+    }
+
+    fn save_state(&self) -> Option<ElementState> {
+        Some(ElementState::Checked(self.on))
+    }
+
+    fn restore_state(&mut self, state: ElementState) {
+        if let ElementState::Checked(on) = state {
+            self.on = on;
+        }
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, point: [f64; 2]) -> bool {
+        shape_contains(self, point)
+    }
+
+    fn update_cursor(&mut self, ui: &mut UiContext, _cursor: [f64; 2]) {
+        if child_hovered(ui, &self.data.children) {
+            self.data.hover_flag = true;
+        }
+    }
+
+    ///Clicks on the parts bubble up to the widget itself.
+    fn on_mouse_event(&mut self, _ui: &mut UiContext, event: &mut MouseEvent) {
+        if event.phase() != EventPhase::Capture && event.kind() == MouseEventKind::Click(MouseButton::Button1) {
+            self.toggle();
+            event.stop_propagation();
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn update_key(&mut self, _ui: &mut UiContext, key: Key, code: Action, _delta: f32) -> bool {
+        if !is_activation_key(key) {
+            return false;
+        }
+        if code == Action::Press {
+            self.toggle();
+        }
+        true
+    }
+
+    ///Lays the parts out like a checkbox, then moves the knob to the end matching the state.
+    fn update(&mut self, ui: &mut UiContext, _delta: f32) {
+        let size = arrange_indicator_row(ui, self.data.position, &self.track, &self.knob, self.label.as_ref());
+        self.data.width = size[0];
+        self.data.height = size[1];
+
+        let (on, color) = (self.on, if self.on { self.on_color } else { self.off_color });
+        let track = match ui.get_element_by_id_mut(&self.track) {
+            None => return,
+            Some(track) => {
+                track.set_color(color);
+                //For stylesheets, which replace the colors above.
+                match on {
+                    true => track.add_class("on"),
+                    false => track.remove_class("on")
+                }
+                (track.position()[0], track.element_data().width())
+            }
+        };
+        if let Some(knob) = ui.get_element_by_id_mut(&self.knob) {
+            let x = match on {
+                true => (track.0 + track.1).saturating_sub(knob.element_data().width() + KNOB_INSET),
+                false => track.0 + KNOB_INSET
+            };
+            let y = knob.position()[1];
+            knob.set_position([x, y]);
+        }
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        let mut parts = vec![self.track, self.knob];
+        parts.extend(self.label);
+        for part in parts {
+            if let Some(part) = ui.get_element_by_id(&part) {
+                part.draw(ui, camera);
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{Camera, CustomUIProperty};
+use crate::gfx::text::TextAlignment;
+use crate::gfx::text::renderer::TextMesh;
+use crate::gfx::ui::{ui_counter, UiContext, UIElement, UIElementData};
+use crate::gfx::ui::shape::shape_contains;
+use crate::gfx::ui::style::{Style, Styled};
+use crate::gfx::ui::style::stylesheet::Stylesheet;
+
+///A string drawn with one of the fonts of the `UiContext`. Its size follows the text.
+///The geometry is rebuilt in `update` after the text or its style changed.
+#[derive(Debug)]
+pub struct Label {
+    data: UIElementData,
+    text: String,
+    font: Styled<Option<String>>,
+    font_size: Styled<f32>,
+    alignment: TextAlignment,
+    max_width: Option<f32>,
+    sdf: bool,
+    mesh: Option<TextMesh>,
+    dirty: bool,
+}
+
+impl Label {
+    pub fn new<T: ToString>(text: T, font_size: f32) -> Label {
+        Self {
+            data: UIElementData::new(ui_counter(), [0; 2], [0; 2]),
+            text: text.to_string(),
+            font: Styled::new(None),
+            font_size: Styled::new(font_size),
+            alignment: TextAlignment::Left,
+            max_width: None,
+            sdf: false,
+            mesh: None,
+            dirty: true,
+        }
+    }
+
+    pub fn with_position(mut self, position: [u32; 2]) -> Label {
+        self.set_position(position);
+        self
+    }
+
+    ///Uses a font added to the context under `font`, instead of its default font.
+    pub fn with_font<T: ToString>(mut self, font: T) -> Label {
+        self.font.set_own(Some(font.to_string()));
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: TextAlignment) -> Label {
+        self.alignment = alignment;
+        self
+    }
+
+    ///Wraps lines between words to fit `max_width` pixels, which also becomes the width alignment happens in.
+    pub fn with_max_width(mut self, max_width: f32) -> Label {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    ///Draws from the SDF atlas of the font, which has to be added to the context's text renderer.
+    ///Use this for text that gets scaled or zoomed.
+    pub fn with_sdf(mut self) -> Label {
+        self.sdf = true;
+        self
+    }
+
+    pub fn with_color(mut self, color: [f32; 4]) -> Label {
+        self.set_color(color);
+        self
+    }
+
+    pub fn with_custom_property(mut self, property: &dyn ToString, value: CustomUIProperty) -> Label {
+        self.set_custom_property(property, value);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text<T: ToString>(&mut self, text: T) {
+        let text = text.to_string();
+        if self.text != text {
+            self.text = text;
+            self.dirty = true;
+        }
+    }
+
+    ///The font of the computed style, or the one set on the label.
+    pub fn font(&self) -> Option<&String> {
+        self.font.get().as_ref()
+    }
+
+    pub fn set_font(&mut self, font: Option<String>) {
+        if self.font.set_own(font) {
+            self.dirty = true;
+        }
+    }
+
+    ///The font size of the computed style, or the one set on the label.
+    pub fn font_size(&self) -> f32 {
+        *self.font_size.get()
+    }
+
+    pub fn set_font_size(&mut self, font_size: f32) {
+        if self.font_size.set_own(font_size) {
+            self.dirty = true;
+        }
+    }
+
+    pub fn alignment(&self) -> TextAlignment {
+        self.alignment
+    }
+
+    pub fn set_alignment(&mut self, alignment: TextAlignment) {
+        self.alignment = alignment;
+        self.dirty = true;
+    }
+
+    pub fn max_width(&self) -> Option<f32> {
+        self.max_width
+    }
+
+    pub fn set_max_width(&mut self, max_width: Option<f32>) {
+        self.max_width = max_width;
+        self.dirty = true;
+    }
+
+    pub fn is_sdf(&self) -> bool {
+        self.sdf
+    }
+
+    pub fn set_sdf(&mut self, sdf: bool) {
+        self.sdf = sdf;
+        self.dirty = true;
+    }
+
+    fn rebuild(&mut self, ui: &mut UiContext) {
+        let text = ui.text_mut();
+        if let Err(error) = text.prepare_program() {
+            eprintln!("{}", error);
+            return;
+        }
+        let mesh = match self.sdf {
+            true => text.build_sdf_mesh(self.font.get().as_deref(), &self.text, *self.font_size.get(), self.alignment, self.max_width),
+            false => text.build_mesh(self.font.get().as_deref(), &self.text, *self.font_size.get(), self.alignment, self.max_width)
+        };
+        match mesh {
+            Err(error) => eprintln!("Could not lay out label \"{}\": {}", self.text, error),
+            Ok(mesh) => {
+                self.data.width = mesh.size()[0].ceil() as u32;
+                self.data.height = mesh.size()[1].ceil() as u32;
+                self.mesh = Some(mesh);
+            }
+        }
+        self.dirty = false;
+    }
+}
+
+impl UIElement for Label {
+    fn tag(&self) -> &'static str {
+        "Label"
+    }
+
+    fn element_data(&self) -> &UIElementData {
+        &self.data
+    }
+
+    fn element_data_mut(&mut self) -> &mut UIElementData {
+        &mut self.data
+    }
+
+    fn resize(&mut self, _size: [i32; 2]) {}
+
+    fn contains_point(&self, point: [f64; 2]) -> bool {
+        shape_contains(self, point)
+    }
+
+    ///Puts font and font size of the style on top of its own, relaying out only on a change.
+    fn apply_style(&mut self, style: &Style, _stylesheet: &Stylesheet) {
+        let font = self.font.set_styled(style.font().map(|font| Some(font.to_string())));
+        let font_size = self.font_size.set_styled(style.font_size());
+        if font || font_size {
+            self.dirty = true;
+        }
+    }
+
+    fn update(&mut self, ui: &mut UiContext, _delta: f32) {
+        if self.dirty {
+            self.rebuild(ui);
+        }
+    }
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        if let Some(mesh) = &self.mesh {
+            let position = self.position();
+            let color = ui.style_of(&self.id()).and_then(|style| style.text_color()).unwrap_or_else(|| self.color());
+            ui.text().draw(mesh, camera.last_orthographic(), [position[0] as f32, position[1] as f32], &color);
+        }
+    }
+}
@@ -0,0 +1,714 @@
+use std::collections::HashMap;
+use crate::gfx::ui::layout::flex::{FlexEntry, FlexLayout};
+use crate::gfx::ui::layout::grid::{align, GridCell, GridLayout};
+
+///Space around the four sides of a box.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Insets {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Insets {
+    pub fn new(left: f32, right: f32, top: f32, bottom: f32) -> Insets {
+        Self { left, right, top, bottom }
+    }
+
+    pub fn all(inset: f32) -> Insets {
+        Self::new(inset, inset, inset, inset)
+    }
+
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Insets {
+        Self::new(horizontal, horizontal, vertical, vertical)
+    }
+
+    ///Left and right, then top and bottom.
+    pub fn sum(&self) -> [f32; 2] {
+        [self.left + self.right, self.top + self.bottom]
+    }
+
+    ///Left and top.
+    pub fn start(&self) -> [f32; 2] {
+        [self.left, self.top]
+    }
+}
+
+///How large a box wants to be along one axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Size {
+    Fixed(f32),
+    ///Percent of the space the parent offers.
+    Percent(f32),
+    ///All of the space the parent offers. Along the stacking axis of the parent, the space its other children leave.
+    MatchParent,
+    ///Just large enough for the children plus padding.
+    WrapContent,
+}
+
+///How a box places its children.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arrangement {
+    ///Top to bottom.
+    Vertical,
+    ///Left to right.
+    Horizontal,
+    ///On top of each other, in the top left corner.
+    Overlay,
+    Flex(FlexLayout),
+    Grid(GridLayout),
+}
+
+impl Arrangement {
+    ///Axis of the stacking arrangements.
+    fn main_axis(&self) -> Option<usize> {
+        match self {
+            Arrangement::Vertical => Some(1),
+            Arrangement::Horizontal => Some(0),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutStyle {
+    ///Width and height.
+    pub size: [Size; 2],
+    pub min: [f32; 2],
+    pub max: [Option<f32>; 2],
+    ///Space between the border of the box and its children.
+    pub padding: Insets,
+    ///Space around the box, kept free by its parent.
+    pub margin: Insets,
+    pub arrangement: Arrangement,
+    ///Space between neighbouring children.
+    pub spacing: f32,
+    ///Distance the children are moved left and up, e.g. by scrolling.
+    pub scroll: [f32; 2],
+    ///Share of the free space of its line the box grows by, in a `Flex` parent.
+    pub grow: f32,
+    ///Weight, relative to its size, the box shrinks with when its line overflows, in a `Flex` parent.
+    pub shrink: f32,
+    ///Cell in a `Grid` parent, the next free one for `None`.
+    pub cell: Option<GridCell>,
+}
+
+impl LayoutStyle {
+    pub fn new() -> LayoutStyle {
+        Self {
+            size: [Size::WrapContent; 2],
+            min: [0f32; 2],
+            max: [None; 2],
+            padding: Default::default(),
+            margin: Default::default(),
+            arrangement: Arrangement::Vertical,
+            spacing: 0f32,
+            scroll: [0f32; 2],
+            grow: 0f32,
+            shrink: 1f32,
+            cell: None,
+        }
+    }
+
+    pub fn fixed(size: [f32; 2]) -> LayoutStyle {
+        Self::new().with_size([Size::Fixed(size[0]), Size::Fixed(size[1])])
+    }
+
+    pub fn with_size(mut self, size: [Size; 2]) -> LayoutStyle {
+        self.size = size;
+        self
+    }
+
+    pub fn with_min(mut self, min: [f32; 2]) -> LayoutStyle {
+        self.min = min;
+        self
+    }
+
+    pub fn with_max(mut self, max: [Option<f32>; 2]) -> LayoutStyle {
+        self.max = max;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Insets) -> LayoutStyle {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_margin(mut self, margin: Insets) -> LayoutStyle {
+        self.margin = margin;
+        self
+    }
+
+    pub fn with_arrangement(mut self, arrangement: Arrangement) -> LayoutStyle {
+        self.arrangement = arrangement;
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: f32) -> LayoutStyle {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_scroll(mut self, scroll: [f32; 2]) -> LayoutStyle {
+        self.scroll = scroll;
+        self
+    }
+
+    pub fn with_flex(mut self, grow: f32, shrink: f32) -> LayoutStyle {
+        self.grow = grow;
+        self.shrink = shrink;
+        self
+    }
+
+    pub fn with_cell(mut self, cell: Option<GridCell>) -> LayoutStyle {
+        self.cell = cell;
+        self
+    }
+
+    fn clamp(&self, axis: usize, size: f32) -> f32 {
+        size.min(self.max[axis].unwrap_or(f32::MAX)).max(self.min[axis])
+    }
+}
+
+impl Default for LayoutStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///Position of the top left corner, y pointing down, and size of a box.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Frame {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+}
+
+impl Frame {
+    pub fn new(position: [f32; 2], size: [f32; 2]) -> Frame {
+        Self { position, size }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    style: LayoutStyle,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    dirty: bool,
+    ///Space offered and resulting size of the last measure pass.
+    measured: Option<([f32; 2], [f32; 2])>,
+    ///Size the children took up in the last measure pass, margins and spacing included.
+    content: [f32; 2],
+    frame: Option<Frame>,
+}
+
+///Boxes keyed by id, laid out in two passes: `measure` works out sizes bottom up, `arrange` positions top down.
+///
+///Changing a style or the children of a box marks it and its ancestors dirty. `layout` skips trees without dirty
+///boxes whose area did not change, and reuses measurements of clean subtrees.
+///Nothing in here touches GL, so it can run anywhere.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutTree {
+    nodes: HashMap<usize, Node>,
+    ///Area each root was last laid out in.
+    areas: HashMap<usize, Frame>,
+}
+
+impl LayoutTree {
+    pub fn new() -> LayoutTree {
+        Default::default()
+    }
+
+    ///Adds or updates a box. Children that are not in the tree yet are added as empty, wrap-content boxes.
+    pub fn set_node(&mut self, id: usize, style: LayoutStyle, children: Vec<usize>) {
+        let changed = match self.nodes.get(&id) {
+            None => true,
+            Some(node) => node.style != style || node.children != children
+        };
+        if !changed {
+            return;
+        }
+
+        let old_children = self.nodes.get(&id).map(|node| node.children.clone()).unwrap_or_default();
+        for child in old_children {
+            if !children.contains(&child) {
+                if let Some(child) = self.nodes.get_mut(&child) {
+                    child.parent = None;
+                }
+            }
+        }
+        for child in &children {
+            let node = self.nodes.entry(*child).or_insert_with(|| Node::new(LayoutStyle::new()));
+            node.parent = Some(id);
+        }
+
+        let node = self.nodes.entry(id).or_insert_with(|| Node::new(LayoutStyle::new()));
+        node.style = style;
+        node.children = children;
+        self.mark_dirty(&id);
+    }
+
+    ///Removes the box and its descendants.
+    pub fn remove(&mut self, id: &usize) {
+        let node = match self.nodes.remove(id) {
+            None => return,
+            Some(node) => node
+        };
+        self.areas.remove(id);
+        if let Some(parent) = node.parent {
+            if let Some(parent_node) = self.nodes.get_mut(&parent) {
+                parent_node.children.retain(|child| child != id);
+            }
+            self.mark_dirty(&parent);
+        }
+        for child in node.children {
+            if let Some(child_node) = self.nodes.get_mut(&child) {
+                child_node.parent = None;
+            }
+            self.remove(&child);
+        }
+    }
+
+    ///Marks the box and its ancestors for the next layout.
+    pub fn mark_dirty(&mut self, id: &usize) {
+        let mut current = Some(*id);
+        while let Some(id) = current {
+            current = match self.nodes.get_mut(&id) {
+                None => None,
+                Some(node) => {
+                    node.dirty = true;
+                    node.parent
+                }
+            };
+        }
+    }
+
+    pub fn contains(&self, id: &usize) -> bool {
+        self.nodes.contains_key(id)
+    }
+
+    pub fn is_dirty(&self, id: &usize) -> bool {
+        self.nodes.get(id).map(|node| node.dirty).unwrap_or(false)
+    }
+
+    pub fn style(&self, id: &usize) -> Option<&LayoutStyle> {
+        self.nodes.get(id).map(|node| &node.style)
+    }
+
+    pub fn children(&self, id: &usize) -> Option<&Vec<usize>> {
+        self.nodes.get(id).map(|node| &node.children)
+    }
+
+    ///Size the children took up in the last layout, margins and spacing included. Can exceed the box.
+    pub fn content_size(&self, id: &usize) -> Option<[f32; 2]> {
+        self.nodes.get(id).map(|node| node.content)
+    }
+
+    ///Frame of the border box from the last layout, relative to the area of its root.
+    pub fn frame(&self, id: &usize) -> Option<Frame> {
+        self.nodes.get(id).and_then(|node| node.frame)
+    }
+
+    ///Lays out the tree below `root` within `area`, margins of the root included.
+    ///Returns false if nothing changed since the last layout, in which case the frames are still valid.
+    pub fn layout(&mut self, root: &usize, area: Frame) -> bool {
+        if !self.contains(root) || (!self.is_dirty(root) && self.areas.get(root) == Some(&area)) {
+            return false;
+        }
+        let style = self.nodes[root].style.clone();
+        let margins = style.margin.sum();
+        let available = [(area.size[0] - margins[0]).max(0f32), (area.size[1] - margins[1]).max(0f32)];
+        let size = self.measure(root, available);
+        let start = style.margin.start();
+        self.arrange(root, Frame::new([area.position[0] + start[0], area.position[1] + start[1]], size));
+        self.areas.insert(*root, area);
+        true
+    }
+
+    ///Size of the border box, given the space the parent offers it without its margins.
+    fn measure(&mut self, id: &usize, available: [f32; 2]) -> [f32; 2] {
+        let node = &self.nodes[id];
+        if let (false, Some((offered, size))) = (node.dirty, node.measured) {
+            if offered == available {
+                return size;
+            }
+        }
+        let style = node.style.clone();
+        let children = node.children.clone();
+
+        let own: Vec<Option<f32>> = (0..2).map(|axis| match style.size[axis] {
+            Size::Fixed(size) => Some(size),
+            Size::Percent(percent) => Some(available[axis] * percent / 100f32),
+            Size::MatchParent => Some(available[axis]),
+            Size::WrapContent => None,
+        }).map(|size| size.map(|size| size.max(0f32))).collect();
+        let padding = style.padding.sum();
+        let inner = [
+            (own[0].unwrap_or(available[0]) - padding[0]).max(0f32),
+            (own[1].unwrap_or(available[1]) - padding[1]).max(0f32),
+        ];
+
+        let content = self.measure_children(&children, &style, inner);
+        let mut size = [0f32; 2];
+        for axis in 0..2 {
+            size[axis] = style.clamp(axis, own[axis].unwrap_or(content[axis] + padding[axis]));
+        }
+
+        let node = self.nodes.get_mut(id).unwrap();
+        node.measured = Some((available, size));
+        node.content = content;
+        node.dirty = false;
+        size
+    }
+
+    ///Size the children take up, margins and spacing included.
+    fn measure_children(&mut self, children: &Vec<usize>, style: &LayoutStyle, inner: [f32; 2]) -> [f32; 2] {
+        match &style.arrangement {
+            Arrangement::Flex(flex) => {
+                for child in children {
+                    let margins = self.nodes[child].style.margin.sum();
+                    self.measure(child, [(inner[0] - margins[0]).max(0f32), (inner[1] - margins[1]).max(0f32)]);
+                }
+                return flex.content(&self.flex_entries(children), inner, style.spacing);
+            }
+            Arrangement::Grid(grid) => {
+                for child in children {
+                    let margins = self.nodes[child].style.margin.sum();
+                    self.measure(child, [(inner[0] - margins[0]).max(0f32), (inner[1] - margins[1]).max(0f32)]);
+                }
+                let (cells, sizes) = self.grid_cells(grid, children);
+                return grid.content(&cells, &sizes, style.spacing);
+            }
+            _ => {}
+        }
+        let main = match style.arrangement.main_axis() {
+            None => {
+                let mut content = [0f32; 2];
+                for child in children {
+                    let margins = self.nodes[child].style.margin.sum();
+                    let size = self.measure(child, [(inner[0] - margins[0]).max(0f32), (inner[1] - margins[1]).max(0f32)]);
+                    content = [content[0].max(size[0] + margins[0]), content[1].max(size[1] + margins[1])];
+                }
+                return content;
+            }
+            Some(main) => main
+        };
+        let cross = 1 - main;
+        let spacing = style.spacing * children.len().saturating_sub(1) as f32;
+
+        //Children matching the parent along the main axis share what the others leave.
+        let (matching, others): (Vec<usize>, Vec<usize>) = children.iter()
+            .partition(|child| self.nodes[*child].style.size[main] == Size::MatchParent);
+        let mut content = [0f32; 2];
+        content[main] = spacing;
+        let measure = |tree: &mut LayoutTree, child: &usize, main_space: f32, content: &mut [f32; 2]| {
+            let margins = tree.nodes[child].style.margin.sum();
+            let mut available = [0f32; 2];
+            available[main] = (main_space - margins[main]).max(0f32);
+            available[cross] = (inner[cross] - margins[cross]).max(0f32);
+            let size = tree.measure(child, available);
+            content[main] += size[main] + margins[main];
+            content[cross] = content[cross].max(size[cross] + margins[cross]);
+        };
+        for child in &others {
+            measure(self, child, inner[main], &mut content);
+        }
+        let share = match matching.len() {
+            0 => 0f32,
+            count => (inner[main] - content[main]).max(0f32) / count as f32
+        };
+        for child in &matching {
+            measure(self, child, share, &mut content);
+        }
+        content
+    }
+
+    fn arrange(&mut self, id: &usize, frame: Frame) {
+        let node = self.nodes.get_mut(id).unwrap();
+        node.frame = Some(frame);
+        let style = node.style.clone();
+        let children = node.children.clone();
+
+        //Measured again for the frame, which is larger than the measured size when the parent stretched the box.
+        let padding = style.padding.sum();
+        let inner = [(frame.size[0] - padding[0]).max(0f32), (frame.size[1] - padding[1]).max(0f32)];
+        let content = self.measure_children(&children, &style, inner);
+        self.nodes.get_mut(id).unwrap().content = content;
+
+        let start = style.padding.start();
+        let origin = [frame.position[0] + start[0] - style.scroll[0], frame.position[1] + start[1] - style.scroll[1]];
+        for (child, child_frame) in children.iter().zip(self.child_frames(&children, &style, inner)) {
+            let position = [origin[0] + child_frame.position[0], origin[1] + child_frame.position[1]];
+            self.arrange(child, Frame::new(position, child_frame.size));
+        }
+    }
+
+    ///Frames of the measured children relative to the top left corner of the space inside the padding.
+    fn child_frames(&self, children: &[usize], style: &LayoutStyle, inner: [f32; 2]) -> Vec<Frame> {
+        let margins: Vec<Insets> = children.iter().map(|child| self.nodes[child].style.margin).collect();
+        match &style.arrangement {
+            Arrangement::Flex(flex) => flex.place(&self.flex_entries(children), inner, style.spacing).into_iter()
+                .zip(margins)
+                .map(|((offset, size), margin)| Frame::new([offset[0] + margin.left, offset[1] + margin.top], size))
+                .collect(),
+            Arrangement::Grid(grid) => {
+                let (cells, sizes) = self.grid_cells(grid, children);
+                grid.areas(&cells, &sizes, inner, style.spacing).into_iter()
+                    .zip(cells.iter().zip(sizes).zip(margins))
+                    .map(|((area_offset, area), ((cell, outer), margin))| {
+                        let (x, width) = align(cell.horizontal, area[0], outer[0]);
+                        let (y, height) = align(cell.vertical, area[1], outer[1]);
+                        let margins = margin.sum();
+                        Frame::new(
+                            [area_offset[0] + x + margin.left, area_offset[1] + y + margin.top],
+                            [(width - margins[0]).max(0f32), (height - margins[1]).max(0f32)],
+                        )
+                    })
+                    .collect()
+            }
+            arrangement => {
+                let mut cursor = [0f32; 2];
+                children.iter().zip(margins).map(|(child, margin)| {
+                    let size = self.measured_size(child);
+                    let frame = Frame::new([cursor[0] + margin.left, cursor[1] + margin.top], size);
+                    if let Some(main) = arrangement.main_axis() {
+                        cursor[main] += size[main] + margin.sum()[main] + style.spacing;
+                    }
+                    frame
+                }).collect()
+            }
+        }
+    }
+
+    fn measured_size(&self, id: &usize) -> [f32; 2] {
+        self.nodes[id].measured.map(|(_, size)| size).unwrap_or_default()
+    }
+
+    ///Measured children of a flex box with their factors.
+    fn flex_entries(&self, children: &[usize]) -> Vec<FlexEntry> {
+        children.iter().map(|child| {
+            let style = &self.nodes[child].style;
+            FlexEntry { size: self.measured_size(child), margin: style.margin.sum(), grow: style.grow, shrink: style.shrink }
+        }).collect()
+    }
+
+    ///Cells of the children of a grid and their measured sizes, margins included.
+    fn grid_cells(&self, grid: &GridLayout, children: &[usize]) -> (Vec<GridCell>, Vec<[f32; 2]>) {
+        let cells: Vec<Option<GridCell>> = children.iter().map(|child| self.nodes[child].style.cell).collect();
+        let sizes = children.iter().map(|child| {
+            let (size, margins) = (self.measured_size(child), self.nodes[child].style.margin.sum());
+            [size[0] + margins[0], size[1] + margins[1]]
+        }).collect();
+        (grid.placements(&cells), sizes)
+    }
+}
+
+impl Node {
+    fn new(style: LayoutStyle) -> Node {
+        Self { style, parent: None, children: vec![], dirty: true, measured: None, content: [0f32; 2], frame: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gfx::ui::layout::flex::{Align, FlexDirection, FlexWrap, Justify};
+    use crate::gfx::ui::layout::grid::Track;
+
+    const SCREEN: Frame = Frame { position: [0f32; 2], size: [800f32, 600f32] };
+
+    fn frame(tree: &LayoutTree, id: usize) -> ([f32; 2], [f32; 2]) {
+        let frame = tree.frame(&id).unwrap();
+        (frame.position, frame.size)
+    }
+
+    #[test]
+    fn wrap_content_measures_children_padding_and_spacing() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::new().with_padding(Insets::all(10f32)).with_spacing(5f32), vec![1, 2]);
+        tree.set_node(1, LayoutStyle::fixed([100f32, 20f32]), vec![]);
+        tree.set_node(2, LayoutStyle::fixed([60f32, 30f32]).with_margin(Insets::new(4f32, 0f32, 2f32, 0f32)), vec![]);
+
+        assert!(tree.layout(&0, SCREEN));
+        assert_eq!(frame(&tree, 0), ([0f32, 0f32], [120f32, 77f32]));
+        assert_eq!(frame(&tree, 1), ([10f32, 10f32], [100f32, 20f32]));
+        assert_eq!(frame(&tree, 2), ([14f32, 37f32], [60f32, 30f32]));
+        assert_eq!(tree.content_size(&0), Some([100f32, 57f32]));
+    }
+
+    #[test]
+    fn match_parent_shares_what_the_other_children_leave() {
+        let mut tree = LayoutTree::new();
+        let fill = LayoutStyle::new().with_size([Size::MatchParent, Size::MatchParent]);
+        tree.set_node(0, fill.clone().with_arrangement(Arrangement::Horizontal), vec![1, 2, 3]);
+        tree.set_node(1, LayoutStyle::fixed([200f32, 50f32]), vec![]);
+        tree.set_node(2, fill.clone(), vec![]);
+        tree.set_node(3, fill.with_size([Size::MatchParent, Size::Percent(50f32)]), vec![]);
+
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 0), ([0f32, 0f32], [800f32, 600f32]));
+        assert_eq!(frame(&tree, 2), ([200f32, 0f32], [300f32, 600f32]));
+        assert_eq!(frame(&tree, 3), ([500f32, 0f32], [300f32, 300f32]));
+    }
+
+    #[test]
+    fn min_and_max_clamp_the_measured_size() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::new().with_min([150f32, 0f32]).with_max([None, Some(40f32)]), vec![1]);
+        tree.set_node(1, LayoutStyle::fixed([100f32, 100f32]), vec![]);
+
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 0).1, [150f32, 40f32]);
+    }
+
+    #[test]
+    fn scroll_moves_the_children() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::fixed([100f32, 100f32]).with_arrangement(Arrangement::Overlay).with_scroll([0f32, 30f32]), vec![1]);
+        tree.set_node(1, LayoutStyle::fixed([100f32, 300f32]), vec![]);
+
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 1).0, [0f32, -30f32]);
+        assert_eq!(tree.content_size(&0), Some([100f32, 300f32]));
+    }
+
+    #[test]
+    fn layout_only_reruns_for_changes() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::new(), vec![1, 2]);
+        tree.set_node(1, LayoutStyle::fixed([10f32, 10f32]), vec![]);
+        tree.set_node(2, LayoutStyle::fixed([10f32, 10f32]), vec![]);
+        assert!(tree.is_dirty(&0));
+        assert!(tree.layout(&0, SCREEN));
+        assert!(!tree.is_dirty(&0) && !tree.is_dirty(&1) && !tree.is_dirty(&2));
+
+        //Unchanged styles and areas leave the tree clean.
+        tree.set_node(1, LayoutStyle::fixed([10f32, 10f32]), vec![]);
+        assert!(!tree.is_dirty(&0));
+        assert!(!tree.layout(&0, SCREEN));
+
+        //A changed child marks its ancestors, but not its siblings.
+        tree.set_node(1, LayoutStyle::fixed([10f32, 40f32]), vec![]);
+        assert!(tree.is_dirty(&1) && tree.is_dirty(&0));
+        assert!(!tree.is_dirty(&2));
+        assert!(tree.layout(&0, SCREEN));
+        assert_eq!(frame(&tree, 2).0, [0f32, 40f32]);
+
+        //A new area reruns a clean tree.
+        assert!(tree.layout(&0, Frame::new([5f32, 5f32], [800f32, 600f32])));
+        assert_eq!(frame(&tree, 0).0, [5f32, 5f32]);
+    }
+
+    #[test]
+    fn removing_a_child_marks_the_parent() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::new(), vec![1, 2]);
+        tree.set_node(1, LayoutStyle::fixed([10f32, 10f32]), vec![3]);
+        tree.set_node(2, LayoutStyle::fixed([10f32, 10f32]), vec![]);
+        tree.layout(&0, SCREEN);
+
+        tree.remove(&1);
+        assert!(!tree.contains(&1) && !tree.contains(&3));
+        assert!(tree.is_dirty(&0));
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 2).0, [0f32, 0f32]);
+    }
+
+    fn flex(direction: FlexDirection) -> FlexLayout {
+        FlexLayout::new(direction)
+    }
+
+    #[test]
+    fn flex_grows_by_shares_of_the_free_space() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::fixed([320f32, 100f32]).with_arrangement(Arrangement::Flex(flex(FlexDirection::Row))).with_spacing(10f32), vec![1, 2, 3]);
+        tree.set_node(1, LayoutStyle::fixed([50f32, 20f32]), vec![]);
+        tree.set_node(2, LayoutStyle::fixed([50f32, 20f32]).with_flex(1f32, 1f32), vec![]);
+        tree.set_node(3, LayoutStyle::fixed([50f32, 20f32]).with_flex(2f32, 1f32), vec![]);
+
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 1), ([0f32, 0f32], [50f32, 20f32]));
+        assert_eq!(frame(&tree, 2), ([60f32, 0f32], [100f32, 20f32]));
+        assert_eq!(frame(&tree, 3), ([170f32, 0f32], [150f32, 20f32]));
+    }
+
+    #[test]
+    fn flex_shrinks_overflowing_lines_by_size() {
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::fixed([150f32, 100f32]).with_arrangement(Arrangement::Flex(flex(FlexDirection::Row))), vec![1, 2]);
+        tree.set_node(1, LayoutStyle::fixed([100f32, 20f32]), vec![]);
+        tree.set_node(2, LayoutStyle::fixed([100f32, 20f32]).with_flex(0f32, 0f32), vec![]);
+
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 1).1, [50f32, 20f32]);
+        assert_eq!(frame(&tree, 2), ([50f32, 0f32], [100f32, 20f32]));
+    }
+
+    #[test]
+    fn flex_wraps_justifies_and_aligns() {
+        let mut layout = flex(FlexDirection::Row);
+        layout.wrap = FlexWrap::Wrap;
+        layout.justify = Justify::SpaceBetween;
+        layout.align = Align::Center;
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::fixed([100f32, 100f32]).with_arrangement(Arrangement::Flex(layout)), vec![1, 2, 3]);
+        tree.set_node(1, LayoutStyle::fixed([40f32, 10f32]), vec![]);
+        tree.set_node(2, LayoutStyle::fixed([40f32, 20f32]), vec![]);
+        tree.set_node(3, LayoutStyle::fixed([40f32, 10f32]), vec![]);
+
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 1).0, [0f32, 5f32]);
+        assert_eq!(frame(&tree, 2).0, [60f32, 0f32]);
+        assert_eq!(frame(&tree, 3).0, [0f32, 20f32]);
+    }
+
+    #[test]
+    fn flex_wrap_content_and_stretch_relayout_the_children() {
+        let mut layout = flex(FlexDirection::Column);
+        layout.align = Align::Stretch;
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::new().with_arrangement(Arrangement::Flex(layout)).with_spacing(4f32), vec![1, 2]);
+        tree.set_node(1, LayoutStyle::fixed([120f32, 20f32]), vec![]);
+        tree.set_node(2, LayoutStyle::new().with_arrangement(Arrangement::Horizontal), vec![3]);
+        tree.set_node(3, LayoutStyle::new().with_size([Size::MatchParent, Size::Fixed(10f32)]), vec![]);
+
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 0).1, [800f32, 34f32]);
+        //The stretched box lays its matching child out in its final width, not the one it was measured with.
+        assert_eq!(frame(&tree, 2), ([0f32, 24f32], [800f32, 10f32]));
+        assert_eq!(frame(&tree, 3).1, [800f32, 10f32]);
+    }
+
+    #[test]
+    fn grid_sizes_fixed_fraction_and_auto_tracks() {
+        let grid = GridLayout::new(vec![Track::Fixed(50), Track::Fraction(1f32), Track::Auto], vec![Track::Auto]);
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::fixed([300f32, 200f32]).with_arrangement(Arrangement::Grid(grid)).with_spacing(10f32), vec![1, 2, 3, 4]);
+        tree.set_node(1, LayoutStyle::fixed([20f32, 20f32]), vec![]);
+        tree.set_node(2, LayoutStyle::fixed([20f32, 30f32]), vec![]);
+        tree.set_node(3, LayoutStyle::fixed([70f32, 20f32]), vec![]);
+        tree.set_node(4, LayoutStyle::fixed([20f32, 20f32]).with_cell(Some(GridCell::new(1, 1).with_alignment(Align::Stretch, Align::End))), vec![]);
+
+        tree.layout(&0, SCREEN);
+        assert_eq!(frame(&tree, 1).0, [0f32, 0f32]);
+        assert_eq!(frame(&tree, 2).0, [60f32, 0f32]);
+        assert_eq!(frame(&tree, 3).0, [230f32, 0f32]);
+        assert_eq!(frame(&tree, 4), ([60f32, 40f32], [160f32, 20f32]));
+    }
+
+    #[test]
+    fn grid_auto_tracks_grow_for_spanning_children() {
+        let grid = GridLayout::new(vec![Track::Auto, Track::Auto, Track::Fixed(30)], vec![]);
+        let mut tree = LayoutTree::new();
+        tree.set_node(0, LayoutStyle::new().with_arrangement(Arrangement::Grid(grid)), vec![1, 2, 3]);
+        tree.set_node(1, LayoutStyle::fixed([10f32, 10f32]), vec![]);
+        tree.set_node(2, LayoutStyle::fixed([10f32, 10f32]), vec![]);
+        tree.set_node(3, LayoutStyle::fixed([130f32, 10f32]).with_cell(Some(GridCell::new(1, 0).with_span(1, 3))), vec![]);
+
+        tree.layout(&0, SCREEN);
+        //The tracks it spans lack 80 pixels, which the auto tracks split while the fixed one keeps its size.
+        assert_eq!(frame(&tree, 2).0, [50f32, 0f32]);
+        assert_eq!(frame(&tree, 0).1, [130f32, 20f32]);
+    }
+}
@@ -0,0 +1,177 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlexDirection {
+    ///Left to right.
+    Row,
+    ///Top to bottom.
+    Column,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlexWrap {
+    NoWrap,
+    ///Starts a new line once the next child would not fit the main axis.
+    Wrap,
+}
+
+///Distribution of the free space along the main axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Justify {
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+///Placement of the children of a line along the cross axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Start,
+    End,
+    Center,
+    Stretch,
+}
+
+///Places the children in lines along a row or column. They grow by `LayoutStyle::grow` shares of the free
+///space of their line and shrink with weight `LayoutStyle::shrink` when it overflows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexLayout {
+    pub direction: FlexDirection,
+    pub wrap: FlexWrap,
+    pub justify: Justify,
+    pub align: Align,
+}
+
+///A child during layout, with its measured size, the sum of its margins and its factors.
+pub(super) struct FlexEntry {
+    pub size: [f32; 2],
+    pub margin: [f32; 2],
+    pub grow: f32,
+    pub shrink: f32,
+}
+
+impl FlexEntry {
+    fn outer(&self, axis: usize) -> f32 {
+        self.size[axis] + self.margin[axis]
+    }
+}
+
+impl FlexLayout {
+    pub fn new(direction: FlexDirection) -> FlexLayout {
+        Self { direction, wrap: FlexWrap::NoWrap, justify: Justify::Start, align: Align::Start }
+    }
+
+    ///Main and cross axis index into sizes and positions.
+    pub fn axes(&self) -> (usize, usize) {
+        match self.direction {
+            FlexDirection::Row => (0, 1),
+            FlexDirection::Column => (1, 0),
+        }
+    }
+
+    ///Ranges of the entries on each line.
+    fn lines(&self, entries: &[FlexEntry], available: f32, gap: f32) -> Vec<Range<usize>> {
+        let (main, _) = self.axes();
+        let mut lines = vec![];
+        let mut start = 0;
+        let mut used = 0f32;
+        for (index, entry) in entries.iter().enumerate() {
+            let extent = entry.outer(main) + if index == start { 0f32 } else { gap };
+            if self.wrap == FlexWrap::Wrap && index > start && used + extent > available {
+                lines.push(start..index);
+                start = index;
+                used = entry.outer(main);
+            } else {
+                used += extent;
+            }
+        }
+        if start < entries.len() {
+            lines.push(start..entries.len());
+        }
+        lines
+    }
+
+    ///Size the entries take up before growing or shrinking, gaps included.
+    pub(super) fn content(&self, entries: &[FlexEntry], inner: [f32; 2], gap: f32) -> [f32; 2] {
+        let (main, cross) = self.axes();
+        let lines = self.lines(entries, inner[main], gap);
+        let mut content = [0f32; 2];
+        for line in &lines {
+            let line = &entries[line.clone()];
+            let length = line.iter().map(|entry| entry.outer(main)).sum::<f32>() + gap * line.len().saturating_sub(1) as f32;
+            content[main] = content[main].max(length);
+            content[cross] += line.iter().map(|entry| entry.outer(cross)).fold(0f32, f32::max);
+        }
+        content[cross] += gap * lines.len().saturating_sub(1) as f32;
+        content
+    }
+
+    ///Offset of the margin box and size of every entry within `inner`.
+    pub(super) fn place(&self, entries: &[FlexEntry], inner: [f32; 2], gap: f32) -> Vec<([f32; 2], [f32; 2])> {
+        let (main, cross) = self.axes();
+        let lines = self.lines(entries, inner[main], gap);
+        let single_line = lines.len() == 1;
+        let mut placed = vec![([0f32; 2], [0f32; 2]); entries.len()];
+        let mut cross_offset = 0f32;
+        for line in lines {
+            let count = line.len() as f32;
+            let mut sizes: Vec<[f32; 2]> = entries[line.clone()].iter().map(|entry| entry.size).collect();
+            let line_entries = &entries[line.clone()];
+            let mut free = inner[main] - line_entries.iter().map(|entry| entry.outer(main)).sum::<f32>() - gap * (count - 1f32);
+
+            let grow: f32 = line_entries.iter().map(|entry| entry.grow).sum();
+            let shrink: f32 = line_entries.iter().map(|entry| entry.shrink * entry.size[main]).sum();
+            if free > 0f32 && grow > 0f32 {
+                for (size, entry) in sizes.iter_mut().zip(line_entries) {
+                    size[main] += free * entry.grow / grow;
+                }
+                free = 0f32;
+            } else if free < 0f32 && shrink > 0f32 {
+                for (size, entry) in sizes.iter_mut().zip(line_entries) {
+                    size[main] = (size[main] + free * entry.shrink * entry.size[main] / shrink).max(0f32);
+                }
+                free = 0f32;
+            }
+            let free = free.max(0f32);
+
+            let (mut main_offset, between) = match self.justify {
+                Justify::Start => (0f32, 0f32),
+                Justify::End => (free, 0f32),
+                Justify::Center => (free / 2f32, 0f32),
+                Justify::SpaceBetween if count > 1f32 => (0f32, free / (count - 1f32)),
+                Justify::SpaceBetween => (0f32, 0f32),
+                Justify::SpaceAround => (free / count / 2f32, free / count),
+                Justify::SpaceEvenly => (free / (count + 1f32), free / (count + 1f32)),
+            };
+
+            //A single line spans the whole cross axis, wrapped lines are as thick as their thickest child.
+            let line_cross = match single_line {
+                true => inner[cross],
+                false => line_entries.iter().map(|entry| entry.outer(cross)).fold(0f32, f32::max)
+            };
+
+            for ((index, entry), mut size) in line.zip(line_entries).zip(sizes) {
+                let outer = size[cross] + entry.margin[cross];
+                let offset_in_line = match self.align {
+                    Align::Start => 0f32,
+                    Align::End => line_cross - outer,
+                    Align::Center => (line_cross - outer) / 2f32,
+                    Align::Stretch => {
+                        size[cross] = (line_cross - entry.margin[cross]).max(0f32);
+                        0f32
+                    }
+                };
+                let mut offset = [0f32; 2];
+                offset[main] = main_offset;
+                offset[cross] = cross_offset + offset_in_line;
+                main_offset += size[main] + entry.margin[main] + gap + between;
+                placed[index] = (offset, size);
+            }
+            cross_offset += line_cross + gap;
+        }
+        placed
+    }
+}
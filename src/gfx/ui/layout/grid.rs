@@ -0,0 +1,174 @@
+use crate::gfx::ui::layout::flex::Align;
+
+///Size of a row or column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    ///Pixels.
+    Fixed(u32),
+    ///Share of the space that fixed and auto tracks leave.
+    Fraction(f32),
+    ///As large as the largest child that only spans this track. Children spanning several tracks widen the
+    ///auto tracks among them by what they lack.
+    Auto,
+}
+
+///Placement of a child. Rows count from the top, columns from the left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridCell {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    pub horizontal: Align,
+    pub vertical: Align,
+}
+
+impl GridCell {
+    pub fn new(row: usize, column: usize) -> GridCell {
+        Self { row, column, row_span: 1, column_span: 1, horizontal: Align::Start, vertical: Align::Start }
+    }
+
+    pub fn with_span(mut self, row_span: usize, column_span: usize) -> GridCell {
+        self.row_span = row_span.max(1);
+        self.column_span = column_span.max(1);
+        self
+    }
+
+    pub fn with_alignment(mut self, horizontal: Align, vertical: Align) -> GridCell {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+}
+
+///Places the children in rows and columns, separated by `LayoutStyle::spacing`. Children without a
+///`LayoutStyle::cell` fill the cells in reading order. Rows past the defined ones are `Track::Auto`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridLayout {
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+}
+
+impl GridLayout {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> GridLayout {
+        Self { columns, rows }
+    }
+
+    ///Cells of the children, placing those without one in the first cell not taken yet.
+    pub(super) fn placements(&self, cells: &[Option<GridCell>]) -> Vec<GridCell> {
+        let columns = self.columns.len().max(1);
+        let mut taken: Vec<(usize, usize)> = cells.iter().flatten()
+            .flat_map(|cell| (cell.row..cell.row + cell.row_span).flat_map(move |row| (cell.column..cell.column + cell.column_span).map(move |column| (row, column))))
+            .collect();
+        let mut next = 0;
+        cells.iter().map(|cell| match cell {
+            Some(cell) => *cell,
+            None => {
+                while taken.contains(&(next / columns, next % columns)) {
+                    next += 1;
+                }
+                taken.push((next / columns, next % columns));
+                GridCell::new(next / columns, next % columns)
+            }
+        }).collect()
+    }
+
+    ///Size the tracks take up, gaps included, with fraction tracks as large as auto ones.
+    pub(super) fn content(&self, cells: &[GridCell], sizes: &[[f32; 2]], gap: f32) -> [f32; 2] {
+        let as_auto = |tracks: &[Track]| tracks.iter()
+            .map(|track| match track {
+                Track::Fraction(_) => Track::Auto,
+                track => *track
+            })
+            .collect::<Vec<Track>>();
+        let [columns, rows] = self.track_sizes(&as_auto(&self.columns), &as_auto(&self.rows), cells, sizes, [0f32; 2], gap);
+        let total = |sizes: Vec<f32>| sizes.iter().sum::<f32>() + gap * sizes.len().saturating_sub(1) as f32;
+        [total(columns), total(rows)]
+    }
+
+    ///Offset and size of the area each cell spans within `inner`, trailing gap excluded.
+    pub(super) fn areas(&self, cells: &[GridCell], sizes: &[[f32; 2]], inner: [f32; 2], gap: f32) -> Vec<([f32; 2], [f32; 2])> {
+        let [columns, rows] = self.track_sizes(&self.columns, &self.rows, cells, sizes, inner, gap);
+        let columns = track_offsets(&columns, gap);
+        let rows = track_offsets(&rows, gap);
+        cells.iter().map(|cell| (
+            [columns[cell.column], rows[cell.row]],
+            [
+                columns[cell.column + cell.column_span] - columns[cell.column] - gap,
+                rows[cell.row + cell.row_span] - rows[cell.row] - gap,
+            ],
+        )).collect()
+    }
+
+    fn track_sizes(&self, columns: &[Track], rows: &[Track], cells: &[GridCell], sizes: &[[f32; 2]], inner: [f32; 2], gap: f32) -> [Vec<f32>; 2] {
+        let column_count = cells.iter().map(|cell| cell.column + cell.column_span).fold(columns.len(), usize::max);
+        let row_count = cells.iter().map(|cell| cell.row + cell.row_span).fold(rows.len(), usize::max);
+        let column_spans: Vec<(usize, usize, f32)> = cells.iter().zip(sizes).map(|(cell, size)| (cell.column, cell.column_span, size[0])).collect();
+        let row_spans: Vec<(usize, usize, f32)> = cells.iter().zip(sizes).map(|(cell, size)| (cell.row, cell.row_span, size[1])).collect();
+        [
+            track_sizes(columns, column_count, &column_spans, inner[0], gap),
+            track_sizes(rows, row_count, &row_spans, inner[1], gap),
+        ]
+    }
+}
+
+///Resolves track sizes along one axis. `spans` holds start, span and outer size of every child.
+fn track_sizes(tracks: &[Track], count: usize, spans: &[(usize, usize, f32)], available: f32, gap: f32) -> Vec<f32> {
+    let track = |index: usize| tracks.get(index).cloned().unwrap_or(Track::Auto);
+    let mut sizes: Vec<f32> = (0..count).map(|index| match track(index) {
+        Track::Fixed(size) => size as f32,
+        Track::Fraction(_) => 0f32,
+        Track::Auto => spans.iter()
+            .filter(|(start, span, _)| *start == index && *span == 1)
+            .map(|(_, _, size)| *size)
+            .fold(0f32, f32::max)
+    }).collect();
+
+    //Narrow spans first, so wider ones only add what the tracks they cover still lack.
+    let mut spanning: Vec<&(usize, usize, f32)> = spans.iter().filter(|(_, span, _)| *span > 1).collect();
+    spanning.sort_by_key(|(_, span, _)| *span);
+    for (start, span, size) in spanning {
+        let covered = *start..(*start + *span).min(count);
+        let auto: Vec<usize> = covered.clone().filter(|index| track(*index) == Track::Auto).collect();
+        let spanned = covered.map(|index| sizes[index]).sum::<f32>() + gap * (*span - 1) as f32;
+        if !auto.is_empty() && *size > spanned {
+            let share = (*size - spanned) / auto.len() as f32;
+            auto.iter().for_each(|index| sizes[*index] += share);
+        }
+    }
+
+    let fractions: f32 = (0..count).filter_map(|index| match track(index) {
+        Track::Fraction(fraction) => Some(fraction),
+        _ => None
+    }).sum();
+    if fractions > 0f32 {
+        let used: f32 = sizes.iter().sum::<f32>() + gap * count.saturating_sub(1) as f32;
+        let free = (available - used).max(0f32);
+        for (index, size) in sizes.iter_mut().enumerate() {
+            if let Track::Fraction(fraction) = track(index) {
+                *size = free * fraction / fractions;
+            }
+        }
+    }
+    sizes
+}
+
+///Start of every track followed by the end of the last one.
+fn track_offsets(sizes: &[f32], gap: f32) -> Vec<f32> {
+    let mut offsets = vec![0f32];
+    for size in sizes {
+        let last = *offsets.last().unwrap();
+        offsets.push(last + size + gap);
+    }
+    offsets
+}
+
+///Offset and size of a child within the area it spans.
+pub(super) fn align(align: Align, area: f32, size: f32) -> (f32, f32) {
+    match align {
+        Align::Start => (0f32, size),
+        Align::End => (area - size, size),
+        Align::Center => ((area - size) / 2f32, size),
+        Align::Stretch => (0f32, area),
+    }
+}
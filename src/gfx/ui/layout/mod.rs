@@ -1,7 +1,29 @@
-#[derive(Debug, Clone, Copy)]
+use crate::gfx::ui::layout::engine::Size;
+
+pub mod engine;
+pub mod flex;
+pub mod grid;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Layout {
     //Match parent with min and max padding.
     MatchParent(u32, u32),
+    ///Percent of the size of the parent.
+    Percent(f32),
+    ///Just large enough for the children. Containers that do not stack their children keep their size.
+    WrapContent,
     ///Absolute layout implies that this layout is ignored.
     Absolute
-}
\ No newline at end of file
+}
+
+impl Layout {
+    ///Size for the layout engine along one axis, with the margins before and after. `Absolute` keeps `current`.
+    pub fn size(&self, current: u32) -> (Size, f32, f32) {
+        match self {
+            Layout::MatchParent(before, after) => (Size::MatchParent, *before as f32, *after as f32),
+            Layout::Percent(percent) => (Size::Percent(*percent), 0f32, 0f32),
+            Layout::WrapContent => (Size::WrapContent, 0f32, 0f32),
+            Layout::Absolute => (Size::Fixed(current as f32), 0f32, 0f32),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use vecmath::Vector4;
+use crate::gfx::ui::layout::engine::Insets;
+use crate::gfx::ui::layout::Layout;
+use crate::gfx::ui::markup::MarkupError;
+use crate::gfx::ui::markup::parser::Node;
+
+///Attributes and text of one node, taken one by one while the element is built. Whatever is left afterwards
+///was not understood by the element, which the loader reports.
+#[derive(Debug, Clone)]
+pub struct Attributes {
+    tag: String,
+    line: usize,
+    values: Vec<(String, String)>,
+    text: Option<String>,
+}
+
+impl Attributes {
+    pub fn new(node: &Node) -> Attributes {
+        Self {
+            tag: node.tag().to_string(),
+            line: node.line(),
+            values: node.attributes().clone(),
+            text: Some(node.text().to_string()).filter(|text| !text.is_empty()),
+        }
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn take(&mut self, name: &str) -> Option<String> {
+        let index = self.values.iter().position(|(key, _)| key == name)?;
+        Some(self.values.remove(index).1)
+    }
+
+    ///The text between the tags, e.g. of a label.
+    pub fn take_text(&mut self) -> Option<String> {
+        self.text.take()
+    }
+
+    ///The attribute `text`, or else the text between the tags.
+    pub fn take_text_or_content(&mut self) -> Option<String> {
+        let content = self.take_text();
+        self.take("text").or(content)
+    }
+
+    ///Takes the attribute and converts it with `parse`, describing the accepted values as `expected` on failure.
+    pub fn take_parsed<T, F>(&mut self, name: &str, expected: &'static str, parse: F) -> Result<Option<T>, MarkupError>
+        where F: FnOnce(&str) -> Option<T> {
+        match self.take(name) {
+            None => Ok(None),
+            Some(value) => match parse(value.trim()) {
+                None => Err(MarkupError::InvalidValue(self.line, name.to_string(), value, expected)),
+                Some(parsed) => Ok(Some(parsed))
+            }
+        }
+    }
+
+    pub fn take_f32(&mut self, name: &str) -> Result<Option<f32>, MarkupError> {
+        self.take_parsed(name, "a number", |value| value.parse().ok())
+    }
+
+    pub fn take_u32(&mut self, name: &str) -> Result<Option<u32>, MarkupError> {
+        self.take_parsed(name, "a whole number", |value| value.parse().ok())
+    }
+
+    pub fn take_i32(&mut self, name: &str) -> Result<Option<i32>, MarkupError> {
+        self.take_parsed(name, "a whole number", |value| value.parse().ok())
+    }
+
+    pub fn take_bool(&mut self, name: &str) -> Result<Option<bool>, MarkupError> {
+        self.take_parsed(name, "true or false", |value| value.parse().ok())
+    }
+
+    pub fn take_pair(&mut self, name: &str) -> Result<Option<[f32; 2]>, MarkupError> {
+        self.take_parsed(name, "two numbers", parse_pair)
+    }
+
+    pub fn take_size(&mut self, name: &str) -> Result<Option<[u32; 2]>, MarkupError> {
+        self.take_parsed(name, "two whole numbers", |value| {
+            let numbers = parse_numbers::<u32>(value)?;
+            match numbers.len() {
+                2 => Some([numbers[0], numbers[1]]),
+                _ => None
+            }
+        })
+    }
+
+    pub fn take_color(&mut self, name: &str) -> Result<Option<Vector4<f32>>, MarkupError> {
+        self.take_parsed(name, "#rrggbb, #rrggbbaa or three to four numbers from 0 to 1", parse_color)
+    }
+
+    pub fn take_layout(&mut self, name: &str) -> Result<Option<Layout>, MarkupError> {
+        self.take_parsed(name, "match_parent [before after], wrap_content, absolute or a percentage", parse_layout)
+    }
+
+    pub fn take_insets(&mut self, name: &str) -> Result<Option<Insets>, MarkupError> {
+        self.take_parsed(name, "one, two (horizontal vertical) or four (left right top bottom) numbers", parse_insets)
+    }
+
+    ///Attributes nobody took yet.
+    pub fn remaining(&self) -> &Vec<(String, String)> {
+        &self.values
+    }
+
+    ///Fails on the first attribute or text nobody took.
+    pub fn finish(&self) -> Result<(), MarkupError> {
+        if let Some((name, _)) = self.values.first() {
+            return Err(MarkupError::UnknownAttribute(self.line, self.tag.clone(), name.clone()));
+        }
+        match &self.text {
+            None => Ok(()),
+            Some(_) => Err(MarkupError::Syntax(self.line, format!("'<{}>' takes no text", self.tag)))
+        }
+    }
+}
+
+///Numbers separated by whitespace or commas.
+pub fn parse_numbers<T: std::str::FromStr>(value: &str) -> Option<Vec<T>> {
+    value.split(|character: char| character.is_whitespace() || character == ',')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+pub fn parse_pair(value: &str) -> Option<[f32; 2]> {
+    let numbers = parse_numbers::<f32>(value)?;
+    match numbers.len() {
+        2 => Some([numbers[0], numbers[1]]),
+        _ => None
+    }
+}
+
+///`#rrggbb`, `#rrggbbaa`, or three to four numbers from 0 to 1. Alpha defaults to 1.
+pub fn parse_color(value: &str) -> Option<Vector4<f32>> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+            return None;
+        }
+        let mut color = [1f32; 4];
+        for (index, channel) in color.iter_mut().enumerate().take(hex.len() / 2) {
+            *channel = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).ok()? as f32 / 255f32;
+        }
+        return Some(color);
+    }
+    let numbers = parse_numbers::<f32>(value)?;
+    match numbers.len() {
+        3 => Some([numbers[0], numbers[1], numbers[2], 1f32]),
+        4 => Some([numbers[0], numbers[1], numbers[2], numbers[3]]),
+        _ => None
+    }
+}
+
+///`match_parent` with optional margins before and after, `wrap_content`, `absolute` or a percentage like `50%`.
+pub fn parse_layout(value: &str) -> Option<Layout> {
+    if let Some(percent) = value.strip_suffix('%') {
+        return percent.trim().parse::<f32>().ok().map(Layout::Percent);
+    }
+    let mut parts = value.split_whitespace();
+    let layout = match parts.next()? {
+        "wrap_content" => Layout::WrapContent,
+        "absolute" => Layout::Absolute,
+        "match_parent" => {
+            let margins = parts.by_ref().map(|part| part.parse::<u32>().ok()).collect::<Option<Vec<u32>>>()?;
+            return match margins.len() {
+                0 => Some(Layout::MatchParent(0, 0)),
+                2 => Some(Layout::MatchParent(margins[0], margins[1])),
+                _ => None
+            };
+        }
+        _ => return None
+    };
+    match parts.next() {
+        None => Some(layout),
+        Some(_) => None
+    }
+}
+
+///One number for all sides, two for horizontal and vertical, or four for left, right, top and bottom.
+pub fn parse_insets(value: &str) -> Option<Insets> {
+    let numbers = parse_numbers::<f32>(value)?;
+    match numbers.len() {
+        1 => Some(Insets::all(numbers[0])),
+        2 => Some(Insets::symmetric(numbers[0], numbers[1])),
+        4 => Some(Insets::new(numbers[0], numbers[1], numbers[2], numbers[3])),
+        _ => None
+    }
+}
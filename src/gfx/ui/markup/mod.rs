@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use crate::gfx::ui::{UiContext, UIElement};
+use crate::gfx::ui::custom_ui_property::CustomUIProperty;
+use crate::gfx::ui::markup::attributes::{Attributes, parse_color};
+use crate::gfx::ui::markup::parser::Node;
+use crate::gfx::ui::markup::registry::ElementRegistry;
+use crate::gfx::ui::tree::UiTreeError;
+
+pub mod attributes;
+pub mod parser;
+pub mod registry;
+pub mod reload;
+
+///Describes a UI tree in an XML-like format, loaded by a `UiLoader`:
+///
+///```xml
+///<VBox id="menu" width="match_parent" height="wrap_content" spacing="8" padding="12">
+///    <Label font_size="24" color="#ffffff">Settings</Label>
+///    <Checkbox id="vsync" checked="true">V-Sync</Checkbox>
+///    <Slider id="volume" value="0.8"/>
+///    <Button id="apply" size="120 32" hover:color="0.3 0.5 0.9">Apply</Button>
+///</VBox>
+///```
+///
+///Tags match `UIElement::tag`, see `ElementRegistry` for the attributes of each. Every element also takes
+///`id`, a name to look it up by in the `LoadedUi` and for `#id` selectors of stylesheets, `class`, classes
+///separated by spaces, `position`, `z_index`, `corner_radius` and custom color properties, i.e. any attribute
+///ending in `color` like `color` or `hover:color`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkupError {
+    ///Line and description of malformed markup.
+    Syntax(usize, String),
+    ///Line and tag without a registered factory.
+    UnknownTag(usize, String),
+    ///Line, tag and name of an attribute the element does not take.
+    UnknownAttribute(usize, String, String),
+    ///Line, attribute name, value and a description of the accepted values.
+    InvalidValue(usize, String, String, &'static str),
+    ///Line of the second element with the name.
+    DuplicateName(usize, String),
+    Io(String),
+    Tree(UiTreeError),
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarkupError::Syntax(line, error) => f.write_fmt(format_args!("Line {}: {}", line, error)),
+            MarkupError::UnknownTag(line, tag) => f.write_fmt(format_args!("Line {}: Unknown element '<{}>'", line, tag)),
+            MarkupError::UnknownAttribute(line, tag, name) => f.write_fmt(format_args!("Line {}: '<{}>' has no attribute '{}'", line, tag, name)),
+            MarkupError::InvalidValue(line, name, value, expected) => f.write_fmt(format_args!("Line {}: Invalid {} '{}', expected {}", line, name, value, expected)),
+            MarkupError::DuplicateName(line, name) => f.write_fmt(format_args!("Line {}: The id '{}' is used twice", line, name)),
+            MarkupError::Io(error) => f.write_fmt(format_args!("IO error: {}", error)),
+            MarkupError::Tree(error) => f.write_fmt(format_args!("Tree error: {}", error)),
+        }
+    }
+}
+
+impl Error for MarkupError {}
+
+impl From<UiTreeError> for MarkupError {
+    fn from(error: UiTreeError) -> Self {
+        MarkupError::Tree(error)
+    }
+}
+
+///Elements registered from one document.
+#[derive(Debug, Clone, Default)]
+pub struct LoadedUi {
+    roots: Vec<usize>,
+    names: HashMap<String, usize>,
+}
+
+impl LoadedUi {
+    ///Ids of the top level elements of the document, in document order.
+    pub fn roots(&self) -> &Vec<usize> {
+        &self.roots
+    }
+
+    ///Id of the element with the `id` attribute `name`.
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.names.get(name).cloned()
+    }
+
+    pub fn names(&self) -> &HashMap<String, usize> {
+        &self.names
+    }
+}
+
+///Builds elements from markup and registers them with a `UiContext`.
+pub struct UiLoader {
+    registry: ElementRegistry,
+}
+
+impl UiLoader {
+    ///A loader knowing the elements of this crate.
+    pub fn new() -> UiLoader {
+        Self { registry: ElementRegistry::new() }
+    }
+
+    pub fn with_registry(registry: ElementRegistry) -> UiLoader {
+        Self { registry }
+    }
+
+    pub fn registry(&self) -> &ElementRegistry {
+        &self.registry
+    }
+
+    ///To add custom elements after construction.
+    pub fn registry_mut(&mut self) -> &mut ElementRegistry {
+        &mut self.registry
+    }
+
+    ///Builds the root elements of `source` with their children in the child buffers, without registering them.
+    ///Names map to the ids the elements will have once registered.
+    pub fn build(&self, source: &str) -> Result<(Vec<Box<dyn UIElement>>, HashMap<String, usize>), MarkupError> {
+        let nodes = parser::parse(source)?;
+        let mut names = HashMap::new();
+        let mut elements = vec![];
+        for node in &nodes {
+            elements.push(self.build_node(node, &mut names)?);
+        }
+        Ok((elements, names))
+    }
+
+    ///Builds `source` and registers its root elements as roots, or as the last children of `parent`. Nothing
+    ///is registered when any element fails.
+    pub fn load_str(&self, ui: &mut UiContext, parent: Option<usize>, source: &str) -> Result<LoadedUi, MarkupError> {
+        let (elements, names) = self.build(source)?;
+        let mut roots = vec![];
+        for element in elements {
+            match ui.attach(element, parent, None) {
+                Ok(id) => roots.push(id),
+                Err(error) => {
+                    for root in &roots {
+                        let _ = ui.remove(root);
+                    }
+                    return Err(error.into());
+                }
+            }
+        }
+        Ok(LoadedUi { roots, names })
+    }
+
+    pub fn load_file<P: AsRef<Path>>(&self, ui: &mut UiContext, parent: Option<usize>, path: P) -> Result<LoadedUi, MarkupError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|error| MarkupError::Io(format!("{}: {}", path.display(), error)))?;
+        self.load_str(ui, parent, &source)
+    }
+
+    fn build_node(&self, node: &Node, names: &mut HashMap<String, usize>) -> Result<Box<dyn UIElement>, MarkupError> {
+        let mut attributes = Attributes::new(node);
+        let mut element = self.registry.create(&mut attributes)?;
+        let name = attributes.take("id");
+        element.set_name(name.clone());
+        if let Some(classes) = attributes.take("class") {
+            for class in classes.split_whitespace() {
+                element.add_class(class);
+            }
+        }
+        if let Some(position) = attributes.take_size("position")? {
+            element.set_position(position);
+        }
+        if let Some(z_index) = attributes.take_i32("z_index")? {
+            element.set_z_index(z_index);
+        }
+        if let Some(corner_radius) = attributes.take_f32("corner_radius")? {
+            element.element_data_mut().set_corner_radius(corner_radius);
+        }
+        let colors: Vec<String> = attributes.remaining().iter()
+            .map(|(key, _)| key.clone())
+            .filter(|key| key.ends_with("color"))
+            .collect();
+        for key in colors {
+            let value = attributes.take_parsed(&key, "#rrggbb, #rrggbbaa or three to four numbers from 0 to 1", parse_color)?;
+            if let Some(color) = value {
+                element.set_custom_property(&key, CustomUIProperty::Vec4(color));
+            }
+        }
+        attributes.finish()?;
+
+        if let Some(name) = name {
+            if names.contains_key(&name) {
+                return Err(MarkupError::DuplicateName(node.line(), name));
+            }
+            names.insert(name, element.id());
+        }
+        for child in node.children() {
+            let child = self.build_node(child, names)?;
+            element.add_child(child);
+        }
+        Ok(element)
+    }
+}
+
+impl Default for UiLoader {
+    fn default() -> Self {
+        UiLoader::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gfx::ui::tests::with_recording_context;
+
+    const DOCUMENT: &str = "<VBox id=\"menu\" class=\"panel wide\" spacing=\"8\">\n    <Rectangle id=\"swatch\" size=\"20 10\" color=\"#ff0000\"/>\n    <Slider id=\"volume\" value=\"0.8\"/>\n    <Rectangle/>\n</VBox>\n<Rectangle id=\"footer\" position=\"5 6\" z_index=\"2\"/>";
+
+    #[test]
+    fn load_str_registers_the_document_and_names_its_elements() {
+        with_recording_context(|ui| {
+            let loaded = UiLoader::new().load_str(ui, None, DOCUMENT).unwrap();
+            let menu = loaded.get("menu").unwrap();
+            let footer = loaded.get("footer").unwrap();
+            assert_eq!(loaded.roots(), &vec![menu, footer]);
+            assert_eq!(ui.roots(), &vec![menu, footer]);
+            assert_eq!(loaded.names().len(), 4);
+            assert_eq!(loaded.get("missing"), None);
+
+            let children = ui.children_of(&menu).unwrap();
+            assert_eq!(children.len(), 3);
+            assert_eq!(children[..2], [loaded.get("swatch").unwrap(), loaded.get("volume").unwrap()]);
+            let menu = ui.get_element_by_id(&menu).unwrap();
+            assert_eq!(menu.tag(), "VBox");
+            assert_eq!(menu.name(), Some("menu"));
+            assert_eq!(menu.classes(), &vec!["panel".to_string(), "wide".to_string()]);
+            let footer = ui.get_element_by_id(&footer).unwrap();
+            assert_eq!(footer.position(), &[5, 6]);
+            assert_eq!(footer.z_index(), 2);
+        });
+    }
+
+    #[test]
+    fn load_str_registers_nothing_when_an_element_fails() {
+        with_recording_context(|ui| {
+            let loader = UiLoader::new();
+            let error = |ui: &mut UiContext, source: &str| loader.load_str(ui, None, source).unwrap_err();
+            assert_eq!(error(ui, "<VBox>\n  <Rectangle/>\n  <Knob/>\n</VBox>"), MarkupError::UnknownTag(3, "Knob".to_string()));
+            assert_eq!(error(ui, "<VBox>\n  <Slider value=\"1\" speed=\"2\"/>\n</VBox>"), MarkupError::UnknownAttribute(2, "Slider".to_string(), "speed".to_string()));
+            assert_eq!(error(ui, "<Rectangle>\ntext</Rectangle>"), MarkupError::Syntax(1, "'<Rectangle>' takes no text".to_string()));
+            assert_eq!(error(ui, "<Slider value=\"loud\"/>"), MarkupError::InvalidValue(1, "value".to_string(), "loud".to_string(), "a number"));
+            assert_eq!(error(ui, "<VBox id=\"a\">\n  <Rectangle id=\"b\"/>\n</VBox>\n<Rectangle id=\"b\"/>"), MarkupError::DuplicateName(4, "b".to_string()));
+            assert!(ui.element_ids().is_empty());
+        });
+    }
+}
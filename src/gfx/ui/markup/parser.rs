@@ -0,0 +1,282 @@
+use crate::gfx::ui::markup::MarkupError;
+
+///An element of a UI description, before it is turned into a `UIElement`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    tag: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+    ///Text between the tags, trimmed, with the text around child elements joined by single spaces.
+    text: String,
+    ///Line of the opening tag, starting at 1.
+    line: usize,
+}
+
+impl Node {
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    ///In the order they were written.
+    pub fn attributes(&self) -> &Vec<(String, String)> {
+        &self.attributes
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn children(&self) -> &Vec<Node> {
+        &self.children
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+///Parses the root elements of a document. The syntax is a subset of XML: elements with quoted attributes,
+///self-closing tags, text content, comments and the five predefined entities.
+pub fn parse(source: &str) -> Result<Vec<Node>, MarkupError> {
+    let mut parser = Parser { chars: source.chars().collect(), index: 0, line: 1 };
+    let mut roots = vec![];
+    loop {
+        parser.skip_whitespace();
+        if parser.is_done() {
+            return Ok(roots);
+        }
+        if parser.starts_with("<!--") {
+            parser.skip_comment()?;
+        } else if parser.starts_with("<?") {
+            parser.skip_past("?>")?;
+        } else if parser.starts_with("<") {
+            roots.push(parser.element()?);
+        } else {
+            return Err(parser.error("Text outside of an element"));
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn is_done(&self) -> bool {
+        self.index >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.index += 1;
+        if character == '\n' {
+            self.line += 1;
+        }
+        Some(character)
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        prefix.chars().enumerate().all(|(offset, character)| self.chars.get(self.index + offset) == Some(&character))
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), MarkupError> {
+        if !self.starts_with(expected) {
+            return Err(self.error(&format!("Expected '{}'", expected)));
+        }
+        for _ in expected.chars() {
+            self.next();
+        }
+        Ok(())
+    }
+
+    fn error(&self, message: &str) -> MarkupError {
+        MarkupError::Syntax(self.line, message.to_string())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map(|character| character.is_whitespace()).unwrap_or(false) {
+            self.next();
+        }
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<(), MarkupError> {
+        while !self.starts_with(end) {
+            if self.next().is_none() {
+                return Err(self.error(&format!("Missing '{}'", end)));
+            }
+        }
+        self.expect(end)
+    }
+
+    fn skip_comment(&mut self) -> Result<(), MarkupError> {
+        self.expect("<!--")?;
+        self.skip_past("-->")
+    }
+
+    fn name(&mut self) -> Result<String, MarkupError> {
+        let mut name = String::new();
+        while let Some(character) = self.peek() {
+            if !(character.is_alphanumeric() || matches!(character, '_' | '-' | ':' | '.')) {
+                break;
+            }
+            name.push(character);
+            self.next();
+        }
+        match name.is_empty() {
+            true => Err(self.error("Expected a name")),
+            false => Ok(name)
+        }
+    }
+
+    fn element(&mut self) -> Result<Node, MarkupError> {
+        let line = self.line;
+        self.expect("<")?;
+        let tag = self.name()?;
+        let mut attributes: Vec<(String, String)> = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("/>") {
+                self.expect("/>")?;
+                return Ok(Node { tag, attributes, children: vec![], text: String::new(), line });
+            }
+            if self.starts_with(">") {
+                self.expect(">")?;
+                break;
+            }
+            let name = self.name()?;
+            if attributes.iter().any(|(key, _)| *key == name) {
+                return Err(self.error(&format!("Attribute '{}' is set twice", name)));
+            }
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let value = self.quoted()?;
+            attributes.push((name, value));
+        }
+
+        let mut children = vec![];
+        let mut text: Vec<String> = vec![];
+        let mut current = String::new();
+        loop {
+            if self.starts_with("</") {
+                self.expect("</")?;
+                let closing = self.name()?;
+                if closing != tag {
+                    return Err(self.error(&format!("Expected '</{}>', found '</{}>'", tag, closing)));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                break;
+            }
+            if self.starts_with("<!--") {
+                self.skip_comment()?;
+            } else if self.starts_with("<") {
+                text.push(std::mem::take(&mut current));
+                children.push(self.element()?);
+            } else {
+                match self.peek() {
+                    None => return Err(MarkupError::Syntax(line, format!("'<{}>' is never closed", tag))),
+                    Some('&') => current.push(self.entity()?),
+                    Some(_) => current.push(self.next().unwrap()),
+                }
+            }
+        }
+        text.push(current);
+        let text = text.iter().map(|part| part.trim()).filter(|part| !part.is_empty()).collect::<Vec<&str>>().join(" ");
+        Ok(Node { tag, attributes, children, text, line })
+    }
+
+    fn quoted(&mut self) -> Result<String, MarkupError> {
+        let quote = match self.peek() {
+            Some(quote) if quote == '"' || quote == '\'' => quote,
+            _ => return Err(self.error("Expected a quoted value"))
+        };
+        self.next();
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("Unterminated value")),
+                Some(character) if character == quote => {
+                    self.next();
+                    return Ok(value);
+                }
+                Some('&') => value.push(self.entity()?),
+                Some(_) => value.push(self.next().unwrap()),
+            }
+        }
+    }
+
+    fn entity(&mut self) -> Result<char, MarkupError> {
+        for (entity, character) in [("&lt;", '<'), ("&gt;", '>'), ("&amp;", '&'), ("&quot;", '"'), ("&apos;", '\'')] {
+            if self.starts_with(entity) {
+                self.expect(entity)?;
+                return Ok(character);
+            }
+        }
+        Err(self.error("Unknown entity, write '&amp;' for '&'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_elements_with_attributes_and_text() {
+        let nodes = parse("<?xml version=\"1.0\"?>\n<VBox id='menu' spacing=\"8\">\n    Title\n    <Label>Hi</Label>\n    more text\n    <Slider/>\n</VBox>\n<Rectangle />").unwrap();
+        assert_eq!(nodes.len(), 2);
+        let menu = &nodes[0];
+        assert_eq!(menu.tag(), "VBox");
+        assert_eq!(menu.attributes(), &vec![("id".to_string(), "menu".to_string()), ("spacing".to_string(), "8".to_string())]);
+        assert_eq!(menu.attribute("spacing"), Some("8"));
+        assert_eq!(menu.attribute("padding"), None);
+        assert_eq!(menu.text(), "Title more text");
+        assert_eq!(menu.line(), 2);
+        assert_eq!(menu.children().iter().map(|child| (child.tag(), child.text(), child.line())).collect::<Vec<_>>(),
+                   vec![("Label", "Hi", 4), ("Slider", "", 6)]);
+        assert!(menu.children()[1].children().is_empty());
+        assert_eq!(nodes[1].tag(), "Rectangle");
+        assert_eq!(nodes[1].line(), 8);
+    }
+
+    #[test]
+    fn replaces_entities_in_text_and_values() {
+        let nodes = parse("<Label text=\"&quot;a&apos; &amp; b\">&lt;1&gt; &amp; 2</Label>").unwrap();
+        assert_eq!(nodes[0].attribute("text"), Some("\"a' & b"));
+        assert_eq!(nodes[0].text(), "<1> & 2");
+        assert_eq!(parse("<Label>a & b</Label>").unwrap_err(), MarkupError::Syntax(1, "Unknown entity, write '&amp;' for '&'".to_string()));
+    }
+
+    #[test]
+    fn skips_comments() {
+        let nodes = parse("<!-- <Label/> -->\n<VBox><!-- a\ncomment --><Label>a<!-- b -->c</Label></VBox><!---->").unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].children().len(), 1);
+        assert_eq!(nodes[0].children()[0].text(), "ac");
+        assert_eq!(nodes[0].children()[0].line(), 3);
+        assert_eq!(parse("<VBox/>\n<!-- open").unwrap_err(), MarkupError::Syntax(2, "Missing '-->'".to_string()));
+    }
+
+    #[test]
+    fn reports_malformed_markup_with_its_line() {
+        let error = |source: &str| parse(source).unwrap_err();
+        assert_eq!(error("<VBox>\n<Label>\n</VBox>"), MarkupError::Syntax(3, "Expected '</Label>', found '</VBox>'".to_string()));
+        assert_eq!(error("<VBox>\n\n<Label/>"), MarkupError::Syntax(1, "'<VBox>' is never closed".to_string()));
+        assert_eq!(error("<Slider\n value=\"1\"\n value=\"2\"/>"), MarkupError::Syntax(3, "Attribute 'value' is set twice".to_string()));
+        assert_eq!(error("<VBox/>\ntext"), MarkupError::Syntax(2, "Text outside of an element".to_string()));
+        assert_eq!(error("<Slider value=1/>"), MarkupError::Syntax(1, "Expected a quoted value".to_string()));
+        assert_eq!(error("<Slider value=\"1\n/>"), MarkupError::Syntax(2, "Unterminated value".to_string()));
+        assert_eq!(error("<Slider value/>"), MarkupError::Syntax(1, "Expected '='".to_string()));
+        assert_eq!(error("< Slider/>"), MarkupError::Syntax(1, "Expected a name".to_string()));
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use crate::gfx::text::TextAlignment;
+use crate::gfx::ui::container::Container;
+use crate::gfx::ui::container::flex::{Align, Flex, FlexDirection, FlexWrap, Justify};
+use crate::gfx::ui::container::grid::{Grid, Track};
+use crate::gfx::ui::container::h_box::HBox;
+use crate::gfx::ui::container::scroll_view::ScrollView;
+use crate::gfx::ui::container::v_box::VBox;
+use crate::gfx::ui::ellipse::Ellipse;
+use crate::gfx::ui::interactable::button::Button;
+use crate::gfx::ui::interactable::checkbox::Checkbox;
+use crate::gfx::ui::interactable::radio_group::RadioGroup;
+use crate::gfx::ui::interactable::text_input::TextInput;
+use crate::gfx::ui::interactable::toggle::Toggle;
+use crate::gfx::ui::label::Label;
+use crate::gfx::ui::markup::attributes::Attributes;
+use crate::gfx::ui::markup::MarkupError;
+use crate::gfx::ui::square::Square;
+use crate::{Rectangle, Slider, UIElement};
+
+///Font size of text elements without a `font_size` attribute.
+pub const DEFAULT_FONT_SIZE: f32 = 16f32;
+
+///Builds an element from the attributes of its node, taking the ones it understands. Children and the attributes
+///every element has, see `UiLoader`, are handled by the loader.
+pub type ElementFactory = dyn Fn(&mut Attributes) -> Result<Box<dyn UIElement>, MarkupError>;
+
+///Maps tags to element factories. Tags match `UIElement::tag` of the built elements.
+pub struct ElementRegistry {
+    factories: HashMap<String, Box<ElementFactory>>,
+}
+
+impl ElementRegistry {
+    ///A registry knowing the elements of this crate.
+    pub fn new() -> ElementRegistry {
+        let mut registry = Self::empty();
+        registry.register("VBox", build_v_box);
+        registry.register("HBox", build_h_box);
+        registry.register("Flex", build_flex);
+        registry.register("Grid", build_grid);
+        registry.register("ScrollView", build_scroll_view);
+        registry.register("Rectangle", build_rectangle);
+        registry.register("Square", build_square);
+        registry.register("Ellipse", build_ellipse);
+        registry.register("Label", build_label);
+        registry.register("Slider", build_slider);
+        registry.register("Button", build_button);
+        registry.register("Checkbox", build_checkbox);
+        registry.register("Toggle", build_toggle);
+        registry.register("RadioGroup", build_radio_group);
+        registry.register("TextInput", build_text_input);
+        registry
+    }
+
+    pub fn empty() -> ElementRegistry {
+        Self { factories: HashMap::new() }
+    }
+
+    pub fn with_element<F>(mut self, tag: &str, factory: F) -> ElementRegistry
+        where F: Fn(&mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> + 'static {
+        self.register(tag, factory);
+        self
+    }
+
+    ///Adds or replaces the factory for `tag`.
+    pub fn register<F>(&mut self, tag: &str, factory: F)
+        where F: Fn(&mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> + 'static {
+        self.factories.insert(tag.to_string(), Box::new(factory));
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.factories.contains_key(tag)
+    }
+
+    pub fn tags(&self) -> Vec<&str> {
+        self.factories.keys().map(|tag| tag.as_str()).collect()
+    }
+
+    pub fn create(&self, attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+        match self.factories.get(attributes.tag()) {
+            None => Err(MarkupError::UnknownTag(attributes.line(), attributes.tag().to_string())),
+            Some(factory) => factory(attributes)
+        }
+    }
+}
+
+impl Default for ElementRegistry {
+    fn default() -> Self {
+        ElementRegistry::new()
+    }
+}
+
+///Takes the attributes every container has: `width`, `height`, `child_width`, `child_height`, `spacing`,
+///`padding` and the minimum and maximum size.
+fn take_container<C: Container>(container: &mut C, attributes: &mut Attributes) -> Result<(), MarkupError> {
+    if let Some(width) = attributes.take_layout("width")? {
+        *container.horizontal_mut() = width;
+    }
+    if let Some(height) = attributes.take_layout("height")? {
+        *container.vertical_mut() = height;
+    }
+    if let Some(width) = attributes.take_layout("child_width")? {
+        *container.child_horizontal_mut() = width;
+    }
+    if let Some(height) = attributes.take_layout("child_height")? {
+        *container.child_vertical_mut() = height;
+    }
+    if let Some(spacing) = attributes.take_u32("spacing")? {
+        *container.spacing_mut() = spacing;
+    }
+    if let Some(padding) = attributes.take_insets("padding")? {
+        *container.padding_mut() = padding;
+    }
+    if let Some(width) = attributes.take_u32("min_width")? {
+        *container.min_width_mut() = width;
+    }
+    if let Some(width) = attributes.take_u32("max_width")? {
+        *container.max_width_mut() = Some(width);
+    }
+    if let Some(height) = attributes.take_u32("min_height")? {
+        *container.min_height_mut() = height;
+    }
+    if let Some(height) = attributes.take_u32("max_height")? {
+        *container.max_height_mut() = Some(height);
+    }
+    Ok(())
+}
+
+fn build_v_box(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let mut v_box = VBox::new();
+    take_container(&mut v_box, attributes)?;
+    Ok(Box::new(v_box))
+}
+
+fn build_h_box(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let mut h_box = HBox::new();
+    take_container(&mut h_box, attributes)?;
+    Ok(Box::new(h_box))
+}
+
+///`direction` is `row` or `column`, `justify` one of `start`, `end`, `center`, `space_between`, `space_around`
+///and `space_evenly`, and `align` one of `start`, `end`, `center` and `stretch`.
+fn build_flex(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let direction = attributes.take_parsed("direction", "row or column", |value| match value {
+        "row" => Some(FlexDirection::Row),
+        "column" => Some(FlexDirection::Column),
+        _ => None
+    })?;
+    let mut flex = Flex::new(direction.unwrap_or(FlexDirection::Row));
+    if let Some(size) = attributes.take_size("size")? {
+        flex = flex.with_size(size);
+    }
+    take_container(&mut flex, attributes)?;
+    if let Some(wrap) = attributes.take_bool("wrap")? {
+        flex = flex.with_wrap(if wrap { FlexWrap::Wrap } else { FlexWrap::NoWrap });
+    }
+    let justify = attributes.take_parsed("justify", "start, end, center, space_between, space_around or space_evenly", |value| match value {
+        "start" => Some(Justify::Start),
+        "end" => Some(Justify::End),
+        "center" => Some(Justify::Center),
+        "space_between" => Some(Justify::SpaceBetween),
+        "space_around" => Some(Justify::SpaceAround),
+        "space_evenly" => Some(Justify::SpaceEvenly),
+        _ => None
+    })?;
+    if let Some(justify) = justify {
+        flex = flex.with_justify(justify);
+    }
+    if let Some(align) = attributes.take_parsed("align", "start, end, center or stretch", parse_align)? {
+        flex = flex.with_align(align);
+    }
+    Ok(Box::new(flex))
+}
+
+///`columns` and `rows` list tracks separated by spaces: pixels like `120`, fractions like `1fr` or `auto`.
+fn build_grid(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    const TRACKS: &str = "tracks like 120, 1fr or auto separated by spaces";
+    let columns = attributes.take_parsed("columns", TRACKS, parse_tracks)?.unwrap_or_default();
+    let rows = attributes.take_parsed("rows", TRACKS, parse_tracks)?.unwrap_or_default();
+    let mut grid = Grid::new(columns, rows);
+    if let Some(size) = attributes.take_size("size")? {
+        grid = grid.with_size(size);
+    }
+    take_container(&mut grid, attributes)?;
+    Ok(Box::new(grid))
+}
+
+fn parse_align(value: &str) -> Option<Align> {
+    match value {
+        "start" => Some(Align::Start),
+        "end" => Some(Align::End),
+        "center" => Some(Align::Center),
+        "stretch" => Some(Align::Stretch),
+        _ => None
+    }
+}
+
+fn parse_tracks(value: &str) -> Option<Vec<Track>> {
+    value.split_whitespace().map(|track| match track {
+        "auto" => Some(Track::Auto),
+        track => match track.strip_suffix("fr") {
+            Some(fraction) => fraction.parse().ok().map(Track::Fraction),
+            None => track.parse().ok().map(Track::Fixed)
+        }
+    }).collect()
+}
+
+///`axes` is `horizontal`, `vertical` or `both`.
+fn build_scroll_view(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let mut scroll_view = ScrollView::new(attributes.take_size("size")?.unwrap_or([200, 200]));
+    if let Some(width) = attributes.take_layout("width")? {
+        scroll_view = scroll_view.with_horizontal(width);
+    }
+    if let Some(height) = attributes.take_layout("height")? {
+        scroll_view = scroll_view.with_vertical(height);
+    }
+    let axes = attributes.take_parsed("axes", "horizontal, vertical or both", |value| match value {
+        "horizontal" => Some((true, false)),
+        "vertical" => Some((false, true)),
+        "both" => Some((true, true)),
+        _ => None
+    })?;
+    if let Some((horizontal, vertical)) = axes {
+        scroll_view = scroll_view.with_axes(horizontal, vertical);
+    }
+    if let Some(scrollbars) = attributes.take_bool("scrollbars")? {
+        scroll_view = scroll_view.with_scrollbars(scrollbars);
+    }
+    if let Some(speed) = attributes.take_f32("scroll_speed")? {
+        scroll_view = scroll_view.with_scroll_speed(speed);
+    }
+    if let Some(padding) = attributes.take_insets("padding")? {
+        scroll_view = scroll_view.with_padding(padding);
+    }
+    Ok(Box::new(scroll_view))
+}
+
+fn build_rectangle(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let size = attributes.take_pair("size")?.unwrap_or([100f32, 30f32]);
+    Ok(Box::new(Rectangle::new([0; 2], size)))
+}
+
+fn build_square(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let size = attributes.take_f32("size")?.unwrap_or(100f32);
+    Ok(Box::new(Square::new([0; 2], size)))
+}
+
+fn build_ellipse(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let size = attributes.take_pair("size")?.unwrap_or([100f32; 2]);
+    Ok(Box::new(Ellipse::new([0; 2], size)))
+}
+
+///The text is the `text` attribute or the content of the node.
+fn build_label(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let text = attributes.take_text_or_content().unwrap_or_default();
+    let mut label = Label::new(text, attributes.take_f32("font_size")?.unwrap_or(DEFAULT_FONT_SIZE));
+    if let Some(font) = attributes.take("font") {
+        label = label.with_font(font);
+    }
+    if let Some(max_width) = attributes.take_f32("max_width")? {
+        label = label.with_max_width(max_width);
+    }
+    let alignment = attributes.take_parsed("alignment", "left, center or right", |value| match value {
+        "left" => Some(TextAlignment::Left),
+        "center" => Some(TextAlignment::Center),
+        "right" => Some(TextAlignment::Right),
+        _ => None
+    })?;
+    if let Some(alignment) = alignment {
+        label = label.with_alignment(alignment);
+    }
+    if attributes.take_bool("sdf")?.unwrap_or(false) {
+        label = label.with_sdf();
+    }
+    Ok(Box::new(label))
+}
+
+fn build_slider(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let mut slider = Slider::default();
+    if let Some(value) = attributes.take_f32("value")? {
+        slider = slider.with_value(value);
+    }
+    //Rounds the track too, unlike the corner radius every element takes.
+    if let Some(corner_radius) = attributes.take_f32("corner_radius")? {
+        slider = slider.with_corner_radius(corner_radius);
+    }
+    Ok(Box::new(slider))
+}
+
+fn build_button(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let mut button = Button::new(attributes.take_size("size")?.unwrap_or([120, 32]));
+    let font_size = attributes.take_f32("font_size")?.unwrap_or(DEFAULT_FONT_SIZE);
+    if let Some(text) = attributes.take_text_or_content() {
+        button = button.with_text(text, font_size);
+    }
+    if let Some(disabled) = attributes.take_bool("disabled")? {
+        button = button.with_disabled(disabled);
+    }
+    Ok(Box::new(button))
+}
+
+fn build_checkbox(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let mut checkbox = Checkbox::new(attributes.take_u32("size")?.unwrap_or(16));
+    let font_size = attributes.take_f32("font_size")?.unwrap_or(DEFAULT_FONT_SIZE);
+    if let Some(text) = attributes.take_text_or_content() {
+        checkbox = checkbox.with_text(text, font_size);
+    }
+    if let Some(checked) = attributes.take_bool("checked")? {
+        checkbox = checkbox.with_checked(checked);
+    }
+    Ok(Box::new(checkbox))
+}
+
+fn build_toggle(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let mut toggle = Toggle::new(attributes.take_size("size")?.unwrap_or([40, 20]));
+    let font_size = attributes.take_f32("font_size")?.unwrap_or(DEFAULT_FONT_SIZE);
+    if let Some(text) = attributes.take_text_or_content() {
+        toggle = toggle.with_text(text, font_size);
+    }
+    if let Some(on) = attributes.take_bool("on")? {
+        toggle = toggle.with_on(on);
+    }
+    Ok(Box::new(toggle))
+}
+
+///`options` are separated by semicolons, `selected` is the index of an option.
+fn build_radio_group(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let options: Vec<String> = attributes.take("options")
+        .map(|options| options.split(';').map(|option| option.trim().to_string()).collect())
+        .unwrap_or_default();
+    let size = attributes.take_u32("size")?.unwrap_or(16);
+    let font_size = attributes.take_f32("font_size")?.unwrap_or(DEFAULT_FONT_SIZE);
+    let mut group = RadioGroup::new(options, size, font_size);
+    if let Some(selected) = attributes.take_u32("selected")? {
+        group = group.with_selected(Some(selected as usize));
+    }
+    if let Some(spacing) = attributes.take_u32("spacing")? {
+        group = group.with_spacing(spacing);
+    }
+    Ok(Box::new(group))
+}
+
+fn build_text_input(attributes: &mut Attributes) -> Result<Box<dyn UIElement>, MarkupError> {
+    let size = attributes.take_size("size")?.unwrap_or([200, 28]);
+    let mut input = TextInput::new(size, attributes.take_f32("font_size")?.unwrap_or(DEFAULT_FONT_SIZE));
+    if let Some(multiline) = attributes.take_bool("multiline")? {
+        input = input.with_multiline(multiline);
+    }
+    if let Some(font) = attributes.take("font") {
+        input = input.with_font(font);
+    }
+    if let Some(text) = attributes.take_text_or_content() {
+        input = input.with_text(text);
+    }
+    if let Some(color) = attributes.take_color("text_color")? {
+        input = input.with_text_color(color);
+    }
+    if let Some(color) = attributes.take_color("selection_color")? {
+        input = input.with_selection_color(color);
+    }
+    Ok(Box::new(input))
+}
@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::gfx::ui::{UiContext, UIElement};
+use crate::gfx::ui::markup::{LoadedUi, MarkupError, UiLoader};
+use crate::utils::file_watcher::FileWatcher;
+
+///Input held by an element that should survive rebuilding it, see `UIElement::save_state`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementState {
+    ///Value of a slider.
+    Value(f32),
+    ///Whether a checkbox is checked or a toggle on.
+    Checked(bool),
+    ///Option chosen in a radio group.
+    Selected(Option<usize>),
+    Text(String),
+    ///Scroll offset of a scroll view.
+    Scroll([f32; 2]),
+}
+
+///A markup file whose elements are rebuilt whenever it changes on disk. State of named elements, see
+///`UIElement::save_state`, and the focus are carried over to the element with the same name and tag.
+///
+///Rebuilt elements get new ids. Look them up by name again and register callbacks again after `poll`
+///returned `true`.
+pub struct LiveUi {
+    loader: UiLoader,
+    path: PathBuf,
+    parent: Option<usize>,
+    loaded: LoadedUi,
+    files: FileWatcher,
+}
+
+impl LiveUi {
+    ///Loads the file like `UiLoader::load_file` and starts watching it.
+    pub fn load<P: AsRef<Path>>(ui: &mut UiContext, loader: UiLoader, parent: Option<usize>, path: P) -> Result<LiveUi, MarkupError> {
+        let path = path.as_ref().to_path_buf();
+        let files = FileWatcher::new().with_file(&path);
+        let loaded = loader.load_file(ui, parent, &path)?;
+        Ok(Self { loader, path, parent, loaded, files })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn loaded(&self) -> &LoadedUi {
+        &self.loaded
+    }
+
+    ///Id of the element with the `id` attribute `name` in the current build.
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.loaded.get(name)
+    }
+
+    pub fn loader_mut(&mut self) -> &mut UiLoader {
+        &mut self.loader
+    }
+
+    ///Rebuilds the elements if the file changed since the last poll. Returns whether it did. On an error the
+    ///previous elements stay in place until the file changes again.
+    pub fn poll(&mut self, ui: &mut UiContext) -> Result<bool, MarkupError> {
+        if self.files.changed().is_empty() {
+            return Ok(false);
+        }
+        self.reload(ui).map(|_| true)
+    }
+
+    ///Rebuilds the elements from the file and puts them where the previous roots were.
+    pub fn reload(&mut self, ui: &mut UiContext) -> Result<(), MarkupError> {
+        let source = fs::read_to_string(&self.path)
+            .map_err(|error| MarkupError::Io(format!("{}: {}", self.path.display(), error)))?;
+        let (mut elements, names) = self.loader.build(&source)?;
+
+        for (name, id) in &names {
+            let old = match self.loaded.get(name).and_then(|old| ui.get_element_by_id(&old)) {
+                None => continue,
+                Some(old) => old
+            };
+            if let Some(element) = find_pending(&mut elements, id) {
+                if element.tag() == old.tag() {
+                    if let Some(state) = old.save_state() {
+                        element.restore_state(state);
+                    }
+                }
+            }
+        }
+        let focused = ui.focused().and_then(|focused| {
+            self.loaded.names().iter().find(|(_, id)| **id == focused).map(|(name, _)| name.clone())
+        });
+
+        //Fails before anything is removed if the parent is gone.
+        let siblings = match self.parent {
+            None => ui.roots().clone(),
+            Some(parent) => ui.children_of(&parent)?
+        };
+        let old_roots: Vec<usize> = self.loaded.roots().iter().filter(|root| siblings.contains(root)).cloned().collect();
+        let mut index = old_roots.iter()
+            .filter_map(|root| siblings.iter().position(|sibling| sibling == root))
+            .min()
+            .unwrap_or(siblings.len());
+        for root in &old_roots {
+            ui.remove(root)?;
+        }
+
+        let mut roots = vec![];
+        for element in elements {
+            roots.push(ui.attach(element, self.parent, Some(index))?);
+            index += 1;
+        }
+        self.loaded = LoadedUi { roots, names };
+        if let Some(id) = focused.and_then(|name| self.loaded.get(&name)) {
+            let _ = ui.focus(&id);
+        }
+        Ok(())
+    }
+}
+
+///Finds an element that is not registered yet in the child buffers below `elements`.
+fn find_pending<'a>(elements: &'a mut Vec<Box<dyn UIElement>>, id: &usize) -> Option<&'a mut Box<dyn UIElement>> {
+    for element in elements.iter_mut() {
+        if element.id() == *id {
+            return Some(element);
+        }
+        if let Some(found) = find_pending(element.child_buffer(), id) {
+            return Some(found);
+        }
+    }
+    None
+}
@@ -1,16 +1,34 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use downcast_rs::{Downcast, impl_downcast};
 use glfw::{Action, Key, MouseButton};
+use imgui::{ClipboardBackend, ImString};
 use vecmath::{Vector4};
-use crate::{Camera, Constructor, cull_face, draw_arrays, DrawMode, enable, Face, FragmentShader, GLConsts, Input, MatrixWrapper, Program, VertexArrayObject, VertexArrayObjectType, VertexShader};
-use crate::gfx::ui::callbacks::OnDrag;
+use crate::clipboard::ClipboardSupport;
+use crate::{BufferDataType, BufferType, Camera, Constructor, cull_face, draw_arrays, DrawMode, DrawType, enable, Face, FragmentShader, GLConsts, Input, LocalAttribPointer, MatrixWrapper, Program, VertexArrayObject, VertexArrayObjectType, VertexBufferObject, VertexShader};
+use crate::gfx::bindings::graphics::{disable, draw_elements, scissor};
+use crate::gfx::shader::watcher::{ProgramSources, ShaderSource};
+use crate::gfx::ui::callbacks::{OnDrag, OnFocus, OnMouseEvent};
+use crate::gfx::ui::event::{MouseEvent, MouseEventKind, PointerState, held_modifiers};
 use crate::gfx::ui::custom_ui_property::CustomUIProperty;
 use crate::gfx::ui::fill_method::FillMethod;
+use crate::gfx::ui::focus::FocusRing;
+use crate::gfx::ui::layout::engine::{Frame, LayoutStyle, LayoutTree, Size};
+use crate::gfx::ui::markup::reload::ElementState;
+use crate::gfx::ui::rectangle::BUFFER;
+use crate::gfx::ui::shape::Shape;
+use crate::gfx::ui::style::{Style, Styled};
+use crate::gfx::ui::style::stylesheet::Stylesheet;
+use crate::gfx::ui::tree::UiTreeError;
+use crate::gfx::text::renderer::TextRenderer;
 use crate::math::linear_algebra::IDENTITY_MAT4;
 
 pub mod rectangle;
 pub mod square;
+pub mod ellipse;
+pub mod label;
 
 pub mod container;
 pub mod layout;
@@ -20,10 +38,17 @@ pub mod interactable;
 pub mod callbacks;
 pub mod fill_method;
 pub mod custom_ui_property;
+pub mod event;
+pub mod focus;
+pub mod golden;
+pub mod markup;
+pub mod shape;
+pub mod style;
+pub mod tree;
 
 pub const DEFAULT_COLOR: Vector4<f32> = [0f32, 0f32, 0f32, 1f32];
-static mut COUNTER: usize = 0;
-static mut UI: Option<UI> = None;
+///Element ids are unique across every `UiContext`.
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 const DEFAULT_VERTEX_SHADER: &'static str = {
     r#"
@@ -47,6 +72,8 @@ void main() {
 "#
 };
 const DEFAULT_FRAGMENT_SHADER: &'static str = include_str!("../../../res/shaders/ui/frag.glsl");
+///Source file of the default fragment shader, relative to the working directory.
+const DEFAULT_FRAGMENT_SHADER_PATH: &'static str = "res/shaders/ui/frag.glsl";
 /*const DEFAULT_FRAGMENT_SHADER: &'static str = {
     r#"
 #version 330 core
@@ -86,198 +113,509 @@ void main()
 "#
 };*/
 
-pub struct UI {
+///Owns a tree of UI elements and the program they are drawn with.
+///Independent contexts can exist side by side, e.g. one per window or render target.
+pub struct UiContext {
     elements: HashMap<usize, Box<dyn UIElement>>,
+    ///Root elements in tree order.
+    roots: Vec<usize>,
     default_program: Program,
+    text: TextRenderer,
+    layout: LayoutTree,
+    ///Size of every element in layout as it set it, and as the last layout left it, see `sync_layout`.
+    layout_sizes: HashMap<usize, ([u32; 2], [u32; 2])>,
+    ///`None` where the platform clipboard is not available.
+    clipboard: Option<ClipboardSupport>,
+    ///Clip rectangles of the elements currently drawing, see `push_clip`.
+    clip_stack: RefCell<Vec<[i32; 4]>>,
+    ///The element receiving keys, see `focus`.
+    focused: Option<usize>,
+    focus_ring: FocusRing,
+    pointer: PointerState,
+    ///Elements taken out by `with_element`, innermost last, with the parent they had.
+    taken: Vec<(usize, Option<usize>)>,
+    ///Taken out elements that were removed, or lost an ancestor, meanwhile. `with_element` drops them.
+    dropped: Vec<usize>,
+    stylesheet: Option<Stylesheet>,
+    ///Computed style of every element while there is a stylesheet, see `restyle`.
+    styles: HashMap<usize, Style>,
+    ///Set when an element was added, moved or removed, the focus moved or input may have changed a pseudo-state,
+    ///see `restyle_changed`.
+    styles_dirty: bool,
+    screen_size: [i32; 2],
 }
 
-impl UI {
-    pub unsafe fn init() {
-        UI = Some(UI {
+impl UiContext {
+    ///Compiles the default UI program. Requires a current GL context.
+    pub fn new() -> UiContext {
+        Self::with_program(Program::new(
+            FragmentShader::new(DEFAULT_FRAGMENT_SHADER),
+            VertexShader::new(DEFAULT_VERTEX_SHADER),
+        ).unwrap())
+    }
+
+    ///Uses `program` to draw every element that does not bring its own.
+    pub fn with_program(program: Program) -> UiContext {
+        Self {
             elements: Default::default(),
-            default_program: Program::new(
-                FragmentShader::new(DEFAULT_FRAGMENT_SHADER),
-                VertexShader::new(DEFAULT_VERTEX_SHADER),
-            ).unwrap(),
-        })
+            roots: vec![],
+            default_program: program,
+            text: TextRenderer::new(),
+            layout: LayoutTree::new(),
+            layout_sizes: HashMap::new(),
+            clipboard: crate::clipboard::init(),
+            clip_stack: RefCell::new(vec![]),
+            focused: None,
+            focus_ring: FocusRing::new(),
+            pointer: Default::default(),
+            taken: vec![],
+            dropped: vec![],
+            stylesheet: None,
+            styles: HashMap::new(),
+            styles_dirty: false,
+            screen_size: [0; 2],
+        }
     }
-    pub fn update(delta: f32) {
-        unsafe {
-            match &mut UI {
-                None => {
-                    panic!("Initialize the UI first.")
-                }
-                Some(ui) => {
-                    for (_, elem) in &mut ui.elements {
-                        elem.update(delta);
-                    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.restyle_changed();
+        for id in self.draw_order() {
+            self.with_element(&id, |elem, ui| elem.update(ui, delta));
+        }
+        self.apply_layout();
+        self.place_focus_ring();
+    }
+
+    ///Lays out every tree of elements taking part in layout, see `UIElement::layout_style`. Its roots fill their
+    ///parent, or the screen. Styles are synced every call, but the engine only reruns for trees where one changed.
+    pub fn apply_layout(&mut self) {
+        let screen = [self.screen_size[0].max(0) as f32, self.screen_size[1].max(0) as f32];
+        let roots: Vec<usize> = self.draw_order().into_iter().filter(|id| {
+            match self.get_element_by_id(id) {
+                None => false,
+                Some(element) => element.layout_style().is_some()
+                    && element.get_parent(self).map(|parent| parent.layout_style().is_none()).unwrap_or(true)
+            }
+        }).collect();
+
+        let mut moved = vec![];
+        for root in roots {
+            self.sync_layout(&root, None);
+            //The engine runs downwards from the top of the screen.
+            let area = match self.get_element_by_id(&root).and_then(|root| root.get_parent(self)) {
+                None => Frame::new([0f32; 2], screen),
+                Some(parent) => {
+                    let data = parent.element_data();
+                    let size = [data.width as f32, data.height as f32];
+                    Frame::new([data.position[0] as f32, screen[1] - data.position[1] as f32 - size[1]], size)
                 }
+            };
+            if self.layout.layout(&root, area) {
+                self.write_layout(&root, screen[1], &mut moved);
             }
         }
+        for id in moved {
+            self.with_element(&id, |element, ui| element.layout_changed(ui));
+        }
     }
 
-    pub fn get_element_by_id(id: &usize) -> Option<&'static Box<dyn UIElement>> {
-        unsafe {
-            match &mut UI {
-                None => {
-                    panic!("Initialize the UI first.")
-                }
-                Some(ui) => {
-                    for (elem_id, elem) in &ui.elements {
-                        if elem_id == id {
-                            return Some(elem);
-                        }
-                    }
+    ///Hands the current styles of the element and, if it takes part in layout, its children to the engine.
+    ///Elements that do not take part keep their size.
+    ///
+    ///Fixed sizes are the ones the elements set themselves, not what a parent stretched them to, so growing
+    ///does not feed back into the next layout.
+    fn sync_layout(&mut self, id: &usize, parent: Option<usize>) {
+        let (mut style, children, current) = match self.elements.get(id) {
+            None => return,
+            Some(element) => {
+                let current = [element.element_data().width, element.element_data().height];
+                match element.layout_style() {
+                    Some(style) => (style, element.children().clone(), current),
+                    None => (LayoutStyle::fixed([current[0] as f32, current[1] as f32]), vec![], current)
                 }
             }
+        };
+        let natural = match self.layout_sizes.get(id) {
+            Some((natural, laid_out)) if *laid_out == current => *natural,
+            _ => current
+        };
+        self.layout_sizes.insert(*id, (natural, current));
+        for axis in 0..2 {
+            if style.size[axis] == Size::Fixed(current[axis] as f32) {
+                style.size[axis] = Size::Fixed(natural[axis] as f32);
+            }
+        }
+        let style = match parent.and_then(|parent| self.elements.get(&parent)) {
+            None => style,
+            Some(parent) => parent.child_layout_style(id, style)
+        };
+        self.layout.set_node(*id, style, children.clone());
+        for child in children {
+            self.sync_layout(&child, Some(*id));
         }
-        None
     }
 
-    pub fn get_element_by_id_mut(id: &usize) -> Option<&'static mut Box<dyn UIElement>> {
-        unsafe {
-            match &mut UI {
-                None => {
-                    panic!("Initialize the UI first.")
-                }
-                Some(ui) => {
-                    for (elem_id, elem) in &mut ui.elements {
-                        if elem_id == id {
-                            return Some(elem);
-                        }
+    ///Writes the computed bounds back to the elements and collects the ids of the ones that moved or resized.
+    fn write_layout(&mut self, id: &usize, screen_height: f32, moved: &mut Vec<usize>) {
+        if let (Some(frame), Some(element)) = (self.layout.frame(id), self.elements.get_mut(id)) {
+            let width = frame.size[0].max(0f32).round() as u32;
+            let height = frame.size[1].max(0f32).round() as u32;
+            let y = screen_height - frame.position[1] - height as f32;
+            let position = [frame.position[0].max(0f32).round() as u32, y.max(0f32).round() as u32];
+            let data = element.element_data();
+            if data.position != position || data.width != width || data.height != height {
+                moved.push(*id);
+            }
+            element.element_data_mut().width = width;
+            element.element_data_mut().height = height;
+            if let Some((_, laid_out)) = self.layout_sizes.get_mut(id) {
+                *laid_out = [width, height];
+            }
+            element.set_position(position);
+        }
+        for child in self.layout.children(id).cloned().unwrap_or_default() {
+            self.write_layout(&child, screen_height, moved);
+        }
+    }
+
+    ///Sizes and positions computed by the last layout, in engine coordinates.
+    pub fn layout(&self) -> &LayoutTree {
+        &self.layout
+    }
+
+    ///Ids of every registered element.
+    pub fn element_ids(&self) -> Vec<usize> {
+        self.elements.keys().cloned().collect()
+    }
+
+    pub fn contains(&self, id: &usize) -> bool {
+        self.elements.contains_key(id)
+    }
+
+    pub fn get_element_by_id(&self, id: &usize) -> Option<&Box<dyn UIElement>> {
+        self.elements.get(id)
+    }
+
+    pub fn get_element_by_id_mut(&mut self, id: &usize) -> Option<&mut Box<dyn UIElement>> {
+        self.elements.get_mut(id)
+    }
+
+    ///Takes the element out of the context while `f` runs, so `f` can use the rest of the context mutably.
+    ///While taken out, the element itself cannot be looked up. If `f` removes the element or one of its ancestors,
+    ///e.g. a button closing its own panel, the element and its descendants are dropped once `f` returns.
+    pub fn with_element<R, F>(&mut self, id: &usize, f: F) -> Option<R> where F: FnOnce(&mut Box<dyn UIElement>, &mut UiContext) -> R {
+        let mut element = self.elements.remove(id)?;
+        self.taken.push((*id, element.parent()));
+        let result = f(&mut element, self);
+        self.taken.pop();
+        match self.dropped.iter().position(|dropped| dropped == id) {
+            None => {
+                //Children removed while this element was taken out as well could not be unlinked from it.
+                let (elements, taken) = (&self.elements, &self.taken);
+                let mut unlinked = vec![];
+                element.element_data_mut().children.retain(|child| {
+                    let kept = elements.contains_key(child) || taken.iter().any(|(taken, _)| taken == child);
+                    if !kept {
+                        unlinked.push(*child);
                     }
+                    kept
+                });
+                self.elements.insert(*id, element);
+                for child in unlinked {
+                    self.notify_child_detach(Some(*id), &child);
                 }
             }
+            Some(index) => {
+                self.dropped.remove(index);
+                self.discard(element);
+            }
         }
-        None
+        Some(result)
     }
 
-    pub fn draw(camera: &Camera) {
+    ///Draws the roots in painter's order. Elements draw their own children, see `children_in_draw_order`.
+    pub fn draw(&self, camera: &Camera) {
         cull_face(Face::Front);
         enable(GLConsts::Blend);
-        unsafe {
-            match &mut UI {
-                None => panic!("Initialize the UI first."),
-                Some(ui) => {
-                    ui.elements.keys().into_iter().for_each(|key| {
-                        match ui.elements.get(key) {
-                            None => panic!("Getting a ui element by an invalid id is not possible."),
-                            Some(elem) => {
-                                if elem.is_root() {
-                                    elem.draw(camera);
-                                }
-                            }
-                        }
-                    });
-                }
+        for root in self.sort_by_z_index(&self.roots) {
+            if let Some(elem) = self.get_element_by_id(&root) {
+                elem.draw(self, camera);
             }
         }
+        self.draw_focus_ring(camera);
     }
 
-    pub fn update_screen_size(screen_size: [i32; 2]) {
-        unsafe {
-            match &mut UI {
-                None => panic!("Initialize the UI first."),
-                Some(ui) => {
-                    for (_id, elem) in &mut ui.elements {
-                        elem.resize(screen_size);
-                    }
-                }
-            }
+    ///Ids of every element in painter's order: roots and siblings by ascending `z_index` (ties keep tree order),
+    ///each parent before its children. Hit-testing walks this in reverse.
+    pub fn draw_order(&self) -> Vec<usize> {
+        let mut order = vec![];
+        for root in self.sort_by_z_index(&self.roots) {
+            self.push_draw_order(&root, &mut order);
         }
+        order
     }
 
-    pub fn update_key(key: Key, code: Action, delta: f32) {
-        unsafe {
-            if let Some(ui) = &mut UI {
-                for (_, elem) in &mut ui.elements {
-                    elem.update_key(key, code, delta);
-                }
+    fn push_draw_order(&self, id: &usize, order: &mut Vec<usize>) {
+        order.push(*id);
+        if let Some(element) = self.get_element_by_id(id) {
+            for child in self.sort_by_z_index(element.children()) {
+                self.push_draw_order(&child, order);
             }
         }
     }
 
-    pub fn update_mouse(button: MouseButton, action: Action) {
-        unsafe {
-            if let Some(ui) = &mut UI {
-                for (_, elem) in &mut ui.elements {
-                    elem.update_mouse(button, action);
-                }
+    pub fn children_in_draw_order(&self, parent: &usize) -> Vec<&Box<dyn UIElement>> {
+        match self.get_element_by_id(parent) {
+            None => vec![],
+            Some(parent) => self.sort_by_z_index(parent.children()).iter().filter_map(|id| self.get_element_by_id(id)).collect()
+        }
+    }
+
+    fn sort_by_z_index(&self, ids: &Vec<usize>) -> Vec<usize> {
+        let mut ids = ids.clone();
+        //Stable, so equal z-indices keep tree order.
+        ids.sort_by_key(|id| self.get_element_by_id(id).map(|element| element.z_index()).unwrap_or(0));
+        ids
+    }
+
+    ///The topmost element hit at `point`, see `UIElement::hit_test`, ignoring elements where an ancestor clips it away.
+    pub fn element_at(&self, point: [f64; 2]) -> Option<usize> {
+        self.draw_order().into_iter().rev().find(|id| {
+            self.get_element_by_id(id).map(|element| element.hit_test(self, point)).unwrap_or(false)
+                && !self.is_clipped(id, point)
+        })
+    }
+
+    ///Whether an ancestor of the element clips its children and does not contain `point`.
+    pub fn is_clipped(&self, id: &usize, point: [f64; 2]) -> bool {
+        let mut ancestor = self.get_element_by_id(id).and_then(|element| element.parent());
+        while let Some(id) = ancestor {
+            let element = match self.get_element_by_id(&id) {
+                None => return false,
+                Some(element) => element
+            };
+            if element.clips_children() && !element.contains_point(point) {
+                return true;
             }
+            ancestor = element.parent();
         }
+        false
     }
 
-    pub fn update_cursor(cursor: [f64; 2]) {
-        unsafe {
-            match &mut UI {
-                None => panic!("Initialize the UI first."),
-                Some(ui) => {
-                    for (_id, elem) in &mut ui.elements {
-                        elem.update_cursor(cursor);
-                    }
-                }
+    ///Restricts drawing to `rect`, as x, y, width and height in UI coordinates, within the current clip rectangle.
+    ///Every push has to be followed by a `pop_clip` once the clipped elements are drawn.
+    pub fn push_clip(&self, rect: [i32; 4]) {
+        let mut stack = self.clip_stack.borrow_mut();
+        let rect = match stack.last() {
+            None => rect,
+            Some(outer) => intersect(outer, &rect)
+        };
+        stack.push(rect);
+        enable(GLConsts::ScissorTest);
+        scissor([rect[0], rect[1]], [rect[2], rect[3]]);
+    }
+
+    ///Restores the clip rectangle from before the last `push_clip`.
+    pub fn pop_clip(&self) {
+        let mut stack = self.clip_stack.borrow_mut();
+        stack.pop();
+        match stack.last() {
+            None => disable(GLConsts::ScissorTest),
+            Some(rect) => scissor([rect[0], rect[1]], [rect[2], rect[3]])
+        }
+    }
+
+    ///The rectangle drawing is currently restricted to, if any.
+    pub fn clip(&self) -> Option<[i32; 4]> {
+        self.clip_stack.borrow().last().cloned()
+    }
+
+    pub fn roots(&self) -> &Vec<usize> {
+        &self.roots
+    }
+
+    pub fn update_screen_size(&mut self, screen_size: [i32; 2]) {
+        self.screen_size = screen_size;
+        for (_id, elem) in &mut self.elements {
+            elem.resize(screen_size);
+        }
+    }
+
+    pub fn screen_size(&self) -> [i32; 2] {
+        self.screen_size
+    }
+
+    ///Hands a key to the focused element, then to its ancestors until one handles it.
+    ///Tab and shift and tab move the focus unless handled.
+    pub fn update_key(&mut self, key: Key, code: Action, delta: f32) {
+        let mut current = self.focused;
+        while let Some(id) = current {
+            current = self.get_element_by_id(&id).and_then(|element| element.parent());
+            if self.with_element(&id, |elem, ui| elem.update_key(ui, key, code, delta)).unwrap_or(false) {
+                return;
+            }
+        }
+        if key == Key::Tab && code != Action::Release {
+            match Input::is_key_held(Key::LeftShift) || Input::is_key_held(Key::RightShift) {
+                true => self.focus_previous(),
+                false => self.focus_next()
             }
         }
     }
 
-    pub fn default_program() -> Program {
-        unsafe {
-            match &UI {
-                None => panic!("Initialize the UI first."),
-                Some(ui) => {
-                    ui.default_program
+    ///Sends a wheel event with the scroll offset to the topmost element under the cursor.
+    pub fn update_scroll(&mut self, offset: [f64; 2]) {
+        let cursor = Input::ui_cursor();
+        if let Some(target) = self.element_at(cursor) {
+            self.dispatch_mouse_event(MouseEvent::new(MouseEventKind::Wheel(offset), target, cursor, held_modifiers()));
+        }
+    }
+
+    ///Hands a typed character, e.g. from `WindowEvent::Char`, to the focused element.
+    pub fn update_char(&mut self, character: char) {
+        if let Some(id) = self.focused {
+            self.with_element(&id, |elem, ui| elem.update_char(ui, character));
+        }
+    }
+
+    ///Sends down, up, click and double click events, then the raw input along the path of the press or release,
+    ///deepest first, see `pointer_path`. Only the hovered element, see `update_cursor`, starts dragging on press.
+    ///Pressing the primary button first focuses the element under the cursor, or its closest focusable ancestor,
+    ///and clears the focus when there is none.
+    pub fn update_mouse(&mut self, button: MouseButton, action: Action) {
+        let cursor = Input::ui_cursor();
+        let path = self.pointer_path(button, action, cursor);
+        match action {
+            Action::Press => {
+                if button == MouseButton::Button1 {
+                    let target = self.focus_target(self.element_at(cursor));
+                    self.change_focus(target);
                 }
+                self.press_pointer(button, cursor);
             }
+            Action::Release => self.release_pointer(button, cursor),
+            Action::Repeat => {}
+        }
+        for id in path {
+            self.with_element(&id, |elem, ui| elem.update_mouse(ui, button, action));
         }
     }
 
-    pub fn register<E: UIElement + 'static>(mut element: E) -> usize {
-        unsafe {
-            match &mut UI {
-                None => {
-                    panic!("Initialize the UI first.")
+    ///Marks only the topmost element under the cursor as hovered, then dispatches topmost first.
+    ///Enter and leave events follow once every element saw the new cursor.
+    pub fn update_cursor(&mut self, cursor: [f64; 2]) {
+        let hovered = self.element_at(cursor);
+        for id in self.draw_order().into_iter().rev() {
+            self.with_element(&id, |elem, ui| {
+                let was_hovered = elem.element_data().hover_flag;
+                elem.element_data_mut().hover_flag = hovered == Some(id);
+                elem.update_cursor(ui, cursor);
+                if elem.element_data().hover_flag != was_hovered {
+                    ui.styles_dirty = true;
                 }
-                Some(ui) => {
-                    let id = element.id();
-                    let mut children = {
-                        let mut children = vec![];
-                        while !element.child_buffer().is_empty() {
-                            let child = element.child_buffer().remove(0);
-                            children.push(child);
-                        }
-                        children
-                    };
-
-                    while !children.is_empty() {
-                        let mut child = children.remove(0);
-                        element.register_child(&child);
-                        child.set_parent(id);
-                        if ui.elements.contains_key(&child.id()) {
-                            eprintln!("Cannot have duplicate ids.");
-                            continue;
-                        }
-                        ui.elements.insert(child.id(), child);
-                    }
-                    ui.elements.insert(element.id(), Box::new(element));
+                if let Some(offset) = elem.element_data().drag_offset {
+                    let relative = elem.relative_mouse_pos(cursor);
+                    let ui_mouse = Input::ui_cursor();
 
-                    id
+                    let mut callbacks = std::mem::take(&mut elem.element_data_mut().callbacks.on_drag);
+                    for on_drag in &mut callbacks {
+                        on_drag(ui, elem, ui_mouse, offset, relative);
+                    }
+                    //Keep callbacks that were registered while dispatching.
+                    callbacks.append(&mut elem.element_data_mut().callbacks.on_drag);
+                    elem.element_data_mut().callbacks.on_drag = callbacks;
                 }
-            }
+            });
         }
+        self.move_pointer(hovered, cursor);
+    }
+
+    pub fn default_program(&self) -> Program {
+        self.default_program
+    }
+
+    ///Draws with `program` from now on and deletes the previous default program.
+    pub fn set_default_program(&mut self, program: Program) {
+        self.default_program.delete();
+        self.default_program = program;
+    }
+
+    ///Sources of the default program, with the fragment shader read from `res/shaders/ui/frag.glsl`, so a
+    ///`ShaderWatcher` can rebuild it while the shader is worked on.
+    pub fn default_program_sources() -> ProgramSources {
+        ProgramSources::new(ShaderSource::embedded(DEFAULT_VERTEX_SHADER), ShaderSource::file(DEFAULT_FRAGMENT_SHADER_PATH))
+    }
+
+    pub fn clipboard_text(&mut self) -> Option<String> {
+        self.clipboard.as_mut()?.get().map(|text| text.to_string())
+    }
+
+    pub fn set_clipboard_text(&mut self, text: &str) {
+        if let Some(clipboard) = &mut self.clipboard {
+            clipboard.set(&ImString::new(text));
+        }
+    }
+
+    ///Fonts and glyph atlas of labels.
+    pub fn text(&self) -> &TextRenderer {
+        &self.text
+    }
+
+    pub fn text_mut(&mut self) -> &mut TextRenderer {
+        &mut self.text
+    }
+
+    ///Registers `element` as a root and, recursively, the children that were added to it before registration.
+    pub fn register<E: UIElement + 'static>(&mut self, element: E) -> Result<usize, UiTreeError> {
+        self.register_boxed(Box::new(element))
+    }
+
+    pub fn register_boxed(&mut self, element: Box<dyn UIElement>) -> Result<usize, UiTreeError> {
+        self.attach(element, None, None)
     }
 }
 
+///Overlap of two rectangles given as x, y, width and height.
+fn intersect(a: &[i32; 4], b: &[i32; 4]) -> [i32; 4] {
+    let x = a[0].max(b[0]);
+    let y = a[1].max(b[1]);
+    let right = (a[0] + a[2]).min(b[0] + b[2]);
+    let top = (a[1] + a[3]).min(b[1] + b[3]);
+    [x, y, (right - x).max(0), (top - y).max(0)]
+}
+
+impl Drop for UiContext {
+    fn drop(&mut self) {
+        self.default_program.delete();
+    }
+}
+
+///Program (`None` drawing with the default program of the context), geometry and fill method of an element.
 #[derive(Debug)]
-pub struct UIRenderData(Program, VertexArrayObject, FillMethod);
+pub struct UIRenderData(Option<Program>, VertexArrayObject, FillMethod);
+
+///The unit quad of `rectangle::BUFFER`, which `draw_self` stretches over the bounds of an element, drawn with the
+///default program and fill method.
+pub fn quad_render_data() -> UIRenderData {
+    let vao = VertexArrayObject::new(Some(VertexArrayObjectType::ArrayStrips(4)))
+        .with_buffer(VertexBufferObject::array(BufferType::ArrayBuffer, DrawType::StaticDraw, &BUFFER))
+        .with_local_attrib_pointer(LocalAttribPointer::new(2, BufferDataType::Float, false))
+        .with_local_attrib_pointer(LocalAttribPointer::new(2, BufferDataType::Float, false))
+        .build();
+    UIRenderData(None, vao, Default::default())
+}
 
 pub struct Callbacks {
     on_drag: Vec<Box<OnDrag>>,
+    on_focus: Vec<Box<OnFocus>>,
+    on_blur: Vec<Box<OnFocus>>,
+    on_mouse_event: Vec<Box<OnMouseEvent>>,
 }
 
 impl Debug for Callbacks {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let on_drag = self.on_drag.len();
-        f.write_fmt(format_args!("Callbacks: [on_drag: {}]", on_drag))
+        f.write_fmt(format_args!("Callbacks: [on_drag: {}, on_focus: {}, on_blur: {}, on_mouse_event: {}]", on_drag, self.on_focus.len(), self.on_blur.len(), self.on_mouse_event.len()))
     }
 }
 
@@ -294,12 +632,50 @@ pub struct UIElementData {
     render_data: Option<UIRenderData>,
     hover_flag: bool,
     custom_properties: HashMap<String, CustomUIProperty>,
-    corner_radius: f32,
+    ///Of the `Shape::Rectangle` of the default `UIElement::shape`.
+    corner_radius: Styled<f32>,
     drag_offset: Option<[f64; 2]>,
+    ///Siblings with a higher z-index are drawn later and hit first.
+    z_index: i32,
+    ///Matched by `#name` selectors, see `style::selector::Selector`.
+    name: Option<String>,
+    ///Matched by `.class` selectors.
+    classes: Vec<String>,
+    ///Set when the name or classes changed, see `mark_style_dirty`.
+    style_dirty: bool,
     callbacks: Callbacks,
 }
 
 impl UIElementData {
+    ///Data of an element without render data, see `with_render_data`, and without children or callbacks.
+    pub fn new(id: usize, position: [u32; 2], size: [u32; 2]) -> UIElementData {
+        Self {
+            id,
+            parent: None,
+            children: vec![],
+            position,
+            width: size[0],
+            height: size[1],
+            tmp_children: vec![],
+            render_data: None,
+            hover_flag: false,
+            custom_properties: Default::default(),
+            corner_radius: Styled::new(0.0),
+            drag_offset: None,
+            z_index: 0,
+            name: None,
+            classes: vec![],
+            style_dirty: false,
+            callbacks: Callbacks { on_drag: vec![], on_focus: vec![], on_blur: vec![], on_mouse_event: vec![] },
+        }
+    }
+
+    ///Draws the element with `render_data`, e.g. `quad_render_data()`, in `UIElement::draw_self`.
+    pub fn with_render_data(mut self, render_data: UIRenderData) -> UIElementData {
+        self.render_data = Some(render_data);
+        self
+    }
+
     pub fn id(&self) -> usize {
         self.id
     }
@@ -309,17 +685,10 @@ impl UIElementData {
     pub fn children(&self) -> &Vec<usize> {
         &self.children
     }
-    pub fn get_children(&self) -> Vec<Option<&'static Box<dyn UIElement>>> {
-        let mut children = vec![];
-        for child in &self.children {
-            children.push(UI::get_element_by_id(child));
-        }
-        children
-    }
-    pub fn get_children_mut(&self) -> Vec<Option<&'static mut Box<dyn UIElement>>> {
+    pub fn get_children<'a>(&self, ui: &'a UiContext) -> Vec<Option<&'a Box<dyn UIElement>>> {
         let mut children = vec![];
         for child in &self.children {
-            children.push(UI::get_element_by_id_mut(child));
+            children.push(ui.get_element_by_id(child));
         }
         children
     }
@@ -345,11 +714,29 @@ impl UIElementData {
         &self.custom_properties
     }
     pub fn corner_radius(&self) -> f32 {
-        self.corner_radius
+        *self.corner_radius.get()
+    }
+    ///Sets the radius the element was built with, which a stylesheet can override.
+    pub fn set_corner_radius(&mut self, corner_radius: f32) {
+        self.corner_radius.set_own(corner_radius);
     }
     pub fn drag_offset(&self) -> Option<[f64; 2]> {
         self.drag_offset
     }
+    pub fn z_index(&self) -> i32 {
+        self.z_index
+    }
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    pub fn classes(&self) -> &Vec<String> {
+        &self.classes
+    }
+    ///Restyles the element with the next update. Elements call this when the state behind `UIElement::is_pressed`
+    ///or `UIElement::is_disabled` changes outside of input and focus, e.g. from a setter.
+    pub fn mark_style_dirty(&mut self) {
+        self.style_dirty = true;
+    }
     pub fn callbacks(&self) -> &Callbacks {
         &self.callbacks
     }
@@ -368,23 +755,23 @@ pub trait UIElement where Self: Debug + Downcast {
     fn parent(&self) -> Option<usize> {
         self.element_data().parent
     }
-    fn get_parent(&self) -> Option<&'static Box<dyn UIElement>> {
+    fn get_parent<'a>(&self, ui: &'a UiContext) -> Option<&'a Box<dyn UIElement>> {
         return match &self.parent() {
             None => {
                 None
             }
             Some(id) => {
-                UI::get_element_by_id(id)
+                ui.get_element_by_id(id)
             }
         };
     }
-    fn get_parent_mut(&self) -> Option<&'static mut Box<dyn UIElement>> {
+    fn get_parent_mut<'a>(&self, ui: &'a mut UiContext) -> Option<&'a mut Box<dyn UIElement>> {
         return match &self.parent() {
             None => {
                 None
             }
             Some(id) => {
-                UI::get_element_by_id_mut(id)
+                ui.get_element_by_id_mut(id)
             }
         };
     }
@@ -403,29 +790,76 @@ pub trait UIElement where Self: Debug + Downcast {
     fn set_position(&mut self, position: [u32; 2]) {
         self.element_data_mut().position = position;
     }
+    fn z_index(&self) -> i32 {
+        self.element_data().z_index
+    }
+    fn set_z_index(&mut self, z_index: i32) {
+        self.element_data_mut().z_index = z_index;
+    }
+    fn name(&self) -> Option<&str> {
+        self.element_data().name.as_deref()
+    }
+    ///Name for stylesheets, set by the markup loader from the `id` attribute.
+    fn set_name(&mut self, name: Option<String>) {
+        self.element_data_mut().name = name;
+        self.element_data_mut().style_dirty = true;
+    }
+    fn classes(&self) -> &Vec<String> {
+        &self.element_data().classes
+    }
+    fn has_class(&self, class: &str) -> bool {
+        self.element_data().classes.iter().any(|own| own == class)
+    }
+    fn add_class(&mut self, class: &str) {
+        if !self.has_class(class) {
+            self.element_data_mut().classes.push(class.to_string());
+            self.element_data_mut().style_dirty = true;
+        }
+    }
+    fn remove_class(&mut self, class: &str) {
+        if self.has_class(class) {
+            self.element_data_mut().classes.retain(|own| own != class);
+            self.element_data_mut().style_dirty = true;
+        }
+    }
+
+    ///Style for the layout engine. Elements returning `None` keep their size and, when below an element taking
+    ///part in layout, are only moved. Their children are left alone, see `layout_changed`.
+    fn layout_style(&self) -> Option<LayoutStyle> {
+        None
+    }
+
+    ///Lets a parent taking part in layout adjust the style of its child `child`.
+    fn child_layout_style(&self, _child: &usize, style: LayoutStyle) -> LayoutStyle {
+        style
+    }
+
+    ///Called by `UiContext::apply_layout` right after a layout moved or resized the element, to place parts
+    ///that are not laid out themselves.
+    fn layout_changed(&mut self, _ui: &mut UiContext) {}
 
     fn element_data(&self) -> &UIElementData;
     fn element_data_mut(&mut self) -> &mut UIElementData;
     fn resize(&mut self, size: [i32; 2]);
-    fn update_cursor(&mut self, cursor: [f64; 2]) {
-        self.element_data_mut().hover_flag = self.contains_point(cursor);
-
-        if let Some(offset) = self.element_data().drag_offset {
-            let relative = self.relative_mouse_pos(cursor);
-            let me = self.self_mut().unwrap();
-            for on_drag in &mut self.element_data_mut().callbacks.on_drag {
-                let ui_mouse = Input::ui_cursor();
-
-                on_drag(me, ui_mouse, offset, relative);
-            }
-        }
-    }
+    ///Called on every element after the `UiContext` updated the hover state. Drag callbacks are fired afterwards.
+    ///To only hear about the element under the cursor, see `on_mouse_event`.
+    fn update_cursor(&mut self, _ui: &mut UiContext, _cursor: [f64; 2]) {}
 
     fn register_on_drag(&mut self, callback: Box<OnDrag>) {
         self.element_data_mut().callbacks.on_drag.push(callback);
     }
 
-    fn update_mouse(&mut self, mouse_button: MouseButton, action: Action) {
+    ///A mouse event in any phase on its way to or from its target, see `UiContext::dispatch_mouse_event`.
+    fn on_mouse_event(&mut self, _ui: &mut UiContext, _event: &mut MouseEvent) {}
+
+    ///Called in the target and bubble phases, after `on_mouse_event`.
+    fn register_on_mouse_event(&mut self, callback: Box<OnMouseEvent>) {
+        self.element_data_mut().callbacks.on_mouse_event.push(callback);
+    }
+
+    ///Called for each press and release on the element under the cursor and its ancestors, and on release also
+    ///on the element the button went down on and its ancestors, wherever the cursor is.
+    fn update_mouse(&mut self, _ui: &mut UiContext, mouse_button: MouseButton, action: Action) {
         match action {
             Action::Release => {
                 self.element_data_mut().drag_offset = None;
@@ -454,6 +888,17 @@ pub trait UIElement where Self: Debug + Downcast {
         self.child_buffer().push(child);
     }
     fn contains_point(&self, point: [f64; 2]) -> bool;
+
+    ///Whether `point` hits the element, e.g. for `UiContext::element_at`. Elements drawing nothing themselves,
+    ///like containers, can answer with what their children cover.
+    fn hit_test(&self, _ui: &UiContext, point: [f64; 2]) -> bool {
+        self.contains_point(point)
+    }
+
+    ///Outline the element is drawn with within its bounds, which hit-testing should follow, see `shape::shape_contains`.
+    fn shape(&self) -> Shape {
+        Shape::Rectangle(self.element_data().corner_radius())
+    }
     fn set_custom_property(&mut self, key: &dyn ToString, value: CustomUIProperty) {
         self.element_data_mut().custom_properties.insert(key.to_string(), value);
     }
@@ -464,14 +909,6 @@ pub trait UIElement where Self: Debug + Downcast {
         self.element_data_mut().custom_properties.get_mut(&key.to_string())
     }
 
-    fn self_ref(&self) -> Option<&'static Box<dyn UIElement>> {
-        UI::get_element_by_id(&self.id())
-    }
-
-    fn self_mut(&self) -> Option<&'static mut Box<dyn UIElement>> {
-        UI::get_element_by_id_mut(&self.id())
-    }
-
     fn color(&self) -> Vector4<f32> {
         return match self.element_data().hover_flag {
             true => {
@@ -522,15 +959,86 @@ pub trait UIElement where Self: Debug + Downcast {
         }
     }
 
-    fn update_key(&mut self, _key: Key, _code: Action, _delta: f32) {}
+    ///Whether a mouse button is held on the element, for `:pressed` selectors.
+    fn is_pressed(&self) -> bool {
+        false
+    }
+
+    ///Whether the element ignores input, for `:disabled` selectors.
+    fn is_disabled(&self) -> bool {
+        false
+    }
+
+    ///Takes the computed style over, after the context applied its corner radius. Elements with fonts, padding
+    ///or parts drawn without being registered override this. Colors are read from `UiContext::style_of` while
+    ///drawing instead.
+    fn apply_style(&mut self, _style: &Style, _stylesheet: &Stylesheet) {}
+
+    ///Whether the element takes keyboard focus, by tab or by clicking it or a descendant that does not.
+    fn focusable(&self) -> bool {
+        false
+    }
 
-    fn update(&mut self, _delta: f32) {}
+    ///Called once the element gained focus, before the focus callbacks.
+    fn on_focus(&mut self, _ui: &mut UiContext) {}
 
-    fn draw(&self, camera: &Camera) {
+    ///Called once the element lost focus, before the blur callbacks.
+    fn on_blur(&mut self, _ui: &mut UiContext) {}
+
+    fn register_on_focus(&mut self, callback: Box<OnFocus>) {
+        self.element_data_mut().callbacks.on_focus.push(callback);
+    }
+
+    fn register_on_blur(&mut self, callback: Box<OnFocus>) {
+        self.element_data_mut().callbacks.on_blur.push(callback);
+    }
+
+    ///A key while the element or one of its descendants is focused.
+    ///Returns whether the element handled it, otherwise it is passed on to the parent.
+    fn update_key(&mut self, _ui: &mut UiContext, _key: Key, _code: Action, _delta: f32) -> bool {
+        false
+    }
+
+    ///Whether children are only drawn and hit within `contains_point` of this element.
+    fn clips_children(&self) -> bool {
+        false
+    }
+
+    ///A character typed on the keyboard while the element is focused, after keyboard layout and modifiers were applied.
+    fn update_char(&mut self, _ui: &mut UiContext, _character: char) {}
+
+    ///Called once the element is part of the tree below `parent`, after registration or after being moved.
+    fn on_attach(&mut self, _ui: &mut UiContext, _parent: Option<usize>) {}
+
+    ///Called while the element is still below `parent`, before it is removed or moved away.
+    fn on_detach(&mut self, _ui: &mut UiContext, _parent: Option<usize>) {}
+
+    ///Called once `child` is no longer a child of the element, after it was removed or moved away, to drop what
+    ///the element keeps per child.
+    fn on_child_detach(&mut self, _ui: &mut UiContext, _child: usize) {}
+
+    ///Input the user made, like a slider value, to carry over to the element replacing this one when its
+    ///markup is reloaded, see `LiveUi`.
+    fn save_state(&self) -> Option<ElementState> {
+        None
+    }
+
+    ///Applies state saved by an element with the same tag before the element is registered.
+    fn restore_state(&mut self, _state: ElementState) {}
+
+    fn update(&mut self, _ui: &mut UiContext, _delta: f32) {}
+
+    fn draw(&self, ui: &UiContext, camera: &Camera) {
+        self.draw_self(ui, camera);
+    }
+
+    ///Draws the render data, if any, as a rectangle filling the element. Elements overriding `draw` to draw
+    ///their children as well can call this first.
+    fn draw_self(&self, ui: &UiContext, camera: &Camera) {
         if let Some(render_data) = &self.element_data().render_data {
             let projection = camera.last_orthographic();
 
-            let program = render_data.0;
+            let program = render_data.0.unwrap_or(ui.default_program());
             let vao = &render_data.1;
             program.enable();
             vao.bind();
@@ -538,7 +1046,8 @@ pub trait UIElement where Self: Debug + Downcast {
             let t = self.position();
 
             let mut model = MatrixWrapper(IDENTITY_MAT4).translated([(t[0] * 2) as f32, (t[1] * 2) as f32, 0f32]);
-            let color = self.color();
+            let style = ui.style_of(&self.id());
+            let color = style.and_then(|style| style.background()).unwrap_or_else(|| self.color());
 
             model = model.scale([self.element_data().width as f32, self.element_data().height as f32, 1f32]);
 
@@ -551,9 +1060,16 @@ pub trait UIElement where Self: Debug + Downcast {
             program.set_uniform_vec2("bottomLeft", &[pos[0] as f32, pos[1] as f32]);
             program.set_uniform_vec2("size", &[self.element_data().width as f32, self.element_data().height as f32]);
 
-            let radius_enable = self.element_data().corner_radius.abs() > 0.0;
-            program.set_uniform_bool("enableCorner", &radius_enable);
-            program.set_uniform_float("cornerRadius", &self.element_data().corner_radius);
+            let shape = self.shape();
+            let radius = match shape {
+                Shape::Rectangle(radius) => radius,
+                Shape::Ellipse => 0f32
+            };
+            program.set_uniform_int("shape", &shape.uniform());
+            program.set_uniform_bool("enableCorner", &(radius.abs() > 0.0));
+            program.set_uniform_float("cornerRadius", &radius);
+            program.set_uniform_float("borderWidth", &style.and_then(|style| style.border_width()).unwrap_or(0f32));
+            program.set_uniform_vec4("borderColor", &style.and_then(|style| style.border_color()).unwrap_or(color));
 
             render_data.2.bind(&program, "fillStrategy");
 
@@ -565,6 +1081,9 @@ pub trait UIElement where Self: Debug + Downcast {
                     VertexArrayObjectType::ArrayStrips(tris) => {
                         draw_arrays(DrawMode::TriangleStrip, 0, *tris);
                     }
+                    VertexArrayObjectType::Elements(count, index_type) => {
+                        draw_elements(DrawMode::Triangles, *count, *index_type, 0);
+                    }
                 }
             }
 
@@ -575,9 +1094,103 @@ pub trait UIElement where Self: Debug + Downcast {
 }
 
 pub fn ui_counter() -> usize {
-    unsafe {
-        let id = COUNTER;
-        COUNTER += 1;
-        id
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+    use super::*;
+    use crate::{Rectangle, Slider};
+    use crate::gfx::backend::{restore_backend, set_backend};
+    use crate::gfx::ui::container::v_box::VBox;
+    use crate::gfx::ui::layout::Layout;
+    use crate::gfx::ui::layout::engine::Insets;
+    use crate::gfx::backend::recording::{GlCall, RecordingBackend};
+
+    static INPUT: Once = Once::new();
+
+    ///Runs `f` on a context drawing to a `RecordingBackend`, for tests that need no window. The context is
+    ///dropped before the previous backend is restored, as it deletes its vertex arrays. `Input` is shared by
+    ///every test and only initialized once, with no keys held.
+    pub(crate) fn with_recording_context<R>(f: impl FnOnce(&mut UiContext) -> R) -> R {
+        INPUT.call_once(Input::init);
+        let previous = set_backend(RecordingBackend::new());
+        let mut ui = UiContext::new();
+        let result = f(&mut ui);
+        drop(ui);
+        restore_backend(previous);
+        result
+    }
+
+    ///The call setting `name`, made right after looking up its location.
+    fn uniform(calls: &[GlCall], name: &str) -> Option<GlCall> {
+        calls.windows(2).find_map(|pair| match &pair[0] {
+            GlCall::GetUniformLocation(_, uniform) if uniform == name => Some(pair[1].clone()),
+            _ => None
+        })
+    }
+
+    #[test]
+    fn draws_a_rectangle_as_one_strip() {
+        let backend = RecordingBackend::new();
+        let log = backend.log();
+        let previous = set_backend(backend);
+
+        let mut ui = UiContext::new();
+        let rectangle = Rectangle::new([10, 20], [30f32, 40f32])
+            .with_custom_property(&"color", CustomUIProperty::Vec4([1.0, 0.5, 0.25, 1.0]));
+        let id = ui.register(rectangle).unwrap();
+        let program = ui.default_program().id();
+        let vao = ui.get_element_by_id(&id).unwrap().element_data().render_data.as_ref().unwrap().1.id();
+
+        log.clear();
+        ui.draw(&Camera::new(90f32, 100f32, 0.1f32, [200, 100]));
+        let calls = log.take();
+
+        assert_eq!(calls[..4], [
+            GlCall::CullFace(gl::FRONT),
+            GlCall::Enable(gl::BLEND),
+            GlCall::UseProgram(program),
+            GlCall::BindVertexArray(vao),
+        ]);
+        assert_eq!(calls[calls.len() - 3..], [
+            GlCall::DrawArrays(gl::TRIANGLE_STRIP, 0, 4),
+            GlCall::BindVertexArray(0),
+            GlCall::UseProgram(0),
+        ]);
+        assert_eq!(calls.iter().filter(|call| matches!(call, GlCall::DrawArrays(..) | GlCall::DrawElements(..))).count(), 1);
+
+        assert!(matches!(uniform(&calls, "color"), Some(GlCall::Uniform4fv(_, 1, color)) if color == vec![1.0, 0.5, 0.25, 1.0]));
+        assert!(matches!(uniform(&calls, "bottomLeft"), Some(GlCall::Uniform2fv(_, 1, position)) if position == vec![10f32, 20f32]));
+        assert!(matches!(uniform(&calls, "size"), Some(GlCall::Uniform2fv(_, 1, size)) if size == vec![30f32, 40f32]));
+        assert!(matches!(uniform(&calls, "enableCorner"), Some(GlCall::Uniform1i(_, 0))));
+
+        //The vertex array is deleted with the element, so the context goes before the backend.
+        drop(ui);
+        restore_backend(previous);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn slider_parts_follow_the_layout_in_the_same_update() {
+        with_recording_context(|ui| {
+            ui.update_screen_size([300, 140]);
+            let slider = Slider::default().with_value(0.5);
+            let slider_id = slider.id();
+            let vbox = VBox::new()
+                .with_horizontal(Layout::WrapContent)
+                .with_vertical(Layout::WrapContent)
+                .with_padding(Insets::all(10f32))
+                .with_child(slider);
+            ui.register(vbox).unwrap();
+            ui.update(0f32);
+
+            let slider = ui.get_element_by_id(&slider_id).unwrap();
+            assert_eq!(slider.element_data().position, [10, 100]);
+            let parts = slider.children().clone();
+            let area = ui.get_element_by_id(&parts[0]).unwrap().element_data();
+            assert_eq!((area.position, area.width, area.height), ([10, 100], 250, 30));
+            //Centred on the middle of the area, which is at 0.5.
+            assert_eq!(ui.get_element_by_id(&parts[1]).unwrap().element_data().position, [130, 95]);
+        });
+    }
+}
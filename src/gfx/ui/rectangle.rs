@@ -1,6 +1,7 @@
 use vecmath::Matrix4;
-use crate::gfx::ui::{Callbacks, ui_counter, UIElement, UIElementData, UIRenderData};
-use crate::{BufferDataType, BufferType, Constructor, CustomUIProperty, DrawType, LocalAttribPointer, MatrixWrapper, UI, VertexArrayObject, VertexArrayObjectType, VertexBufferObject};
+use crate::gfx::ui::{quad_render_data, ui_counter, UIElement, UIElementData};
+use crate::gfx::ui::shape::{AlphaMask, shape_contains};
+use crate::{CustomUIProperty, MatrixWrapper};
 use crate::math::linear_algebra::IDENTITY_MAT4;
 
 pub const BUFFER: [f32; 16] = [
@@ -14,6 +15,8 @@ pub const BUFFER: [f32; 16] = [
 pub struct Rectangle {
     data: UIElementData,
     size: [f32; 2],
+    ///Limits hits to opaque pixels, for rectangles drawing an image through their own program.
+    hit_mask: Option<AlphaMask>,
 }
 
 impl Rectangle {
@@ -27,34 +30,39 @@ impl Rectangle {
         self
     }
 
+    pub fn with_corner_radius(mut self, corner_radius: f32) -> Self {
+        self.data.set_corner_radius(corner_radius);
+        self
+    }
+
+    ///Adds a class for stylesheets, e.g. to address the rectangle as part of a widget.
+    pub fn with_class(mut self, class: &str) -> Self {
+        self.add_class(class);
+        self
+    }
+
+    pub fn with_hit_mask(mut self, hit_mask: AlphaMask) -> Self {
+        self.hit_mask = Some(hit_mask);
+        self
+    }
+
+    ///The mask is stretched over the bounds, like the image drawn with it.
+    pub fn set_hit_mask(&mut self, hit_mask: Option<AlphaMask>) {
+        self.hit_mask = hit_mask;
+    }
+
+    pub fn hit_mask(&self) -> Option<&AlphaMask> {
+        self.hit_mask.as_ref()
+    }
+
     pub fn scale(&self) -> Matrix4<f32> {
         MatrixWrapper(IDENTITY_MAT4).scale([self.size[0], self.size[1], 0f32]).0
     }
     pub fn new(position: [u32; 2], size: [f32; 2]) -> Self {
-        let buffer = BUFFER;
-
-        let vao = VertexArrayObject::new(Some(VertexArrayObjectType::ArrayStrips(4)))
-            .with_buffer(VertexBufferObject::array(BufferType::ArrayBuffer, DrawType::StaticDraw, &buffer))
-            .with_local_attrib_pointer(LocalAttribPointer::new(2, BufferDataType::Float, false))
-            .with_local_attrib_pointer(LocalAttribPointer::new(2, BufferDataType::Float, false))
-            .build();
         Self {
-            data: UIElementData {
-                id: ui_counter(),
-                parent: None,
-                children: vec![],
-                position,
-                width: size[0] as u32,
-                height: size[1] as u32,
-                tmp_children: vec![],
-                render_data: Some(UIRenderData(UI::default_program(), vao, Default::default())),
-                hover_flag: false,
-                custom_properties: Default::default(),
-                corner_radius: 0.0,
-                drag_offset: None,
-                callbacks: Callbacks { on_drag: vec![] },
-            },
+            data: UIElementData::new(ui_counter(), position, [size[0] as u32, size[1] as u32]).with_render_data(quad_render_data()),
             size,
+            hit_mask: None,
         }
     }
     pub fn data(&self) -> &UIElementData {
@@ -87,12 +95,15 @@ impl UIElement for Rectangle {
     fn resize(&mut self, _size: [i32; 2]) {}
 
     fn contains_point(&self, point: [f64; 2]) -> bool {
-        let x = self.position()[0] as f32;
-        let y = self.position()[1] as f32;
-        let w = self.size[0] + x;
-        let h = self.size[1] + y;
-        point[0] as f32 >= x && point[0] as f32 <= w
-            &&
-            point[1] as f32 >= y && point[1] as f32 <= h
+        if !shape_contains(self, point) {
+            return false;
+        }
+        match &self.hit_mask {
+            None => true,
+            Some(mask) => {
+                let local = self.relative_mouse_pos(point);
+                mask.contains([local[0] / self.data.width as f64, local[1] / self.data.height as f64])
+            }
+        }
     }
 }
\ No newline at end of file
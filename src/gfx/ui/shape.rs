@@ -0,0 +1,170 @@
+use crate::gfx::image::Image;
+use crate::gfx::ui::{UiContext, UIElement};
+
+///Outline of an element within its bounds, used alike for drawing and hit-testing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    ///A rectangle with corners rounded by the radius in pixels, sharp for zero. Radii beyond half the shorter
+    ///side are capped to it, like the shader does.
+    Rectangle(f32),
+    ///The ellipse touching all four sides, a circle for square bounds.
+    Ellipse,
+}
+
+impl Shape {
+    ///Value of the `shape` uniform of the UI fragment shader.
+    pub fn uniform(&self) -> i32 {
+        match self {
+            Shape::Rectangle(_) => 0,
+            Shape::Ellipse => 1,
+        }
+    }
+
+    ///Whether `point` is inside the shape filling the bounds at `position`, the bottom left corner, of `size`.
+    pub fn contains(&self, position: [f64; 2], size: [f64; 2], point: [f64; 2]) -> bool {
+        let local = [point[0] - position[0], point[1] - position[1]];
+        if local[0] < 0.0 || local[1] < 0.0 || local[0] > size[0] || local[1] > size[1] {
+            return false;
+        }
+        match self {
+            Shape::Rectangle(radius) => {
+                let radius = (*radius as f64).min(size[0].min(size[1]) / 2.0);
+                if radius <= 0.0 {
+                    return true;
+                }
+                //Distance to the rectangle shrunk by the radius, which is zero everywhere but in the corners.
+                let dx = (local[0] - local[0].clamp(radius, size[0] - radius)).abs();
+                let dy = (local[1] - local[1].clamp(radius, size[1] - radius)).abs();
+                dx * dx + dy * dy <= radius * radius
+            }
+            Shape::Ellipse => {
+                if size[0] <= 0.0 || size[1] <= 0.0 {
+                    return false;
+                }
+                let x = local[0] / size[0] * 2.0 - 1.0;
+                let y = local[1] / size[1] * 2.0 - 1.0;
+                x * x + y * y <= 1.0
+            }
+        }
+    }
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Shape::Rectangle(0f32)
+    }
+}
+
+///Opaque pixels of an image, for hit-testing elements drawn with it so transparent parts let the cursor through.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlphaMask {
+    size: [u32; 2],
+    opaque: Vec<bool>,
+}
+
+impl AlphaMask {
+    ///Pixels with an alpha above `threshold` count as hit. Expects the rows bottom-up, the order
+    ///`decoder::load` produces by default.
+    pub fn from_image(image: &Image, threshold: u8) -> AlphaMask {
+        Self {
+            size: image.size(),
+            opaque: image.pixels().iter().map(|pixel| pixel.0[3] > threshold).collect(),
+        }
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    ///Whether the pixel at `uv`, from 0 to 1 across the image starting at its bottom left, is opaque.
+    pub fn contains(&self, uv: [f64; 2]) -> bool {
+        if self.size[0] == 0 || self.size[1] == 0 || uv[0] < 0.0 || uv[1] < 0.0 || uv[0] > 1.0 || uv[1] > 1.0 {
+            return false;
+        }
+        let x = ((uv[0] * self.size[0] as f64) as u32).min(self.size[0] - 1);
+        let y = ((uv[1] * self.size[1] as f64) as u32).min(self.size[1] - 1);
+        self.opaque[(y * self.size[0] + x) as usize]
+    }
+}
+
+///Whether `point` is inside the shape of the element, see `UIElement::shape`.
+pub fn shape_contains(element: &dyn UIElement, point: [f64; 2]) -> bool {
+    let data = element.element_data();
+    let position = [data.position()[0] as f64, data.position()[1] as f64];
+    element.shape().contains(position, [data.width() as f64, data.height() as f64], point)
+}
+
+///Whether one of `children` is hit at `point`, for elements covering just the union of their children.
+pub fn children_hit(ui: &UiContext, children: &Vec<usize>, point: [f64; 2]) -> bool {
+    children.iter()
+        .filter_map(|child| ui.get_element_by_id(child))
+        .any(|child| child.hit_test(ui, point))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSITION: [f64; 2] = [10.0, 20.0];
+    const SIZE: [f64; 2] = [100.0, 50.0];
+
+    ///`point` relative to the bottom left corner of the bounds.
+    fn contains(shape: Shape, point: [f64; 2]) -> bool {
+        shape.contains(POSITION, SIZE, [POSITION[0] + point[0], POSITION[1] + point[1]])
+    }
+
+    #[test]
+    fn rounded_corners_cut_off_what_is_outside_the_radius() {
+        let shape = Shape::Rectangle(10f32);
+        //Like `outside_shape` of the UI fragment shader. The bottom left corner is rounded around (10, 10).
+        assert!(contains(shape, [3.0, 3.0]));
+        assert!(!contains(shape, [2.0, 2.0]));
+        assert!(contains(shape, [4.0, 2.0]));
+        assert!(!contains(shape, [0.0, 0.0]));
+        assert!(contains(shape, [97.0, 47.0]));
+        assert!(!contains(shape, [98.0, 48.0]));
+        assert!(contains(shape, [2.0, 38.0]));
+        assert!(!contains(shape, [2.0, 48.0]));
+        //The edges between the corners are straight.
+        assert!(contains(shape, [0.0, 25.0]));
+        assert!(contains(shape, [50.0, 50.0]));
+        assert!(!contains(shape, [-0.01, 25.0]));
+        assert!(!contains(shape, [50.0, 50.01]));
+    }
+
+    #[test]
+    fn radii_beyond_half_the_shorter_side_are_capped() {
+        let shape = Shape::Rectangle(100f32);
+        //Rounded by 25, so both short sides are half circles.
+        assert!(contains(shape, [2.0, 25.0]));
+        assert!(!contains(shape, [5.0, 5.0]));
+        assert!(contains(shape, [50.0, 0.0]));
+        assert!(!contains(shape, [99.0, 45.0]));
+    }
+
+    #[test]
+    fn a_zero_radius_is_the_box() {
+        for shape in [Shape::Rectangle(0f32), Shape::Rectangle(-5f32), Shape::default()] {
+            assert!(contains(shape, [0.0, 0.0]));
+            assert!(contains(shape, [100.0, 50.0]));
+            assert!(contains(shape, [0.0, 50.0]));
+            assert!(!contains(shape, [100.01, 50.0]));
+            assert!(!contains(shape, [0.0, -0.01]));
+        }
+    }
+
+    #[test]
+    fn ellipses_touch_every_side() {
+        let shape = Shape::Ellipse;
+        assert!(contains(shape, [50.0, 25.0]));
+        assert!(contains(shape, [0.0, 25.0]));
+        assert!(contains(shape, [100.0, 25.0]));
+        assert!(contains(shape, [50.0, 0.0]));
+        assert!(contains(shape, [50.0, 50.0]));
+        assert!(contains(shape, [15.0, 10.0]));
+        assert!(!contains(shape, [10.0, 5.0]));
+        assert!(!contains(shape, [0.0, 0.0]));
+        assert!(!contains(shape, [0.0, 24.0]));
+        assert!(!Shape::Ellipse.contains(POSITION, [0.0, 50.0], POSITION));
+    }
+}
@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use vecmath::Vector4;
+use crate::gfx::ui::{UiContext, UIElement};
+use crate::gfx::ui::layout::engine::Insets;
+use crate::gfx::ui::style::selector::PseudoState;
+use crate::gfx::ui::style::stylesheet::Stylesheet;
+
+pub mod selector;
+pub mod stylesheet;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleError {
+    ///Line and description of a malformed stylesheet.
+    Syntax(usize, String),
+    UnknownProperty(usize, String),
+    ///Line, property and value.
+    InvalidValue(usize, String, String),
+    Io(String),
+}
+
+impl Display for StyleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StyleError::Syntax(line, error) => f.write_fmt(format_args!("Line {}: {}", line, error)),
+            StyleError::UnknownProperty(line, name) => f.write_fmt(format_args!("Line {}: Unknown property '{}'", line, name)),
+            StyleError::InvalidValue(line, name, value) => f.write_fmt(format_args!("Line {}: Invalid {} '{}'", line, name, value)),
+            StyleError::Io(error) => f.write_fmt(format_args!("IO error: {}", error)),
+        }
+    }
+}
+
+impl Error for StyleError {}
+
+///Properties a stylesheet rule sets, or the computed style of an element. Unset properties leave the element
+///as it was built. Font, font size and text color are inherited by the children of an element that sets them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    background: Option<Vector4<f32>>,
+    border_color: Option<Vector4<f32>>,
+    ///In pixels, drawn inside the bounds.
+    border_width: Option<f32>,
+    corner_radius: Option<f32>,
+    ///Of containers.
+    padding: Option<Insets>,
+    font: Option<String>,
+    font_size: Option<f32>,
+    text_color: Option<Vector4<f32>>,
+}
+
+impl Style {
+    pub fn new() -> Style {
+        Default::default()
+    }
+
+    pub fn with_background(mut self, background: Vector4<f32>) -> Style {
+        self.background = Some(background);
+        self
+    }
+
+    pub fn with_border(mut self, width: f32, color: Vector4<f32>) -> Style {
+        self.border_width = Some(width);
+        self.border_color = Some(color);
+        self
+    }
+
+    pub fn with_border_color(mut self, color: Vector4<f32>) -> Style {
+        self.border_color = Some(color);
+        self
+    }
+
+    pub fn with_corner_radius(mut self, corner_radius: f32) -> Style {
+        self.corner_radius = Some(corner_radius);
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Insets) -> Style {
+        self.padding = Some(padding);
+        self
+    }
+
+    ///A font added to the text renderer of the context under `font`.
+    pub fn with_font<T: ToString>(mut self, font: T) -> Style {
+        self.font = Some(font.to_string());
+        self
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Style {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    pub fn with_text_color(mut self, text_color: Vector4<f32>) -> Style {
+        self.text_color = Some(text_color);
+        self
+    }
+
+    pub fn background(&self) -> Option<Vector4<f32>> {
+        self.background
+    }
+
+    pub fn border_color(&self) -> Option<Vector4<f32>> {
+        self.border_color
+    }
+
+    pub fn border_width(&self) -> Option<f32> {
+        self.border_width
+    }
+
+    pub fn corner_radius(&self) -> Option<f32> {
+        self.corner_radius
+    }
+
+    pub fn padding(&self) -> Option<Insets> {
+        self.padding
+    }
+
+    pub fn font(&self) -> Option<&str> {
+        self.font.as_deref()
+    }
+
+    pub fn font_size(&self) -> Option<f32> {
+        self.font_size
+    }
+
+    pub fn text_color(&self) -> Option<Vector4<f32>> {
+        self.text_color
+    }
+
+    ///Takes every property `other` sets.
+    pub fn merge(&mut self, other: &Style) {
+        self.background = other.background.or(self.background);
+        self.border_color = other.border_color.or(self.border_color);
+        self.border_width = other.border_width.or(self.border_width);
+        self.corner_radius = other.corner_radius.or(self.corner_radius);
+        self.padding = other.padding.or(self.padding);
+        self.font = other.font.clone().or(self.font.take());
+        self.font_size = other.font_size.or(self.font_size);
+        self.text_color = other.text_color.or(self.text_color);
+    }
+
+    ///Takes the inherited properties of `parent` that are not set yet.
+    pub fn inherit(&mut self, parent: &Style) {
+        if self.font.is_none() {
+            self.font = parent.font.clone();
+        }
+        self.font_size = self.font_size.or(parent.font_size);
+        self.text_color = self.text_color.or(parent.text_color);
+    }
+}
+
+///A value an element was built with and the one its computed style puts on top. Where a later stylesheet sets
+///nothing, the element falls back to what it was built with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Styled<T> {
+    own: T,
+    styled: Option<T>,
+}
+
+impl<T: Clone + PartialEq> Styled<T> {
+    pub fn new(own: T) -> Styled<T> {
+        Self { own, styled: None }
+    }
+
+    ///The styled value, or the own one without.
+    pub fn get(&self) -> &T {
+        self.styled.as_ref().unwrap_or(&self.own)
+    }
+
+    pub fn own(&self) -> &T {
+        &self.own
+    }
+
+    pub fn own_mut(&mut self) -> &mut T {
+        &mut self.own
+    }
+
+    ///Returns whether `get` changed.
+    pub fn set_own(&mut self, own: T) -> bool {
+        let before = self.get().clone();
+        self.own = own;
+        *self.get() != before
+    }
+
+    ///Puts `styled` on top of the own value, or removes what is on top for `None`. Returns whether `get` changed.
+    pub fn set_styled(&mut self, styled: Option<T>) -> bool {
+        let before = self.get().clone();
+        self.styled = styled;
+        *self.get() != before
+    }
+}
+
+impl<T: Default> Default for Styled<T> {
+    fn default() -> Self {
+        Self { own: T::default(), styled: None }
+    }
+}
+
+///States of an element a selector can require, see `PseudoState`.
+pub fn pseudo_states(ui: &UiContext, element: &dyn UIElement) -> Vec<PseudoState> {
+    let mut states = vec![];
+    if element.element_data().hover_flag {
+        states.push(PseudoState::Hover);
+    }
+    if element.is_pressed() {
+        states.push(PseudoState::Pressed);
+    }
+    if ui.is_focused(&element.id()) {
+        states.push(PseudoState::Focused);
+    }
+    if element.is_disabled() {
+        states.push(PseudoState::Disabled);
+    }
+    states
+}
+
+///Theming. Without a stylesheet elements are drawn with their `color` custom properties. With one, the properties
+///its rules set win, and the custom properties remain the fallback for what no rule sets. Geometry and fonts
+///work the same way, see `Styled`.
+impl UiContext {
+    pub fn stylesheet(&self) -> Option<&Stylesheet> {
+        self.stylesheet.as_ref()
+    }
+
+    ///Replaces the stylesheet, e.g. to switch between `Stylesheet::light` and `Stylesheet::dark`, and restyles
+    ///every element right away.
+    pub fn set_stylesheet(&mut self, stylesheet: Option<Stylesheet>) {
+        self.stylesheet = stylesheet;
+        self.restyle();
+    }
+
+    ///Computed style of a registered element, `None` without a stylesheet.
+    pub fn style_of(&self, id: &usize) -> Option<&Style> {
+        self.styles.get(id)
+    }
+
+    ///Computes the style of every element from the stylesheet, its state and its ancestors and applies it to
+    ///every element, see `UIElement::apply_style`.
+    pub fn restyle(&mut self) {
+        self.apply_styles(true);
+    }
+
+    ///Restyles the elements whose style changed, if an element was added, moved or removed, changed its name,
+    ///classes or pseudo-states since the last restyle, see `UIElementData::mark_style_dirty`. Runs every update,
+    ///so states take effect the next frame.
+    pub(crate) fn restyle_changed(&mut self) {
+        let dirty = self.styles_dirty || self.elements.values().any(|element| element.element_data().style_dirty);
+        if self.stylesheet.is_some() && dirty {
+            self.apply_styles(false);
+        }
+    }
+
+    fn apply_styles(&mut self, all: bool) {
+        let mut styles = HashMap::new();
+        if let Some(stylesheet) = &self.stylesheet {
+            for root in &self.roots {
+                self.compute_styles(stylesheet, root, None, &mut styles);
+            }
+        }
+        self.styles_dirty = false;
+        let previous = std::mem::replace(&mut self.styles, styles);
+
+        //Without a stylesheet every element goes back to its own values.
+        let unstyled = Style::new();
+        let empty = Stylesheet::new();
+        let stylesheet = self.stylesheet.as_ref().unwrap_or(&empty);
+        for (id, element) in self.elements.iter_mut() {
+            element.element_data_mut().style_dirty = false;
+            let style = self.styles.get(id).unwrap_or(&unstyled);
+            if all || previous.get(id).unwrap_or(&unstyled) != style {
+                element.element_data_mut().corner_radius.set_styled(style.corner_radius);
+                element.apply_style(style, stylesheet);
+            }
+        }
+    }
+
+    fn compute_styles(&self, stylesheet: &Stylesheet, id: &usize, parent: Option<&Style>, styles: &mut HashMap<usize, Style>) {
+        let element = match self.elements.get(id) {
+            None => return,
+            Some(element) => element
+        };
+        let style = stylesheet.compute(element.as_ref(), &pseudo_states(self, element.as_ref()), parent);
+        for child in element.children() {
+            self.compute_styles(stylesheet, child, Some(&style), styles);
+        }
+        styles.insert(*id, style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rectangle;
+    use crate::gfx::ui::container::v_box::VBox;
+    use crate::gfx::ui::tests::with_recording_context;
+
+    const RED: Vector4<f32> = [1f32, 0f32, 0f32, 1f32];
+    const BLUE: Vector4<f32> = [0f32, 0f32, 1f32, 1f32];
+
+    #[test]
+    fn updates_restyle_after_class_state_and_tree_changes_only() {
+        with_recording_context(|ui| {
+            ui.set_stylesheet(Some(Stylesheet::parse(".on { background: #ff0000; }\n.panel { text_color: #0000ff; }\nRectangle:hover { corner_radius: 3; }").unwrap()));
+            let mut panel = VBox::new();
+            panel.add_class("panel");
+            let panel = ui.register(panel).unwrap();
+            let rectangle = ui.register(Rectangle::new([0; 2], [10f32; 2])).unwrap();
+            let background = |ui: &UiContext| ui.style_of(&rectangle).unwrap().background();
+            ui.update(0f32);
+            assert!(!ui.styles_dirty);
+            assert_eq!(background(ui), None);
+
+            ui.get_element_by_id_mut(&rectangle).unwrap().add_class("on");
+            ui.update(0f32);
+            assert_eq!(background(ui), Some(RED));
+            assert!(!ui.get_element_by_id(&rectangle).unwrap().element_data().style_dirty);
+
+            //Re-adding a class it has changes nothing.
+            ui.get_element_by_id_mut(&rectangle).unwrap().add_class("on");
+            assert!(!ui.get_element_by_id(&rectangle).unwrap().element_data().style_dirty);
+
+            ui.update_cursor([5.0, 5.0]);
+            assert!(ui.styles_dirty);
+            ui.update(0f32);
+            assert_eq!(ui.style_of(&rectangle).unwrap().corner_radius(), Some(3f32));
+            assert_eq!(ui.get_element_by_id(&rectangle).unwrap().element_data().corner_radius(), 3f32);
+
+            //The cursor staying on the same element changes no state.
+            ui.update_cursor([6.0, 6.0]);
+            assert!(!ui.styles_dirty);
+
+            ui.reparent(&rectangle, Some(panel)).unwrap();
+            ui.update(0f32);
+            assert_eq!(ui.style_of(&rectangle).unwrap().text_color(), Some(BLUE));
+        });
+    }
+}
@@ -0,0 +1,169 @@
+use crate::gfx::ui::UIElement;
+
+///State of an element a selector can require, written `:hover`, `:pressed`, `:focused` or `:disabled`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoState {
+    ///The cursor is over the element.
+    Hover,
+    ///See `UIElement::is_pressed`.
+    Pressed,
+    ///The element has keyboard focus.
+    Focused,
+    ///See `UIElement::is_disabled`.
+    Disabled,
+}
+
+impl PseudoState {
+    pub fn from_name(name: &str) -> Option<PseudoState> {
+        match name {
+            "hover" => Some(PseudoState::Hover),
+            "pressed" => Some(PseudoState::Pressed),
+            "focused" => Some(PseudoState::Focused),
+            "disabled" => Some(PseudoState::Disabled),
+            _ => None
+        }
+    }
+}
+
+///Matches elements by tag, name, classes and states, like `Button.primary:hover`, `#apply` or `*`. Every part
+///given has to match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selector {
+    ///`UIElement::tag`, any tag for `None`.
+    tag: Option<String>,
+    ///`UIElement::name`.
+    name: Option<String>,
+    classes: Vec<String>,
+    states: Vec<PseudoState>,
+}
+
+impl Selector {
+    ///Matches every element.
+    pub fn any() -> Selector {
+        Default::default()
+    }
+
+    pub fn tag<T: ToString>(tag: T) -> Selector {
+        Self { tag: Some(tag.to_string()), ..Default::default() }
+    }
+
+    pub fn class<T: ToString>(class: T) -> Selector {
+        Self::any().with_class(class)
+    }
+
+    pub fn name<T: ToString>(name: T) -> Selector {
+        Self { name: Some(name.to_string()), ..Default::default() }
+    }
+
+    pub fn with_class<T: ToString>(mut self, class: T) -> Selector {
+        self.classes.push(class.to_string());
+        self
+    }
+
+    pub fn with_state(mut self, state: PseudoState) -> Selector {
+        self.states.push(state);
+        self
+    }
+
+    ///Parses a selector like `Tag.class#name:state`, with the parts after the tag in any order. `None` for
+    ///malformed selectors and unknown states.
+    pub fn parse(selector: &str) -> Option<Selector> {
+        let selector = selector.trim();
+        let is_name = |character: char| character.is_alphanumeric() || character == '_' || character == '-';
+        let mut result = Selector::any();
+        let mut rest = selector;
+        if let Some(after) = rest.strip_prefix('*') {
+            rest = after;
+        } else {
+            let end = rest.find(|character: char| !is_name(character)).unwrap_or(rest.len());
+            if end > 0 {
+                result.tag = Some(rest[..end].to_string());
+                rest = &rest[end..];
+            }
+        }
+        while let Some(prefix) = rest.chars().next() {
+            let after = &rest[prefix.len_utf8()..];
+            let end = after.find(|character: char| !is_name(character)).unwrap_or(after.len());
+            if end == 0 {
+                return None;
+            }
+            let part = &after[..end];
+            match prefix {
+                '.' => result.classes.push(part.to_string()),
+                '#' => result.name = Some(part.to_string()),
+                ':' => result.states.push(PseudoState::from_name(part)?),
+                _ => return None
+            }
+            rest = &after[end..];
+        }
+        match selector.is_empty() {
+            true => None,
+            false => Some(result)
+        }
+    }
+
+    pub fn matches(&self, element: &dyn UIElement, states: &[PseudoState]) -> bool {
+        self.tag.as_ref().map(|tag| tag == element.tag()).unwrap_or(true)
+            && self.name.as_ref().map(|name| Some(name.as_str()) == element.name()).unwrap_or(true)
+            && self.classes.iter().all(|class| element.has_class(class))
+            && self.states.iter().all(|state| states.contains(state))
+    }
+
+    ///Names, then classes and states, then tags. Rules with more specific selectors win, ties go to the later rule.
+    pub fn specificity(&self) -> (usize, usize, usize) {
+        (self.name.iter().count(), self.classes.len() + self.states.len(), self.tag.iter().count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rectangle;
+    use crate::gfx::ui::tests::with_recording_context;
+
+    #[test]
+    fn parses_tags_names_classes_and_states_in_any_order() {
+        assert_eq!(Selector::parse(" Button.primary#apply:hover "),
+                   Some(Selector { name: Some("apply".to_string()), ..Selector::tag("Button").with_class("primary").with_state(PseudoState::Hover) }));
+        assert_eq!(Selector::parse(":focused.wide.tall"),
+                   Some(Selector::class("wide").with_class("tall").with_state(PseudoState::Focused)));
+        assert_eq!(Selector::parse("*"), Some(Selector::any()));
+        assert_eq!(Selector::parse("*.slider-track"), Some(Selector::class("slider-track")));
+        assert_eq!(Selector::parse("#volume"), Some(Selector::name("volume")));
+    }
+
+    #[test]
+    fn rejects_malformed_selectors_and_unknown_states() {
+        for selector in ["", "  ", "Button extra", "Button > Label", ":unknown", "Button:", ".", "#", "Button..a", "a$"] {
+            assert_eq!(Selector::parse(selector), None, "{:?}", selector);
+        }
+    }
+
+    #[test]
+    fn matches_every_part_given() {
+        with_recording_context(|_| {
+            let mut rectangle = Rectangle::default();
+            rectangle.set_name(Some("swatch".to_string()));
+            rectangle.add_class("a");
+            rectangle.add_class("b");
+            let matches = |selector: &str, states: &[PseudoState]| Selector::parse(selector).unwrap().matches(&rectangle, states);
+            assert!(matches("*", &[]));
+            assert!(matches("Rectangle.b.a#swatch", &[]));
+            assert!(!matches("Button.a", &[]));
+            assert!(!matches(".a.c", &[]));
+            assert!(!matches("#other", &[]));
+            assert!(!matches(".a:hover", &[]));
+            assert!(matches(".a:hover", &[PseudoState::Pressed, PseudoState::Hover]));
+        });
+    }
+
+    #[test]
+    fn specificity_counts_names_then_classes_and_states_then_tags() {
+        let specificity = |selector: &str| Selector::parse(selector).unwrap().specificity();
+        assert_eq!(specificity("*"), (0, 0, 0));
+        assert_eq!(specificity("Button.primary:hover#apply"), (1, 2, 1));
+        assert!(specificity("#apply") > specificity("Button.a.b.c"));
+        assert!(specificity(".a") > specificity("Button"));
+        assert_eq!(specificity(".a"), specificity(":hover"));
+    }
+}
@@ -0,0 +1,322 @@
+use std::fs;
+use std::path::Path;
+use vecmath::Vector4;
+use crate::gfx::ui::UIElement;
+use crate::gfx::ui::markup::attributes::{parse_color, parse_insets};
+use crate::gfx::ui::style::{Style, StyleError};
+use crate::gfx::ui::style::selector::{PseudoState, Selector};
+
+///Rules assigning styles to the elements their selectors match. Where several rules set a property, the one with
+///the most specific selector wins, see `Selector::specificity`.
+///
+///Stylesheets can also be read from text:
+///
+///```css
+////* Comments like this. */
+///:root { text_color: #202020; font_size: 16; }
+///Button, .primary { background: #3366cc; corner_radius: 4; }
+///TextInput:focused { border_color: 0.25 0.55 1; border_width: 2; }
+///```
+///
+///Properties are `background`, `border_color`, `border_width`, `corner_radius`, `padding` (like the markup
+///attribute), `font`, `font_size` and `text_color`. `:root` sets what every tree inherits.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stylesheet {
+    ///Inherited by root elements as if it was the style of their parent, e.g. the text color of a theme.
+    root: Style,
+    rules: Vec<(Selector, Style)>,
+}
+
+impl Stylesheet {
+    pub fn new() -> Stylesheet {
+        Default::default()
+    }
+
+    pub fn with_root(mut self, root: Style) -> Stylesheet {
+        self.root = root;
+        self
+    }
+
+    pub fn root(&self) -> &Style {
+        &self.root
+    }
+
+    pub fn with_rule(mut self, selector: Selector, style: Style) -> Stylesheet {
+        self.add_rule(selector, style);
+        self
+    }
+
+    pub fn add_rule(&mut self, selector: Selector, style: Style) {
+        self.rules.push((selector, style));
+    }
+
+    pub fn rules(&self) -> &Vec<(Selector, Style)> {
+        &self.rules
+    }
+
+    ///Appends the rules of `other`, which win ties against the existing ones, and merges its root style.
+    pub fn extend(&mut self, other: Stylesheet) {
+        self.root.merge(&other.root);
+        self.rules.extend(other.rules);
+    }
+
+    ///Style of `element` in `states`, inheriting from the computed style of its parent, or the root style.
+    pub fn compute(&self, element: &dyn UIElement, states: &[PseudoState], parent: Option<&Style>) -> Style {
+        let mut matching: Vec<&(Selector, Style)> = self.rules.iter()
+            .filter(|(selector, _)| selector.matches(element, states))
+            .collect();
+        //Stable, so later rules stay behind earlier ones of the same specificity.
+        matching.sort_by_key(|(selector, _)| selector.specificity());
+        let mut style = Style::new();
+        for (_, rule) in matching {
+            style.merge(rule);
+        }
+        style.inherit(parent.unwrap_or(&self.root));
+        style
+    }
+
+    pub fn parse(source: &str) -> Result<Stylesheet, StyleError> {
+        let mut stylesheet = Stylesheet::new();
+        let mut rest = strip_comments(source)?;
+        loop {
+            let start = rest.len() - rest.trim_start().len();
+            rest.drain(..start);
+            if rest.is_empty() {
+                return Ok(stylesheet);
+            }
+            let line = line_of(source, &rest);
+            let open = rest.find('{').ok_or(StyleError::Syntax(line, "Expected '{'".to_string()))?;
+            let close = rest.find('}').ok_or(StyleError::Syntax(line, "Missing '}'".to_string()))?;
+            if close < open {
+                return Err(StyleError::Syntax(line, "Unexpected '}'".to_string()));
+            }
+            let style = parse_declarations(&rest[open + 1..close], line)?;
+            for selector in rest[..open].split(',') {
+                if selector.trim() == ":root" {
+                    stylesheet.root.merge(&style);
+                    continue;
+                }
+                let selector = Selector::parse(selector)
+                    .ok_or(StyleError::Syntax(line, format!("Invalid selector '{}'", selector.trim())))?;
+                stylesheet.add_rule(selector, style.clone());
+            }
+            rest.drain(..close + 1);
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Stylesheet, StyleError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|error| StyleError::Io(format!("{}: {}", path.display(), error)))?;
+        Self::parse(&source)
+    }
+
+    ///Dark text on light gray widgets with a blue accent.
+    pub fn light() -> Stylesheet {
+        Self::theme(Palette {
+            text: [0.1, 0.1, 0.1, 1.0],
+            muted_text: [0.55, 0.55, 0.55, 1.0],
+            surface: [0.95, 0.95, 0.95, 1.0],
+            control: [0.85, 0.85, 0.85, 1.0],
+            control_hover: [0.78, 0.78, 0.78, 1.0],
+            control_pressed: [0.68, 0.68, 0.68, 1.0],
+            control_disabled: [0.92, 0.92, 0.92, 1.0],
+            frame: [0.6, 0.6, 0.6, 1.0],
+            mark: [0.2, 0.2, 0.2, 1.0],
+            knob: [1.0, 1.0, 1.0, 1.0],
+            accent: [0.25, 0.55, 0.9, 1.0],
+        })
+    }
+
+    ///Light text on dark gray widgets with a blue accent.
+    pub fn dark() -> Stylesheet {
+        Self::theme(Palette {
+            text: [0.92, 0.92, 0.92, 1.0],
+            muted_text: [0.5, 0.5, 0.5, 1.0],
+            surface: [0.16, 0.16, 0.17, 1.0],
+            control: [0.26, 0.26, 0.28, 1.0],
+            control_hover: [0.32, 0.32, 0.35, 1.0],
+            control_pressed: [0.2, 0.2, 0.22, 1.0],
+            control_disabled: [0.2, 0.2, 0.2, 1.0],
+            frame: [0.45, 0.45, 0.48, 1.0],
+            mark: [0.9, 0.9, 0.9, 1.0],
+            knob: [0.85, 0.85, 0.85, 1.0],
+            accent: [0.3, 0.6, 1.0, 1.0],
+        })
+    }
+
+    ///Rules for the widgets of this crate, addressing their parts by class.
+    fn theme(palette: Palette) -> Stylesheet {
+        let button = Selector::tag("Button");
+        Stylesheet::new()
+            .with_root(Style::new().with_text_color(palette.text))
+            .with_rule(button.clone(), Style::new().with_background(palette.control).with_corner_radius(4f32))
+            .with_rule(button.clone().with_state(PseudoState::Hover), Style::new().with_background(palette.control_hover))
+            .with_rule(button.clone().with_state(PseudoState::Pressed), Style::new().with_background(palette.control_pressed))
+            .with_rule(button.clone().with_state(PseudoState::Disabled), Style::new().with_background(palette.control_disabled).with_text_color(palette.muted_text))
+            .with_rule(Selector::class("checkbox-frame"), Style::new().with_background(palette.frame))
+            .with_rule(Selector::class("checkbox-mark"), Style::new().with_background(palette.mark))
+            .with_rule(Selector::class("radio-ring"), Style::new().with_background(palette.frame))
+            .with_rule(Selector::class("radio-ring").with_class("highlighted"), Style::new().with_background(palette.accent))
+            .with_rule(Selector::class("radio-dot"), Style::new().with_background(palette.mark))
+            .with_rule(Selector::class("toggle-track"), Style::new().with_background(palette.frame))
+            .with_rule(Selector::class("toggle-track").with_class("on"), Style::new().with_background(palette.accent))
+            .with_rule(Selector::class("toggle-knob"), Style::new().with_background(palette.knob))
+            .with_rule(Selector::class("slider-track"), Style::new().with_background(palette.frame))
+            .with_rule(Selector::class("slider-handle"), Style::new().with_background(palette.mark))
+            .with_rule(Selector::class("scrollbar-track"), Style::new().with_background(palette.control))
+            .with_rule(Selector::class("scrollbar-handle"), Style::new().with_background(palette.frame))
+            .with_rule(Selector::tag("TextInput"), Style::new().with_background(palette.surface).with_border(1f32, palette.frame))
+            .with_rule(Selector::tag("TextInput").with_state(PseudoState::Focused), Style::new().with_border_color(palette.accent))
+    }
+}
+
+///Colors the built-in themes are made of.
+struct Palette {
+    text: Vector4<f32>,
+    muted_text: Vector4<f32>,
+    ///Background of text inputs.
+    surface: Vector4<f32>,
+    control: Vector4<f32>,
+    control_hover: Vector4<f32>,
+    control_pressed: Vector4<f32>,
+    control_disabled: Vector4<f32>,
+    ///Frames of checkboxes and radio buttons, tracks of toggles and sliders.
+    frame: Vector4<f32>,
+    ///Check marks, radio dots and slider handles.
+    mark: Vector4<f32>,
+    knob: Vector4<f32>,
+    accent: Vector4<f32>,
+}
+
+///Replaces comments by as many bytes of spaces, keeping line breaks, so offsets and lines match `source`.
+fn strip_comments(source: &str) -> Result<String, StyleError> {
+    let mut result = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        let end = rest[start..].find("*/")
+            .ok_or(StyleError::Syntax(line_of(source, &rest[start..]), "Unterminated comment".to_string()))?;
+        let comment = &rest[start..start + end + 2];
+        for character in comment.chars() {
+            match character {
+                '\n' => result.push('\n'),
+                _ => result.push_str(&" ".repeat(character.len_utf8()))
+            }
+        }
+        rest = &rest[start + end + 2..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+///Line, starting at 1, where `rest`, a suffix of `source` or of its copy without comments, begins.
+fn line_of(source: &str, rest: &str) -> usize {
+    let offset = source.len().saturating_sub(rest.len());
+    source.as_bytes()[..offset].iter().filter(|byte| **byte == b'\n').count() + 1
+}
+
+fn parse_declarations(declarations: &str, line: usize) -> Result<Style, StyleError> {
+    let mut style = Style::new();
+    for declaration in declarations.split(';').map(|declaration| declaration.trim()).filter(|declaration| !declaration.is_empty()) {
+        let (name, value) = declaration.split_once(':')
+            .ok_or(StyleError::Syntax(line, format!("Expected 'property: value', found '{}'", declaration)))?;
+        let (name, value) = (name.trim(), value.trim());
+        let invalid = || StyleError::InvalidValue(line, name.to_string(), value.to_string());
+        let number = || value.parse::<f32>().map_err(|_| invalid());
+        let color = || parse_color(value).ok_or_else(invalid);
+        match name {
+            "background" => style.background = Some(color()?),
+            "border_color" => style.border_color = Some(color()?),
+            "border_width" => style.border_width = Some(number()?),
+            "corner_radius" => style.corner_radius = Some(number()?),
+            "padding" => style.padding = Some(parse_insets(value).ok_or_else(invalid)?),
+            "font" => style.font = Some(value.trim_matches(|character| character == '"' || character == '\'').to_string()),
+            "font_size" => style.font_size = Some(number()?),
+            "text_color" => style.text_color = Some(color()?),
+            _ => return Err(StyleError::UnknownProperty(line, name.to_string()))
+        }
+    }
+    Ok(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rectangle;
+    use crate::gfx::ui::tests::with_recording_context;
+
+    const RED: Vector4<f32> = [1f32, 0f32, 0f32, 1f32];
+    const GREEN: Vector4<f32> = [0f32, 1f32, 0f32, 1f32];
+    const BLUE: Vector4<f32> = [0f32, 0f32, 1f32, 1f32];
+
+    fn rectangle(classes: &[&str]) -> Rectangle {
+        let mut rectangle = Rectangle::default();
+        for class in classes {
+            rectangle.add_class(class);
+        }
+        rectangle
+    }
+
+    #[test]
+    fn more_specific_rules_win_and_ties_go_to_the_later_rule() {
+        with_recording_context(|_| {
+            let stylesheet = Stylesheet::parse(".a { background: #0000ff; corner_radius: 2; }\nRectangle { background: #ff0000; border_width: 3; }\n.b { background: #00ff00; }").unwrap();
+            let style = stylesheet.compute(&rectangle(&["a"]), &[], None);
+            assert_eq!(style.background(), Some(BLUE));
+            assert_eq!(style.corner_radius(), Some(2f32));
+            assert_eq!(style.border_width(), Some(3f32));
+            assert_eq!(stylesheet.compute(&rectangle(&["a", "b"]), &[], None).background(), Some(GREEN));
+            assert_eq!(stylesheet.compute(&rectangle(&["b", "a"]), &[], None).background(), Some(GREEN));
+            assert_eq!(stylesheet.compute(&rectangle(&[]), &[], None).background(), Some(RED));
+
+            let mut hovered = stylesheet.clone();
+            hovered.extend(Stylesheet::parse("Rectangle:hover { background: #0000ff; }").unwrap());
+            assert_eq!(hovered.compute(&rectangle(&[]), &[PseudoState::Hover], None).background(), Some(BLUE));
+            assert_eq!(hovered.compute(&rectangle(&[]), &[], None).background(), Some(RED));
+        });
+    }
+
+    #[test]
+    fn root_styles_are_inherited_by_roots_only_when_there_is_no_parent_style() {
+        with_recording_context(|_| {
+            let stylesheet = Stylesheet::parse(":root { text_color: #ff0000; font_size: 12; background: #00ff00; }\n.big { font_size: 20; }").unwrap();
+            assert_eq!(stylesheet.root().text_color(), Some(RED));
+            assert_eq!(stylesheet.rules().len(), 1);
+
+            let root = stylesheet.compute(&rectangle(&["big"]), &[], None);
+            assert_eq!(root.text_color(), Some(RED));
+            assert_eq!(root.font_size(), Some(20f32));
+            //Backgrounds are not inherited.
+            assert_eq!(root.background(), None);
+
+            let parent = Style::new().with_text_color(BLUE);
+            let child = stylesheet.compute(&rectangle(&[]), &[], Some(&parent));
+            assert_eq!(child.text_color(), Some(BLUE));
+            assert_eq!(child.font_size(), None);
+        });
+    }
+
+    #[test]
+    fn comments_are_skipped_without_shifting_lines() {
+        let source = "/* Theme\n   colors */ .a { /* inline */ background: #ff0000; }\n/* .b { background: nothing; } */\n.c { text_color: 0 0 1; }";
+        let stylesheet = Stylesheet::parse(source).unwrap();
+        assert_eq!(stylesheet.rules(), &vec![
+            (Selector::class("a"), Style::new().with_background(RED)),
+            (Selector::class("c"), Style::new().with_text_color(BLUE)),
+        ]);
+        assert_eq!(Stylesheet::parse("/* a\n*/\n.a { colour: #ff0000; }").unwrap_err(), StyleError::UnknownProperty(3, "colour".to_string()));
+    }
+
+    #[test]
+    fn errors_name_the_line_of_the_rule() {
+        let error = |source: &str| Stylesheet::parse(source).unwrap_err();
+        assert_eq!(error(".a {}\n\n.b { background: red; }"), StyleError::InvalidValue(3, "background".to_string(), "red".to_string()));
+        assert_eq!(error(".a {}\nButton extra { corner_radius: 1; }"), StyleError::Syntax(2, "Invalid selector 'Button extra'".to_string()));
+        assert_eq!(error(".a, :unknown { corner_radius: 1; }"), StyleError::Syntax(1, "Invalid selector ':unknown'".to_string()));
+        assert_eq!(error(".a {}\n.b { corner_radius }"), StyleError::Syntax(2, "Expected 'property: value', found 'corner_radius'".to_string()));
+        assert_eq!(error(".a {}\n.b"), StyleError::Syntax(2, "Expected '{'".to_string()));
+        assert_eq!(error(".a {}\n.b { corner_radius: 1;"), StyleError::Syntax(2, "Missing '}'".to_string()));
+        assert_eq!(error(".a {}\n}\n.b {}"), StyleError::Syntax(2, "Unexpected '}'".to_string()));
+        assert_eq!(error(".a {}\n/* open\n.b {}"), StyleError::Syntax(2, "Unterminated comment".to_string()));
+    }
+}
@@ -0,0 +1,399 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::gfx::ui::{UiContext, UIElement};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UiTreeError {
+    ///The element is not registered, or is currently taken out by `UiContext::with_element`, which only
+    ///`UiContext::remove` accepts.
+    UnknownElement(usize),
+    DuplicateId(usize),
+    ///Moving the first element below the second would make it its own ancestor.
+    Cycle(usize, usize),
+    ///The second element is not a child of the first.
+    NotAChild(usize, usize),
+    ///The element does not take keyboard focus, see `UIElement::focusable`.
+    NotFocusable(usize),
+}
+
+impl Display for UiTreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UiTreeError::UnknownElement(id) => f.write_fmt(format_args!("No element with id {} is registered", id)),
+            UiTreeError::DuplicateId(id) => f.write_fmt(format_args!("An element with id {} is already registered", id)),
+            UiTreeError::Cycle(id, parent) => f.write_fmt(format_args!("Cannot move element {} below its descendant {}", id, parent)),
+            UiTreeError::NotAChild(parent, child) => f.write_fmt(format_args!("Element {} is not a child of {}", child, parent)),
+            UiTreeError::NotFocusable(id) => f.write_fmt(format_args!("Element {} cannot be focused", id)),
+        }
+    }
+}
+
+impl Error for UiTreeError {}
+
+///Tree mutation. Every operation keeps `parent` and `children` of the involved elements consistent and
+///notifies the moved elements through `UIElement::on_detach` and `UIElement::on_attach`.
+impl UiContext {
+    ///Registers `element` with its child buffer as the last child of `parent`.
+    pub fn add_child<E: UIElement + 'static>(&mut self, parent: &usize, element: E) -> Result<usize, UiTreeError> {
+        self.attach(Box::new(element), Some(*parent), None)
+    }
+
+    ///Registers `element` with its child buffer as child number `index` of `parent`.
+    pub fn insert_child<E: UIElement + 'static>(&mut self, parent: &usize, index: usize, element: E) -> Result<usize, UiTreeError> {
+        self.attach(Box::new(element), Some(*parent), Some(index))
+    }
+
+    pub(crate) fn attach(&mut self, element: Box<dyn UIElement>, parent: Option<usize>, index: Option<usize>) -> Result<usize, UiTreeError> {
+        if let Some(parent) = parent {
+            if !self.contains(&parent) {
+                return Err(UiTreeError::UnknownElement(parent));
+            }
+        }
+        //Check the whole subtree up front, so a duplicate does not leave it half registered.
+        let mut ids = vec![];
+        collect_pending_ids(&element, &mut ids);
+        for (i, id) in ids.iter().enumerate() {
+            if self.contains(id) || ids[..i].contains(id) {
+                return Err(UiTreeError::DuplicateId(*id));
+            }
+        }
+
+        let id = element.id();
+        let mut attached = vec![];
+        self.insert_subtree(element, parent, &mut attached);
+        let siblings = self.siblings_mut(parent);
+        let index = index.unwrap_or(siblings.len()).min(siblings.len());
+        siblings.insert(index, id);
+
+        for element in attached {
+            self.notify_attach(&element);
+        }
+        Ok(id)
+    }
+
+    fn insert_subtree(&mut self, mut element: Box<dyn UIElement>, parent: Option<usize>, attached: &mut Vec<usize>) {
+        let id = element.id();
+        let children: Vec<Box<dyn UIElement>> = element.child_buffer().drain(..).collect();
+        element.element_data_mut().parent = parent;
+        for child in &children {
+            element.register_child(child);
+        }
+        self.elements.insert(id, element);
+        attached.push(id);
+
+        for child in children {
+            self.insert_subtree(child, Some(id), attached);
+        }
+    }
+
+    ///Removes the element and its descendants and drops them, which frees their vertex arrays. An element taken
+    ///out by `UiContext::with_element` is unlinked right away and dropped once it is handed back.
+    pub fn remove(&mut self, id: &usize) -> Result<(), UiTreeError> {
+        if let Some((_, parent)) = self.taken.iter().find(|(taken, _)| taken == id).cloned() {
+            //A parent that is taken out as well drops the id when `with_element` hands it back.
+            if parent.map(|parent| self.contains(&parent)).unwrap_or(true) {
+                self.siblings_mut(parent).retain(|sibling| sibling != id);
+                self.notify_child_detach(parent, id);
+            }
+            if !self.dropped.contains(id) {
+                self.dropped.push(*id);
+            }
+            return Ok(());
+        }
+        self.take(id).map(|_| ())
+    }
+
+    ///Like `remove`, but only if `child` is a direct child of `parent`, e.g. to take an entry out of a `VBox`.
+    pub fn remove_child(&mut self, parent: &usize, child: &usize) -> Result<(), UiTreeError> {
+        if !self.children_of(parent)?.contains(child) {
+            return Err(UiTreeError::NotAChild(*parent, *child));
+        }
+        self.remove(child)
+    }
+
+    ///Unregisters the element and its descendants and hands them back, with the descendants in the child buffers,
+    ///so the subtree can be registered again, in this or another context.
+    pub fn take(&mut self, id: &usize) -> Result<Box<dyn UIElement>, UiTreeError> {
+        let parent = self.parent_of(id)?;
+
+        let mut subtree = vec![];
+        self.collect_subtree(id, &mut subtree);
+        if self.focused.map(|focused| subtree.contains(&focused)).unwrap_or(false) {
+            self.blur();
+        }
+        self.forget_pointer(&subtree, parent);
+        for element in &subtree {
+            self.notify_detach(element);
+        }
+
+        self.siblings_mut(parent).retain(|child| child != id);
+        self.notify_child_detach(parent, id);
+        Ok(self.take_subtree(id).unwrap())
+    }
+
+    fn take_subtree(&mut self, id: &usize) -> Option<Box<dyn UIElement>> {
+        let mut element = match self.elements.remove(id) {
+            Some(element) => element,
+            None => {
+                //Below a removed element, so `with_element` must not put it back.
+                if self.taken.iter().any(|(taken, _)| taken == id) && !self.dropped.contains(id) {
+                    self.dropped.push(*id);
+                }
+                return None;
+            }
+        };
+        self.layout.remove(id);
+        self.layout_sizes.remove(id);
+        element.element_data_mut().parent = None;
+        let children = std::mem::take(&mut element.element_data_mut().children);
+        for child in children {
+            if let Some(child) = self.take_subtree(&child) {
+                element.add_child(child);
+            }
+        }
+        Some(element)
+    }
+
+    ///Drops an element that was removed while `with_element` had it taken out, with its descendants, which are
+    ///still registered. Its parent is already gone, so it is detached as if it was a root.
+    pub(crate) fn discard(&mut self, mut element: Box<dyn UIElement>) {
+        let id = element.id();
+        element.element_data_mut().parent = None;
+        self.elements.insert(id, element);
+
+        let mut subtree = vec![];
+        self.collect_subtree(&id, &mut subtree);
+        if self.focused.map(|focused| subtree.contains(&focused)).unwrap_or(false) {
+            self.blur();
+        }
+        self.forget_pointer(&subtree, None);
+        for element in &subtree {
+            self.notify_detach(element);
+        }
+        self.roots.retain(|root| *root != id);
+        self.take_subtree(&id);
+    }
+
+    ///Moves the element with its descendants to the end of the children of `parent`, or to the last root when `None`.
+    pub fn reparent(&mut self, id: &usize, parent: Option<usize>) -> Result<(), UiTreeError> {
+        self.reparent_at(id, parent, usize::MAX)
+    }
+
+    ///Moves the element with its descendants to child number `index` of `parent`, or to root number `index` when `None`.
+    pub fn reparent_at(&mut self, id: &usize, parent: Option<usize>, index: usize) -> Result<(), UiTreeError> {
+        let old_parent = self.parent_of(id)?;
+        if let Some(parent) = parent {
+            //Walk up from the new parent. Meeting the element means it would end up below itself.
+            let mut ancestor = Some(parent);
+            while let Some(current) = ancestor {
+                if current == *id {
+                    return Err(UiTreeError::Cycle(*id, parent));
+                }
+                ancestor = self.parent_of(&current)?;
+            }
+        }
+        if old_parent == parent {
+            let siblings = self.siblings_mut(parent);
+            siblings.retain(|sibling| sibling != id);
+            siblings.insert(index.min(siblings.len()), *id);
+            return Ok(());
+        }
+
+        self.notify_detach(id);
+        self.siblings_mut(old_parent).retain(|sibling| sibling != id);
+        self.notify_child_detach(old_parent, id);
+        self.elements.get_mut(id).unwrap().element_data_mut().parent = parent;
+        let siblings = self.siblings_mut(parent);
+        siblings.insert(index.min(siblings.len()), *id);
+        self.notify_attach(id);
+        Ok(())
+    }
+
+    ///Reorders `child` among its siblings. Indices past the end move it to the end.
+    pub fn move_child(&mut self, parent: &usize, child: &usize, index: usize) -> Result<(), UiTreeError> {
+        let children = &mut self.elements.get_mut(parent).ok_or(UiTreeError::UnknownElement(*parent))?.element_data_mut().children;
+        let current = children.iter().position(|id| id == child).ok_or(UiTreeError::NotAChild(*parent, *child))?;
+        children.remove(current);
+        children.insert(index.min(children.len()), *child);
+        Ok(())
+    }
+
+    ///The children of `parent`, or the roots. `parent` has to be registered.
+    fn siblings_mut(&mut self, parent: Option<usize>) -> &mut Vec<usize> {
+        match parent {
+            None => &mut self.roots,
+            Some(parent) => &mut self.elements.get_mut(&parent).unwrap().element_data_mut().children
+        }
+    }
+
+    ///Child ids of `parent` in order.
+    pub fn children_of(&self, parent: &usize) -> Result<Vec<usize>, UiTreeError> {
+        match self.get_element_by_id(parent) {
+            None => Err(UiTreeError::UnknownElement(*parent)),
+            Some(parent) => Ok(parent.children().clone())
+        }
+    }
+
+    ///Errors if the element or its parent is not available.
+    fn parent_of(&self, id: &usize) -> Result<Option<usize>, UiTreeError> {
+        let parent = self.get_element_by_id(id).ok_or(UiTreeError::UnknownElement(*id))?.parent();
+        if let Some(parent) = parent {
+            if !self.contains(&parent) {
+                return Err(UiTreeError::UnknownElement(parent));
+            }
+        }
+        Ok(parent)
+    }
+
+    fn collect_subtree(&self, id: &usize, ids: &mut Vec<usize>) {
+        ids.push(*id);
+        if let Some(element) = self.get_element_by_id(id) {
+            for child in element.children() {
+                self.collect_subtree(child, ids);
+            }
+        }
+    }
+
+    fn notify_attach(&mut self, id: &usize) {
+        self.styles_dirty = true;
+        self.with_element(id, |element, ui| {
+            let parent = element.parent();
+            element.on_attach(ui, parent);
+        });
+    }
+
+    fn notify_detach(&mut self, id: &usize) {
+        self.styles_dirty = true;
+        self.with_element(id, |element, ui| {
+            let parent = element.parent();
+            element.on_detach(ui, parent);
+        });
+    }
+
+    ///Tells `parent`, if any, that `child` left it.
+    pub(crate) fn notify_child_detach(&mut self, parent: Option<usize>, child: &usize) {
+        if let Some(parent) = parent {
+            self.with_element(&parent, |parent, ui| parent.on_child_detach(ui, *child));
+        }
+    }
+}
+
+fn collect_pending_ids(element: &Box<dyn UIElement>, ids: &mut Vec<usize>) {
+    ids.push(element.id());
+    for child in element.element_data().tmp_children() {
+        collect_pending_ids(child, ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rectangle;
+    use crate::gfx::ui::container::v_box::VBox;
+    use crate::gfx::ui::tests::with_recording_context;
+
+    ///Registers a `VBox` with `count` rectangles and returns its id and theirs.
+    fn register_box(ui: &mut UiContext, count: usize) -> (usize, Vec<usize>) {
+        let children: Vec<Rectangle> = (0..count).map(|_| Rectangle::default()).collect();
+        let ids = children.iter().map(|child| child.id()).collect();
+        (ui.register(VBox::new().with_children(children)).unwrap(), ids)
+    }
+
+    #[test]
+    fn attach_rejects_duplicates_without_registering_part_of_the_subtree() {
+        with_recording_context(|ui| {
+            let existing = ui.register(Rectangle::default()).unwrap();
+            let fresh = Rectangle::default();
+            let fresh_id = fresh.id();
+            let mut duplicate = Rectangle::default();
+            duplicate.element_data_mut().id = existing;
+            let vbox = VBox::new().with_child(fresh).with_child(duplicate);
+            let vbox_id = vbox.id();
+
+            assert_eq!(ui.register(vbox).unwrap_err(), UiTreeError::DuplicateId(existing));
+            assert!(!ui.contains(&vbox_id));
+            assert!(!ui.contains(&fresh_id));
+            assert_eq!(ui.element_ids(), vec![existing]);
+            assert_eq!(ui.roots(), &vec![existing]);
+            assert_eq!(ui.add_child(&usize::MAX, Rectangle::default()).unwrap_err(), UiTreeError::UnknownElement(usize::MAX));
+        });
+    }
+
+    #[test]
+    fn take_hands_back_a_subtree_that_registers_again() {
+        with_recording_context(|ui| {
+            let (vbox, children) = register_box(ui, 3);
+
+            let taken = ui.take(&vbox).unwrap();
+            assert!(ui.element_ids().is_empty());
+            assert!(ui.roots().is_empty());
+            assert_eq!(taken.element_data().tmp_children().len(), 3);
+
+            assert_eq!(ui.register_boxed(taken).unwrap(), vbox);
+            assert_eq!(ui.children_of(&vbox).unwrap(), children);
+            assert_eq!(ui.get_element_by_id(&children[0]).unwrap().parent(), Some(vbox));
+
+            ui.remove(&children[1]).unwrap();
+            assert!(!ui.contains(&children[1]));
+            assert_eq!(ui.children_of(&vbox).unwrap(), vec![children[0], children[2]]);
+            assert_eq!(ui.remove_child(&children[0], &children[2]).unwrap_err(), UiTreeError::NotAChild(children[0], children[2]));
+            assert_eq!(ui.remove(&children[1]).unwrap_err(), UiTreeError::UnknownElement(children[1]));
+        });
+    }
+
+    #[test]
+    fn reparent_at_rejects_moving_an_element_below_itself() {
+        with_recording_context(|ui| {
+            let (outer, _) = register_box(ui, 0);
+            let (inner, children) = register_box(ui, 1);
+            ui.reparent(&inner, Some(outer)).unwrap();
+
+            assert_eq!(ui.reparent(&outer, Some(children[0])).unwrap_err(), UiTreeError::Cycle(outer, children[0]));
+            assert_eq!(ui.reparent(&outer, Some(outer)).unwrap_err(), UiTreeError::Cycle(outer, outer));
+            assert_eq!(ui.roots(), &vec![outer]);
+            assert_eq!(ui.get_element_by_id(&inner).unwrap().parent(), Some(outer));
+
+            ui.reparent_at(&children[0], None, 0).unwrap();
+            assert_eq!(ui.roots(), &vec![children[0], outer]);
+            assert!(ui.children_of(&inner).unwrap().is_empty());
+            assert_eq!(ui.get_element_by_id(&children[0]).unwrap().parent(), None);
+        });
+    }
+
+    #[test]
+    fn move_child_clamps_the_index() {
+        with_recording_context(|ui| {
+            let (vbox, children) = register_box(ui, 3);
+
+            ui.move_child(&vbox, &children[0], 10).unwrap();
+            assert_eq!(ui.children_of(&vbox).unwrap(), vec![children[1], children[2], children[0]]);
+            ui.move_child(&vbox, &children[0], 0).unwrap();
+            assert_eq!(ui.children_of(&vbox).unwrap(), children);
+            assert_eq!(ui.move_child(&vbox, &vbox, 0).unwrap_err(), UiTreeError::NotAChild(vbox, vbox));
+        });
+    }
+
+    #[test]
+    fn removal_inside_with_element_drops_the_element_when_it_is_handed_back() {
+        with_recording_context(|ui| {
+            let (vbox, children) = register_box(ui, 3);
+
+            ui.with_element(&children[0], |_, ui| {
+                ui.remove(&children[0]).unwrap();
+                assert_eq!(ui.children_of(&vbox).unwrap(), vec![children[1], children[2]]);
+            });
+            assert!(!ui.contains(&children[0]));
+
+            //The parent is taken out as well, so the child is unlinked when the parent is handed back.
+            ui.with_element(&vbox, |_, ui| {
+                ui.with_element(&children[1], |_, ui| ui.remove(&children[1]).unwrap());
+            });
+            assert!(!ui.contains(&children[1]));
+            assert_eq!(ui.children_of(&vbox).unwrap(), vec![children[2]]);
+
+            ui.with_element(&children[2], |_, ui| ui.remove(&vbox).unwrap());
+            assert!(ui.element_ids().is_empty());
+            assert!(ui.roots().is_empty());
+        });
+    }
+}
@@ -219,4 +219,19 @@ impl GlfwPlatform {
             }
         }
     }
-}
\ No newline at end of file
+}
+///Creates a hidden window and makes its GL 3.3 core context current, for rendering without anything on screen,
+///e.g. golden image tests on CI with a software driver.
+pub fn headless_context(size: [u32; 2]) -> Result<(glfw::Glfw, Window), String> {
+    use glfw::Context;
+    let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).map_err(|error| format!("{:?}", error))?;
+    glfw.window_hint(glfw::WindowHint::Visible(false));
+    glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
+    glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
+
+    let (mut window, _events) = glfw.create_window(size[0], size[1], "headless", glfw::WindowMode::Windowed)
+        .ok_or_else(|| "Failed to create a hidden window.".to_string())?;
+    window.make_current();
+    gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+    Ok((glfw, window))
+}
@@ -2,7 +2,7 @@ use std::ops::Index;
 use crate::math::color::Color;
 use crate::math::color::rgba::RGBA;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct RGB(pub [u8; 3]);
 
 impl Index<usize> for RGB {
@@ -2,7 +2,7 @@ use std::ops::Index;
 use crate::math::color::Color;
 use crate::math::color::rgb::RGB;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct RGBA(pub [u8; 4]);
 
 impl Index<usize> for RGBA {
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+///Detects created, modified and deleted files by polling their modification times. Works the same on every
+///platform and with editors that save by replacing the file.
+#[derive(Debug, Clone, Default)]
+pub struct FileWatcher {
+    ///Last seen modification time of every known file, `None` while it does not exist.
+    files: HashMap<PathBuf, Option<SystemTime>>,
+    ///Directories, searched recursively, with the extension of the files to watch in them, `None` for all files.
+    directories: Vec<(PathBuf, Option<String>)>,
+}
+
+impl FileWatcher {
+    pub fn new() -> FileWatcher {
+        Self { files: HashMap::new(), directories: vec![] }
+    }
+
+    pub fn with_file<P: AsRef<Path>>(mut self, path: P) -> FileWatcher {
+        self.watch(path);
+        self
+    }
+
+    pub fn with_directory<P: AsRef<Path>>(mut self, directory: P, extension: Option<&str>) -> FileWatcher {
+        self.watch_directory(directory, extension);
+        self
+    }
+
+    ///Watches a single file, which does not have to exist yet.
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) {
+        let path = path.as_ref().to_path_buf();
+        let modified = modified(&path);
+        self.files.entry(path).or_insert(modified);
+    }
+
+    ///Watches the files in `directory` and its subdirectories with the given extension, e.g. `glsl`, including
+    ///files created later.
+    pub fn watch_directory<P: AsRef<Path>>(&mut self, directory: P, extension: Option<&str>) {
+        let directory = (directory.as_ref().to_path_buf(), extension.map(|extension| extension.to_string()));
+        let mut found = vec![];
+        collect_files(&directory.0, directory.1.as_deref(), &mut found);
+        for path in found {
+            self.watch(path);
+        }
+        if !self.directories.contains(&directory) {
+            self.directories.push(directory);
+        }
+    }
+
+    pub fn unwatch<P: AsRef<Path>>(&mut self, path: P) {
+        let path = path.as_ref();
+        self.files.remove(path);
+        self.directories.retain(|(directory, _)| directory != path);
+    }
+
+    ///Files currently known, existing or not.
+    pub fn files(&self) -> Vec<&Path> {
+        self.files.keys().map(|path| path.as_path()).collect()
+    }
+
+    ///Files that were created, modified or deleted since the last call, sorted. Polling once per frame is fine
+    ///for a few dozen files.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut found = vec![];
+        for (directory, extension) in &self.directories {
+            collect_files(directory, extension.as_deref(), &mut found);
+        }
+        let mut changed = vec![];
+        for path in found {
+            if !self.files.contains_key(&path) {
+                self.files.insert(path.clone(), modified(&path));
+                changed.push(path);
+            }
+        }
+        for (path, last) in self.files.iter_mut() {
+            let current = modified(path);
+            if current != *last {
+                *last = current;
+                if !changed.contains(path) {
+                    changed.push(path.clone());
+                }
+            }
+        }
+        changed.sort();
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+///Unreadable directories are skipped, they are searched again on the next poll.
+fn collect_files(directory: &Path, extension: Option<&str>, found: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(directory) {
+        Err(_) => return,
+        Ok(entries) => entries
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, extension, found);
+        } else if extension.map(|extension| path.extension().map(|own| own == extension).unwrap_or(false)).unwrap_or(true) {
+            found.push(path);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod constructor;
+pub mod file_watcher;
 
 pub fn flatten<T: Copy>(values: Vec<&[T]>) -> Vec<T> {
     let mut output = vec![];